@@ -5,17 +5,7 @@ type Cmd  = (char, Rect);
 
 fn main()
 {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    let result = part_one(input);
-    println!("Part 1: {} ({:?})", result, t.elapsed());
-
-    let t = Instant::now();
-    let result = part_two(input);
-    println!("Part 2: {} ({:?})", result, t.elapsed());
+    aoc::run((part_one, part_two), include_str!("../input.txt"));
 }
 
 fn part_one(input: &str) -> i32
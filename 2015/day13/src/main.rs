@@ -1,19 +1,7 @@
 use std::collections::HashMap;
 
 fn main() {
-    use std::time::Instant;
-
-    let happiness = load(include_str!("./input.txt"));
-
-    let t1 = Instant::now();
-    let delta = part_one(&happiness);
-    let t2 = Instant::now();
-    println!("Part 1: {} ({:?})", delta, t2 - t1);
-
-    let t1 = Instant::now();
-    let delta = part_two(&happiness);
-    let t2 = Instant::now();
-    println!("Part 2: {} ({:?})", delta, t2 - t1);
+    aoc::run((part_one, part_two), &load(include_str!("./input.txt")));
 }
 
 fn load(input: &str) -> HashMap<(&str, &str), i32> {
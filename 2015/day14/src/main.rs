@@ -1,17 +1,5 @@
 fn main() {
-    use std::time::Instant;
-
-    let reindeer = load(include_str!("./input.txt"));
-
-    let t1 = Instant::now();
-    let dist = part_one(&reindeer);
-    let t2 = Instant::now();
-    println!("Part 1: {} ({:?})", dist, t2 - t1);
-
-    let t1 = Instant::now();
-    let winner = part_two(&reindeer);
-    let t2 = Instant::now();
-    println!("Part 2: {} ({:?})", winner, t2 - t1);
+    aoc::run((part_one, part_two), &load(include_str!("./input.txt")));
 }
 
 struct Reindeer {
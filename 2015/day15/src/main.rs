@@ -1,13 +1,5 @@
 fn main() {
-    use std::time::Instant;
-
-    let ingredients = load(include_str!("../input.txt"));
-
-    let t = Instant::now();
-    println!("Part 1: {} ({:?})", part_one(&ingredients), t.elapsed());
-
-    let t = Instant::now();
-    println!("Part 2: {} ({:?})", part_two(&ingredients), t.elapsed());
+    aoc::run((part_one, part_two), &load(include_str!("../input.txt")));
 }
 
 fn load(input: &str) -> Vec<Ingredient> {
@@ -1,18 +1,13 @@
 use std::collections::HashMap;
 
 fn main() {
-    use std::time::Instant;
-
-    let aunts = load(include_str!("./input.txt"));
     let clues = sues_clues();
+    let aunts = load(include_str!("./input.txt"));
 
-    let t = Instant::now();
-    let sue = part_one(&aunts, &clues);
-    println!("Part 1: {} ({:?})", sue, t.elapsed());
-
-    let t = Instant::now();
-    let sue = part_two(&aunts, &clues);
-    println!("Part 2: {} ({:?})", sue, t.elapsed());
+    aoc::run((
+        |aunts: &[HashMap<&str, i32>]| part_one(aunts, &clues),
+        |aunts: &[HashMap<&str, i32>]| part_two(aunts, &clues),
+    ), &aunts[..]);
 }
 
 fn load(input: &str) -> Vec<HashMap<&str, i32>> {
@@ -1,105 +1,42 @@
+use bitmatrix::BitMatrix;
+use bitmatrix::life::{Boundary, Life, Neighbourhood};
+
 fn main()
 {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    let result = part_one(input);
-    println!("Part 1: {} ({:?})", result, t.elapsed());
-
-    let t = Instant::now();
-    let result = part_two(input);
-    println!("Part 2: {} ({:?})", result, t.elapsed());
+    aoc::run((part_one, part_two), include_str!("../input.txt"));
 }
+
 fn part_one(input: &str) -> usize
 {
-    let mut nrows = 0;
-    let mut ncols = 0;
-    let grid = input.lines()
-        .inspect(|l| { nrows += 1; ncols = l.len() })
-        .fold(Vec::new(), |mut v, l| { v.extend_from_slice(l.as_bytes()); v });
-
-    (0..100)
-        .fold(grid, |g, _| {
-            g.iter()
-                .enumerate()
-                .map(|(i, c)| {
-                    let lit = neighbours(i, nrows, ncols)
-                        .filter(|ix| g[*ix] == b'#')
-                        .count();
-                    (c, lit)
-                })
-                .map(|(c, lit)| {
-                    match (c, lit) {
-                        (b'.', 3) => b'#',
-                        (b'#', 2) => b'#',
-                        (b'#', 3) => b'#',
-                        _         => b'.'
-                    }
-                })
-                .collect()
-        })
-        .iter()
-        .filter(|c| **c == b'#')
-        .count()
+    animate(input, 100, false)
 }
 
 fn part_two(input: &str) -> usize
 {
-    let mut nrows = 0;
-    let mut ncols = 0;
-    let mut grid = input.lines()
-        .inspect(|l| { nrows += 1; ncols = l.len() })
-        .fold(Vec::new(), |mut v, l| { v.extend_from_slice(l.as_bytes()); v });
+    animate(input, 100, true)
+}
 
+fn animate(input: &str, steps: usize, stuck: bool) -> usize
+{
+    let grid = input.parse::<BitMatrix>().unwrap();
+    let (rows, cols) = (grid.rows, grid.cols);
     // top left, top right, bottom left, bottom right
-    let on = [0, ncols - 1, (nrows - 1) * ncols, nrows * ncols - 1];
-    on.iter().for_each(|i| grid[*i] = b'#');
+    let corners = [(0, 0), (0, cols - 1), (rows - 1, 0), (rows - 1, cols - 1)];
 
-    (0..100)
-        .fold(grid, |g, _| {
-            let mut grid = g.iter()
-                .enumerate()
-                .map(|(i, c)| {
-                    let lit = neighbours(i, nrows, ncols)
-                        .filter(|ix| g[*ix] == b'#')
-                        .count();
-                    (c, lit)
-                })
-                .map(|(c, lit)| {
-                    match (c, lit) {
-                        (b'.', 3) => b'#',
-                        (b'#', 2) => b'#',
-                        (b'#', 3) => b'#',
-                        _         => b'.'
-                    }
-                })
-                .collect::<Vec<_>>();
-            on.iter().for_each(|i| grid[*i] = b'#');
-            grid
-        })
-        .iter()
-        .filter(|c| **c == b'#')
-        .count()
-}
+    let mut life = Life::new(grid, Neighbourhood::Moore, Boundary::Dead);
+    let stick = |life: &mut Life| if stuck {
+        corners.iter().for_each(|&(r, c)| life.cells_mut().set(r, c))
+    };
 
-fn neighbours(i: usize, nrows: usize, ncols: usize) -> impl Iterator<Item = usize>
-{
-    let r = i / ncols;
-    let c = i % ncols;
-    let (rows, cols) = (
-        r.saturating_sub(1)..nrows.min(r + 2),
-        c.saturating_sub(1)..ncols.min(c + 2)
-    );
+    stick(&mut life);
+    for _ in 0..steps {
+        life.step(&[3], &[2, 3]);
+        stick(&mut life);
+    }
 
-    rows
-        .flat_map(move |rr| cols.clone().map(move |cc| (rr, cc)))
-        .filter(move |&p| p != (r, c))
-        .map(move |(rr, cc)| rr * ncols + cc)
+    life.count()
 }
 
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -117,4 +54,12 @@ mod tests {
         let input = include_str!("../input.txt");
         assert_eq!(part_two(input), 1006);
     }
+
+    #[test]
+    fn example()
+    {
+        let input = include_str!("../example.txt");
+        assert_eq!(animate(input, 4, false), 4);
+        assert_eq!(animate(input, 5, true), 17);
+    }
 }
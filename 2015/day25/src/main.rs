@@ -1,12 +1,6 @@
 fn main()
 {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    let result = part_one(input);
-    println!("Part 1: {} ({:?})", result, t.elapsed());
+    aoc::run((part_one,), include_str!("../input.txt"));
 }
 
 fn part_one(input: &str) -> u64 {
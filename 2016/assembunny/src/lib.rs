@@ -0,0 +1,339 @@
+//! Assembunny interpreter shared by days 12, 23 and 25.
+//!
+//! Addition and multiplication loops built out of inc/dec/jnz are
+//! spotted ahead of time and run in one go. The table of those is
+//! rebuilt whenever `tgl` rewrites the program.
+
+pub type Registers = [i64;4];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Value {
+    Number(i64),
+    Register(usize)
+}
+
+impl Value {
+    fn get(&self, reg: &Registers) -> i64
+    {
+        match self {
+            Value::Number(n)   => *n,
+            Value::Register(r) => reg[*r],
+        }
+    }
+
+    fn register(&self) -> Option<usize>
+    {
+        match self {
+            Value::Number(_)   => None,
+            Value::Register(r) => Some(*r),
+        }
+    }
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cmd {
+    cpy(Value, Value),
+    dec(Value),
+    inc(Value),
+    jnz(Value, Value),
+    out(Value),
+    tgl(Value)
+}
+
+impl Cmd {
+    fn toggle(self) -> Cmd
+    {
+        use Cmd::*;
+
+        match self {
+            inc(v) => dec(v),
+            dec(v) | out(v) | tgl(v) => inc(v),
+            jnz(v1, v2) => cpy(v1, v2),
+            cpy(v1, v2) => jnz(v1, v2),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum State {
+    Output(i64),
+    Done
+}
+
+// Loops the optimizer knows how to short circuit.
+#[derive(Clone, Copy, Debug)]
+enum Fast {
+    // a += b, b = 0
+    Add { a: usize, b: usize },
+    // a += b * d, c = 0, d = 0
+    Mul { a: usize, b: Value, c: usize, d: usize },
+}
+
+impl Fast {
+    fn len(&self) -> i64
+    {
+        match self {
+            Fast::Add { .. } => 3,
+            Fast::Mul { .. } => 6,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Computer {
+    cmds: Vec<Cmd>,
+    fast: Vec<Option<Fast>>,
+    optimize: bool,
+    ip: i64,
+    reg: Registers,
+}
+
+impl Computer {
+    pub fn new(program: &[Cmd], reg: Registers) -> Self
+    {
+        let cmds = program.to_vec();
+        let fast = optimize(&cmds);
+
+        Computer { cmds, fast, optimize: true, ip: 0, reg }
+    }
+
+    /// Turns the loop optimizer on or off (it's on by default).
+    pub fn set_optimize(&mut self, on: bool)
+    {
+        self.optimize = on
+    }
+
+    pub fn registers(&self) -> &Registers
+    {
+        &self.reg
+    }
+
+    pub fn program(&self) -> &[Cmd]
+    {
+        &self.cmds
+    }
+
+    pub fn is_done(&self) -> bool
+    {
+        self.ip < 0 || self.ip as usize >= self.cmds.len()
+    }
+
+    /// Runs until the program outputs a value or halts.
+    pub fn run(&mut self) -> State
+    {
+        while !self.is_done() {
+            if let Some(v) = self.step() {
+                return State::Output(v)
+            }
+        }
+
+        State::Done
+    }
+
+    /// Values sent by `out`, until the program halts.
+    pub fn outputs(&mut self) -> impl Iterator<Item = i64> + '_
+    {
+        std::iter::from_fn(move || match self.run() {
+            State::Output(v) => Some(v),
+            State::Done => None,
+        })
+    }
+
+    /// Executes the next instruction (or optimized loop), returning
+    /// the value if it was an `out`.
+    pub fn step(&mut self) -> Option<i64>
+    {
+        use Cmd::*;
+
+        if self.is_done() {
+            return None
+        }
+        let ip = self.ip as usize;
+        if self.optimize && self.fast_path(ip) {
+            return None
+        }
+
+        let reg = &mut self.reg;
+        let mut output = None;
+        self.ip += match self.cmds[ip] {
+            cpy(v, Value::Register(r)) => { reg[r] = v.get(reg); 1 },
+            inc(Value::Register(r)) => { reg[r] += 1; 1 },
+            dec(Value::Register(r)) => { reg[r] -= 1; 1 },
+            jnz(v, n) => if v.get(reg) != 0 { n.get(reg) } else { 1 },
+            out(v) => { output = Some(v.get(reg)); 1 },
+            tgl(v) => {
+                let idx = self.ip + v.get(reg);
+                if idx >= 0 && (idx as usize) < self.cmds.len() {
+                    let idx = idx as usize;
+                    self.cmds[idx] = self.cmds[idx].toggle();
+                    self.fast = optimize(&self.cmds);
+                }
+                1
+            },
+            // Invalid instructions (from toggling) are skipped
+            cpy(..) | inc(_) | dec(_) => 1,
+        };
+
+        output
+    }
+
+    fn fast_path(&mut self, ip: usize) -> bool
+    {
+        let reg = &mut self.reg;
+        match self.fast[ip] {
+            Some(f @ Fast::Add { a, b }) if reg[b] > 0 => {
+                reg[a] += reg[b];
+                reg[b] = 0;
+                self.ip += f.len();
+                true
+            },
+            Some(f @ Fast::Mul { a, b, c, d }) if b.get(reg) > 0 && reg[d] > 0 => {
+                reg[a] += b.get(reg) * reg[d];
+                reg[c] = 0;
+                reg[d] = 0;
+                self.ip += f.len();
+                true
+            },
+            _ => false
+        }
+    }
+}
+
+pub fn load(input: &str) -> Vec<Cmd>
+{
+    use Cmd::*;
+
+    input.lines().map(|s| {
+        let mut it = s.split(' ');
+        let cmd = it.next().unwrap();
+        let p1  = get_value(it.next().unwrap());
+
+        match cmd {
+            "cpy" => cpy(p1, get_value(it.next().unwrap())),
+            "dec" => dec(p1),
+            "inc" => inc(p1),
+            "jnz" => jnz(p1, get_value(it.next().unwrap())),
+            "out" => out(p1),
+            "tgl" => tgl(p1),
+            _ => panic!("Unknown command: {cmd}")
+        }
+    })
+    .collect()
+}
+
+fn get_value(s: &str) -> Value
+{
+    if let Ok(n) = s.parse::<i64>() {
+        Value::Number(n)
+    } else {
+        Value::Register((s.as_bytes()[0] - b'a') as usize)
+    }
+}
+
+fn optimize(cmds: &[Cmd]) -> Vec<Option<Fast>>
+{
+    (0..cmds.len())
+        .map(|i| mul_at(cmds, i).or_else(|| add_at(cmds, i)))
+        .collect()
+}
+
+// inc a, dec b, jnz b -2 (or with the inc and dec swapped)
+fn add_at(cmds: &[Cmd], i: usize) -> Option<Fast>
+{
+    use Cmd::*;
+    use Value::*;
+
+    let (a, b) = match cmds.get(i..i + 3)? {
+        [inc(Register(a)), dec(Register(b)), jnz(Register(c), Number(-2))]
+            if b == c => (*a, *b),
+        [dec(Register(b)), inc(Register(a)), jnz(Register(c), Number(-2))]
+            if b == c => (*a, *b),
+        _ => return None
+    };
+
+    (a != b).then_some(Fast::Add { a, b })
+}
+
+// cpy b c, <add c into a>, dec d, jnz d -5
+fn mul_at(cmds: &[Cmd], i: usize) -> Option<Fast>
+{
+    use Cmd::*;
+    use Value::*;
+
+    let Some(Fast::Add { a, b: c }) = add_at(cmds, i + 1) else { return None };
+    let (b, d) = match (cmds[i], cmds.get(i + 4..i + 6)?) {
+        (cpy(b, Register(r)), [dec(Register(d)), jnz(Register(e), Number(-5))])
+            if r == c && d == e => (b, *d),
+        _ => return None
+    };
+
+    let distinct = a != d && c != d && b.register().is_none_or(|r| r != a && r != c && r != d);
+    distinct.then_some(Fast::Mul { a, b, c, d })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(input: &str, reg: Registers, optimize: bool) -> Registers
+    {
+        let mut computer = Computer::new(&load(input), reg);
+        computer.set_optimize(optimize);
+        while computer.run() != State::Done {}
+
+        *computer.registers()
+    }
+
+    #[test]
+    fn copy_and_jump()
+    {
+        let input = "cpy 41 a\ninc a\ninc a\ndec a\njnz a 2\ndec a";
+        assert_eq!(run(input, [0;4], true)[0], 42);
+    }
+
+    #[test]
+    fn toggle()
+    {
+        let input = "cpy 2 a\ntgl a\ntgl a\ntgl a\ncpy 1 a\ndec a\ndec a";
+        assert_eq!(run(input, [0;4], true)[0], 3);
+    }
+
+    #[test]
+    fn optimized_loops()
+    {
+        // a = b * d, then a += c
+        let input = "cpy 0 a\ncpy b c\ninc a\ndec c\njnz c -2\ndec d\njnz d -5\n\
+                     cpy 9 c\ndec c\ninc a\njnz c -2";
+        let program = load(input);
+        let computer = Computer::new(&program, [0;4]);
+        assert!(matches!(computer.fast[1], Some(Fast::Mul { a: 0, b: Value::Register(1), c: 2, d: 3 })));
+        assert!(matches!(computer.fast[8], Some(Fast::Add { a: 0, b: 2 })));
+
+        let slow = run(input, [0, 6, 0, 7], false);
+        let fast = run(input, [0, 6, 0, 7], true);
+        assert_eq!(slow, [51, 6, 0, 0]);
+        assert_eq!(slow, fast);
+    }
+
+    #[test]
+    fn toggled_loop()
+    {
+        // The tgl turns the add loop's dec into an inc, so it can't
+        // be short circuited any more: b counts up from -3 to 0.
+        let input = "cpy 3 c\ntgl c\ncpy -3 b\ninc a\ndec b\njnz b -2";
+        let slow = run(input, [0;4], false);
+        let fast = run(input, [0;4], true);
+        assert_eq!(slow, [3, 0, 3, 0]);
+        assert_eq!(slow, fast);
+    }
+
+    #[test]
+    fn output()
+    {
+        let input = "cpy 3 b\nout b\ndec b\njnz b -2";
+        let mut computer = Computer::new(&load(input), [0;4]);
+        assert_eq!(computer.outputs().collect::<Vec<_>>(), [3, 2, 1]);
+    }
+}
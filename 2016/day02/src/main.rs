@@ -1,16 +1,5 @@
 fn main() {
-    use std::fs;
-    use std::time::Instant;
-
-    let input = fs::read_to_string("./input.txt").unwrap();
-
-    let t = Instant::now();
-    let code = part_one(&input);
-    println!("Part 1: {} ({:?})", code, t.elapsed());
-
-    let t = Instant::now();
-    let code = part_two(&input);
-    println!("Part 2: {} ({:?})", code, t.elapsed());
+    aoc::run((part_one, part_two), include_str!("../input.txt"));
 }
 
 fn part_one(input: &str) -> String {
@@ -1,19 +1,5 @@
 fn main() {
-    use std::fs;
-    use std::time::Instant;
-
-    let input = fs::read_to_string("./input.txt").unwrap();
-    let rows = load(&input);
-
-    let t1 = Instant::now();
-    let valid = part_one(&rows);
-    let t2 = Instant::now();
-    println!("Part 1: {} ({:?})", valid, t2 - t1);
-
-    let t1 = Instant::now();
-    let valid = part_two(&rows);
-    let t2 = Instant::now();
-    println!("Part 2: {} ({:?})", valid, t2 - t1);
+    aoc::run((part_one, part_two), &load(include_str!("../input.txt")));
 }
 
 fn load(input: &str) -> Vec<[i32;3]> {
@@ -1,18 +1,5 @@
 fn main() {
-    use std::{fs, time::Instant};
-
-    let input = fs::read_to_string("./input.txt").unwrap();
-    let rooms = load(&input);
-
-    let t1 = Instant::now();
-    let sectors = part_one(&rooms);
-    let t2 = Instant::now();
-    println!("Part 1: {} ({:?})", sectors, t2 - t1);
-
-    let t1 = Instant::now();
-    let room = part_two(&rooms);
-    let t2 = Instant::now();
-    println!("Part 2: {} ({:?})", room, t2 - t1);
+    aoc::run((part_one, part_two), &load(include_str!("../input.txt")));
 }
 
 #[derive(Debug)]
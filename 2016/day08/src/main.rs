@@ -1,20 +1,10 @@
-fn main() {
-    use std::{fs, time::Instant};
-
-    let input = fs::read_to_string("./input.txt").unwrap();
-    let actions = load(&input);
-
-    let t1 = Instant::now();
-    let lcd = part_one(&actions);
-    let t2 = Instant::now();
-    println!("Part 1: {} ({:?})", lcd.iter().sum::<usize>(), t2 - t1);
+use bitmatrix::ocr;
 
-    let t1 = Instant::now();
-    part_two(&lcd);
-    let t2 = Instant::now();
-    println!("Part 2: ({:?})", t2 - t1);
-
-    // ZJHRKCPLYJ
+fn main() {
+    aoc::run((
+        |actions: &[Action]| part_one(actions).iter().sum::<usize>(),
+        |actions: &[Action]| part_two(&part_one(actions)),
+    ), &load(include_str!("../input.txt"))[..]);
 }
 
 #[derive(Debug)]
@@ -74,11 +64,8 @@ fn part_one(actions: &[Action]) -> [usize;300] {
     })
 }
 
-fn part_two(grid: &[usize]) {
-    (0..6).for_each(|y| {
-        (0..50).for_each(|x| print!("{}", if grid[y*50+x] == 1 { '#' } else { '.' }));
-        println!();
-    });
+fn part_two(grid: &[usize]) -> String {
+    ocr::read_fn(6, 50, |y, x| grid[y*50+x] == 1).unwrap()
 }
 
 
@@ -94,5 +81,8 @@ mod tests {
     
         let lcd = part_one(&actions);
         assert_eq!(lcd.iter().sum::<usize>(), 110);
+
+        let code = part_two(&lcd);
+        assert_eq!(code, "ZJHRKCPLYJ");
     }
 }
\ No newline at end of file
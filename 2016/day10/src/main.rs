@@ -1,20 +1,7 @@
 use std::collections::HashMap;
 
 fn main() {
-    use std::{fs, time::Instant};
-
-    let input = fs::read_to_string("./input.txt").unwrap();
-    let bots = load(&input);
-
-    let t1 = Instant::now();
-    let bot = part_one(&bots);
-    let t2 = Instant::now();
-    println!("Part 1: {} ({:?})", bot, t2 - t1);
-
-    let t1 = Instant::now();
-    let val = part_two(&bots);
-    let t2 = Instant::now();
-    println!("Part 2: {} ({:?})", val, t2 - t1);
+    aoc::run((part_one, part_two), &load(include_str!("../input.txt")));
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -9,8 +9,6 @@ const BITS: u32 = 0x3;
 
 fn main()
 {
-    use std::time::Instant;
-
     // Didn't feel like parsing the input (really? sentences?).
 
     // State values represent the floor a given object is on.
@@ -18,11 +16,7 @@ fn main()
     // are two bits per object with a microchip followed by
     // it's generator.
 
-    let t = Instant::now();
-    println!("Part 1: {} ({:?})", part_one(), t.elapsed());
-
-    let t = Instant::now();
-    println!("Part 2: {} ({:?})", part_two(), t.elapsed());
+    aoc::run((|_: &()| part_one(), |_: &()| part_two()), &());
 }
 
 fn part_one() -> usize
@@ -1,110 +1,28 @@
+use assembunny::{load, Computer, Registers};
+
 fn main()
 {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    let result = part_one(input);
-    println!("Part 1: {} ({:?})", result, t.elapsed());
-
-    let t = Instant::now();
-    let result = part_two(input);
-    println!("Part 2: {} ({:?})", result, t.elapsed());
+    aoc::run((part_one, part_two), include_str!("../input.txt"));
 }
 
-fn part_one(input: &str) -> i32
+fn part_one(input: &str) -> i64
 {
-    let program = load(input);
-
-    let mut ip  = 0;
-    let mut reg = [0;4];
-    while ip < program.len() {
-        ip = program[ip].exec(ip, &mut reg)
-    }
-
-    reg[0]
+    run(input, [0;4])
 }
 
-fn part_two(input: &str) -> i32
+fn part_two(input: &str) -> i64
 {
-    
-    let program = load(input);
-
-    let mut ip  = 0;
-    let mut reg = [0, 0, 1, 0];
-    while ip < program.len() {
-        ip = program[ip].exec(ip, &mut reg)
-    }
-
-    reg[0]
-}
-
-#[allow(non_camel_case_types)]
-enum Cmd {
-    cpn(usize, i32),
-    cpr(usize, usize),
-    inc(usize),
-    dec(usize),
-    jnz(usize, i32),
-    jmp(i32),
-}
-
-impl Cmd {
-    fn exec(&self, ip: usize, reg: &mut [i32;4]) -> usize
-    {
-        use Cmd::*;
-        (ip as i32 + match self {
-            cpn(r, n) => { reg[*r] = *n; 1 },
-            cpr(a, b) => { reg[*b] = reg[*a]; 1 },
-            inc(r)    => { reg[*r] += 1; 1 },
-            dec(r)    => { reg[*r] -= 1; 1 },
-            jnz(r, n) => if reg[*r] != 0 { *n } else { 1 },
-            jmp(n)    => *n
-        }) as usize
-    }
+    run(input, [0, 0, 1, 0])
 }
 
-fn load(input: &str) -> Vec<Cmd>
+fn run(input: &str, reg: Registers) -> i64
 {
-    use Cmd::*;
+    let program = load(input);
+
+    let mut computer = Computer::new(&program, reg);
+    computer.run();
 
-    input.lines()
-        .map(|line| {
-            let mut it = line.split(' ');
-            match it.next() {
-                Some("cpy") => {
-                    let a = it.next().unwrap();
-                    let b = it.next().map(|s| s.as_bytes()[0] - b'a').unwrap();
-                    if let Ok(n) = a.parse::<i32>() {
-                        cpn(b as usize, n)
-                    } else {
-                        let a = (a.as_bytes()[0] - b'a') as usize;
-                        cpr(a, b as usize)
-                    }
-                },
-                Some("inc") => {
-                    let r = it.next().map(|s| s.as_bytes()[0] - b'a').unwrap();
-                    inc(r as usize)
-                },
-                Some("dec") => {
-                    let r = it.next().map(|s| s.as_bytes()[0] - b'a').unwrap();
-                    dec(r as usize)
-                },
-                Some("jnz") => {
-                    let a = it.next().unwrap();
-                    let b = it.next().and_then(|s| s.parse::<i32>().ok());
-                    if a.parse::<i32>().is_ok() {
-                        jmp(b.unwrap())
-                    } else {
-                        let a = (a.as_bytes()[0] - b'a') as usize;
-                        jnz(a, b.unwrap())
-                    }
-                },
-                _ => unreachable!()
-            }
-        })
-        .collect()
+    computer.registers()[0]
 }
 
 
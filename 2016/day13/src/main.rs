@@ -4,17 +4,7 @@ type Memo = HashMap<(i32, i32), i32>;
 
 fn main()
 {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    let result = part_one((31, 39), input);
-    println!("Part 1: {} ({:?})", result, t.elapsed());
-
-    let t = Instant::now();
-    let result = part_two(input);
-    println!("Part 2: {} ({:?})", result, t.elapsed());
+    aoc::run((|input: &str| part_one((31, 39), input), part_two), include_str!("../input.txt"));
 }
 
 fn part_one(goal: (i32, i32), input: &str) -> usize
@@ -1,18 +1,5 @@
 fn main() {
-    use std::{fs, time::Instant};
-
-    let input = fs::read_to_string("./input.txt").unwrap();
-    let discs = load(&input);
-
-    let t1 = Instant::now();
-    let when = part_one(&discs);
-    let t2 = Instant::now();
-    println!("Part 1: {} ({:?})", when, t2 - t1);
-
-    let t1 = Instant::now();
-    let when = part_two(&discs);
-    let t2 = Instant::now();
-    println!("Part 2: {} ({:?})", when, t2 - t1);
+    aoc::run((part_one, part_two), &load(include_str!("../input.txt")));
 }
 
 fn load(input: &str) -> Vec<(i64, i64)> {
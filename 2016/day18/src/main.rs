@@ -4,15 +4,10 @@ const TRAPPED: [&[u8]; 4] = [
 ];
 
 fn main() {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    println!("Part 1: {} ({:?})", safe_tiles(input, 40), t.elapsed());
-
-    let t = Instant::now();
-    println!("Part 2: {} ({:?})", safe_tiles(input, 400000), t.elapsed());
+    aoc::run((
+        |input: &str| safe_tiles(input, 40),
+        |input: &str| safe_tiles(input, 400000),
+    ), include_str!("../input.txt"));
 }
 
 fn safe_tiles(input: &str, rows: usize) -> usize {
@@ -1,62 +1,26 @@
-fn main() {
-    use std::{fs, time::Instant};
-
-    let input = fs::read_to_string("./input.txt").unwrap();
-    let excluded = load(&input);
+use aoc::interval::IntervalSet;
 
-    let t1 = Instant::now();
-    let ip = part_one(&excluded);
-    let t2 = Instant::now();
-    println!("Part 1: {} ({:?})", ip, t2 - t1);
-
-    let t1 = Instant::now();
-    let ips = part_two(&excluded);
-    let t2 = Instant::now();
-    println!("Part 2: {} ({:?})", ips, t2 - t1);
+fn main() {
+    aoc::run((part_one, part_two), &load(include_str!("../input.txt")));
 }
 
-fn load(input: &str) -> Vec<(u32, u32)> {
+fn load(input: &str) -> IntervalSet<u32> {
     input.lines().map(|s| {
         let mut it = s.split('-');
         let start = it.next().unwrap().parse::<u32>().unwrap();
         let end   = it.next().unwrap().parse::<u32>().unwrap();
-        (start, end)
+        start..=end
     })
     .collect()
 }
 
-fn part_one(excluded: &[(u32, u32)]) -> u32 {
-    let mut v = excluded.to_vec();
-    v.sort_unstable();
-
-    let mut n = v[0].1 + 1;
-    for (start, end) in v.iter().skip(1) {
-        if *start > n {
-            return n
-        } else if *end >= n {
-            n = end + 1
-        }
-    }
-
-    0
+fn part_one(excluded: &IntervalSet<u32>) -> u32 {
+    excluded.complement(..).first().unwrap_or(0)
 }
 
-fn part_two(excluded: &[(u32, u32)]) -> u32 {
-    let mut v = excluded.to_vec();
-    v.sort_unstable();
-
-    let mut n = v[0].1 + 1;
-    let mut count = 0;
-    for (start, end) in v.iter().skip(1) {
-        if *start > n {
-            count += *start - n;
-            n = if *end < u32::MAX { end + 1 } else { u32::MAX }
-        } else if *end >= n {
-            n = if *end < u32::MAX { end + 1 } else { u32::MAX }
-        }
-    }
-
-    count
+fn part_two(excluded: &IntervalSet<u32>) -> u64 {
+    // All 2^32 addresses are allowed if nothing's blocked.
+    excluded.complement(..).len() as u64
 }
 
 #[cfg(test)]
@@ -75,4 +39,9 @@ mod tests {
         let ips = part_two(&excluded);
         assert_eq!(ips, 146);
     }
+
+    #[test]
+    fn nothing_blocked() {
+        assert_eq!(part_two(&IntervalSet::new()), 1 << 32);
+    }
 }
\ No newline at end of file
@@ -1,20 +1,10 @@
 use std::{fmt, str::FromStr};
 
 fn main() {
-    use std::{fs, time::Instant};
-
-    let input = fs::read_to_string("./input.txt").unwrap();
-    let actions = load(&input);
-
-    let t1 = Instant::now();
-    let password = part_one(&actions, "abcdefgh");
-    let t2 = Instant::now();
-    println!("Part 1: {} ({:?})", password, t2 - t1);
-
-    let t1 = Instant::now();
-    let password = part_two(&actions, "fbgdceah");
-    let t2 = Instant::now();
-    println!("Part 2: {} ({:?})", password, t2 - t1);
+    aoc::run((
+        |actions: &[Action]| part_one(actions, "abcdefgh"),
+        |actions: &[Action]| part_two(actions, "fbgdceah"),
+    ), &load(include_str!("../input.txt"))[..]);
 }
 
 #[derive(Debug)]
@@ -7,20 +7,7 @@ type Wires  = Vec<Wire>;
 type State0 = (Wires, Points);
 
 fn main() {
-    use std::{fs, time::Instant};
-
-    let input = fs::read_to_string("./input.txt").unwrap();
-    let state = load(&input);
-
-    let t1 = Instant::now();
-    let steps = part_one(&state);
-    let t2 = Instant::now();
-    println!("Part 1: {} ({:?})", steps, t2 - t1);
-
-    let t1 = Instant::now();
-    let steps = part_two(&state);
-    let t2 = Instant::now();
-    println!("Part 2: {} ({:?})", steps, t2 - t1);
+    aoc::run((part_one, part_two), &load(include_str!("../input.txt")));
 }
 
 fn load(input: &str) -> State0 {
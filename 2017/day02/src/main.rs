@@ -1,15 +1,5 @@
 fn main() {
-    use std::{fs, time::Instant};
-
-    let input = fs::read_to_string("./input.txt").unwrap();
-
-    let t = Instant::now();
-    let checksum = part_one(&input);
-    println!("Part 1: {} {:?}", checksum, t.elapsed());
-
-    let t = Instant::now();
-    let checksum = part_two(&input);
-    println!("Part 2: {} {:?}", checksum, t.elapsed());
+    aoc::run((part_one, part_two), include_str!("../input.txt"));
 }
 
 fn part_one(input: &str) -> i32 {
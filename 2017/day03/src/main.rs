@@ -1,15 +1,5 @@
 fn main() {
-    use std::time::Instant;
-
-    let t1 = Instant::now();
-    let steps = part_one(347991);
-    let t2 = Instant::now();
-    println!("Part 1: {} {:?}", steps, t2 - t1);
-
-    let t1 = Instant::now();
-    let value = part_two(347991);
-    let t2 = Instant::now();
-    println!("Part 2: {} {:?}", value, t2 - t1);
+    aoc::run((|&n: &i32| part_one(n), |&n: &i32| part_two(n)), &347991);
 }
 
 fn part_one(square: i32) -> i32 {
@@ -1,18 +1,5 @@
 fn main() {
-    use std::fs;
-    use std::time::Instant;
-
-    let input = load(&fs::read_to_string("./input.txt").unwrap());
-
-    let t1 = Instant::now();
-    let steps = part_one(&input);
-    let t2 = Instant::now();
-    println!("Part 1: {} {:?}", steps, t2 - t1);
-
-    let t1 = Instant::now();
-    let steps = part_two(&input);
-    let t2 = Instant::now();
-    println!("Part 2: {} {:?}", steps, t2 - t1);
+    aoc::run((part_one, part_two), &load(include_str!("../input.txt")));
 }
 
 fn load(input: &str) -> Vec<i32> {
@@ -2,21 +2,13 @@ use std::cell::Cell;
 use std::collections::HashMap;
 
 fn main() {
-    use std::fs;
-    use std::time::Instant;
-    
-    let input  = fs::read_to_string("./input.txt").unwrap();
-    let tower = load(&input);
-
-    let t1 = Instant::now();
+    // Part two starts from the bottom program, so find it just once.
+    let tower = load(include_str!("../input.txt"));
     let bottom = part_one(&tower);
-    let t2 = Instant::now();
-    println!("Part 1: {} {:?}", bottom, t2 - t1);
-
-    let t1 = Instant::now();
-    let weight = part_two(&bottom, &tower);
-    let t2 = Instant::now();
-    println!("Part 2: {} {:?}", weight, t2 - t1);
+    aoc::run((
+        |_: &Tower| bottom.clone(),
+        |tower: &Tower| part_two(&bottom, tower),
+    ), &tower);
 }
 
 #[derive(Debug)]
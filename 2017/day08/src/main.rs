@@ -1,17 +1,5 @@
 fn main() {
-    use std::{fs, time::Instant};
-    
-    let input = fs::read_to_string("./input.txt").unwrap();
-
-    let t1 = Instant::now();
-    let value = part_one(&input);
-    let t2 = Instant::now();
-    println!("Part 1: {} {:?}", value, t2 - t1);
-
-    let t1 = Instant::now();
-    let value = part_two(&input);
-    let t2 = Instant::now();
-    println!("Part 2: {} {:?}", value, t2 - t1);
+    aoc::run((part_one, part_two), include_str!("../input.txt"));
 }
 
 fn part_one(input: &str) -> i32 {
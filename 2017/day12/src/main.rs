@@ -1,20 +1,7 @@
 use std::collections::{HashMap, HashSet};
 
 fn main() {
-    use std::{fs, time::Instant};
-    
-    let input = fs::read_to_string("./input.txt").unwrap();
-    let pipes = load(&input);
-
-    let t1 = Instant::now();
-    let programs = part_one(&pipes);
-    let t2 = Instant::now();
-    println!("Part 1: {} {:?}", programs, t2 - t1);
-
-    let t1 = Instant::now();
-    let groups = part_two(&pipes);
-    let t2 = Instant::now();
-    println!("Part 2: {} {:?}", groups, t2 - t1);
+    aoc::run((part_one, part_two), &load(include_str!("../input.txt")));
 }
 
 fn load(input: &str) -> HashMap<i32, Vec<i32>> {
@@ -1,19 +1,6 @@
 
 fn main() {
-    use std::{fs, time::Instant};
-    
-    let input = fs::read_to_string("./input.txt").unwrap();
-    let scanners = load(&input);
-
-    let t1 = Instant::now();
-    let severity = part_one(&scanners);
-    let t2 = Instant::now();
-    println!("Part 1: {} {:?}", severity, t2 - t1);
-
-    let t1 = Instant::now();
-    let delay = part_two(&scanners);
-    let t2 = Instant::now();
-    println!("Part 2: {} {:?}", delay, t2 - t1);
+    aoc::run((part_one, part_two), &load(include_str!("../input.txt")));
 }
 
 fn load(input: &str) -> Vec<(i32, i32)> {
@@ -1,15 +1,7 @@
-fn main() {
-    use std::time::Instant;
-
-    let t1 = Instant::now();
-    let used = part_one("ffayrhll");
-    let t2 = Instant::now();
-    println!("Part 1: {used} {:?}", t2 - t1);
+use bitmatrix::BitMatrix;
 
-    let t1 = Instant::now();
-    let regions = part_two("ffayrhll");
-    let t2 = Instant::now();
-    println!("Part 2: {regions} {:?}", t2 - t1);
+fn main() {
+    aoc::run((part_one, part_two), "ffayrhll");
 }
 
 fn part_one(key: &str) -> i32 {
@@ -27,44 +19,18 @@ fn part_one(key: &str) -> i32 {
     })
 }
 
-fn part_two(key: &str) -> i32 {
-    use std::collections::{HashSet, VecDeque};
-
-    let mut used = HashSet::new();
-    (0..128).for_each(|y| {
-        let hash = knot(&format!("{key}-{y}"));
-        let bits = hash.bytes().map(|c| {
-                let n = if c <= b'9' { c - b'0' } else { c - b'a' + 10 };
-                format!("{n:04b}")
-            })
-            .collect::<Vec<_>>()
-            .join("");
-        bits.chars().enumerate()
-            .for_each(|(x, c)| if c == '1' { used.insert((x as i32, y)); } )
-    });
-
-    let delta = [(0, -1), (0, 1), (-1, 0), (1, 0)];
-    let mut q = VecDeque::new();
-    let mut regions = 0;
-    while !used.is_empty() {
-        regions += 1;
-
-        let p = *used.iter().next().unwrap();
-        used.remove(&p);
-
-        q.push_back(p);
-        while let Some(p) = q.pop_front() {
-            delta.iter().for_each(|d| {
-                let pt = (p.0 + d.0, p.1 + d.1);
-                if used.contains(&pt) {
-                    used.remove(&pt);
-                    q.push_back(pt);
-                }
-            })
-        }
-    }
+fn part_two(key: &str) -> usize {
+    let rows = (0..128)
+        .map(|y| {
+            let hash = knot(&format!("{key}-{y}"));
+            hash.bytes()
+                .map(|c| if c <= b'9' { c - b'0' } else { c - b'a' + 10 })
+                .flat_map(|n| (0..4).rev().map(move |i| n >> i & 1 == 1))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
 
-    regions
+    BitMatrix::from_rows(rows, |used| *used).components(false).len()
 }
 
 fn knot(input: &str) -> String {
@@ -1,15 +1,5 @@
 fn main() {
-    use std::time::Instant;
-
-    let t1 = Instant::now();
-    let count = part_one(591, 393);
-    let t2 = Instant::now();
-    println!("Part 1: {} {:?}", count, t2 - t1);
-
-    let t1 = Instant::now();
-    let count = part_two(591, 393);
-    let t2 = Instant::now();
-    println!("Part 2: {} {:?}", count, t2 - t1);
+    aoc::run((|&(a, b): &(u64, u64)| part_one(a, b), |&(a, b): &(u64, u64)| part_two(a, b)), &(591, 393));
 }
 
 fn part_one(a: u64, b: u64) -> u64 {
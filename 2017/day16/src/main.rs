@@ -1,15 +1,6 @@
 
 fn main() {
-    use std::{fs, time::Instant};
-    
-    let input = fs::read_to_string("./input.txt").unwrap();
-    let moves = load(&input);
-
-    let t = Instant::now();
-    println!("Part 1: {} {:?}", part_one(&moves), t.elapsed());
-
-    let t = Instant::now();
-    println!("Part 2: {} {:?}", part_two(&moves), t.elapsed());
+    aoc::run((part_one, part_two), &load(include_str!("../input.txt")));
 }
 
 enum Move {
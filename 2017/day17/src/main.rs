@@ -1,16 +1,6 @@
 
 fn main() {
-    use std::time::Instant;
-
-    let t1 = Instant::now();
-    let value = part_one(328);
-    let t2 = Instant::now();
-    println!("Part 1: {} {:?}", value, t2 - t1);
-
-    let t1 = Instant::now();
-    let value = part_two(328);
-    let t2 = Instant::now();
-    println!("Part 2: {} {:?}", value, t2 - t1);
+    aoc::run((|&n: &usize| part_one(n), |&n: &usize| part_two(n)), &328);
 }
 
 fn part_one(steps: usize) -> i32 {
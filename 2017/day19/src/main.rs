@@ -1,20 +1,7 @@
 use std::collections::HashMap;
 
 fn main() {
-    use std::{fs, time::Instant};
-    
-    let input = fs::read_to_string("./input.txt").unwrap();
-    let path  = load(&input);
-
-    let t1 = Instant::now();
-    let letters = part_one(&path);
-    let t2 = Instant::now();
-    println!("Part 1: {} {:?}", letters, t2 - t1);
-
-    let t1 = Instant::now();
-    let steps = part_two(&path);
-    let t2 = Instant::now();
-    println!("Part 2: {} {:?}", steps, t2 - t1);
+    aoc::run((part_one, part_two), &load(include_str!("../input.txt")));
 }
 
 enum Segment {
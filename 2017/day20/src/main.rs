@@ -1,20 +1,7 @@
 use std::hash::{Hash, Hasher};
 
 fn main() {
-    use std::{fs, time::Instant};
-    
-    let input = fs::read_to_string("./input.txt").unwrap();
-    let particles = load(&input);
-
-    let t1 = Instant::now();
-    let particle = part_one(&particles);
-    let t2 = Instant::now();
-    println!("Part 1: {} {:?}", particle, t2 - t1);
-
-    let t1 = Instant::now();
-    let left = part_two(&particles);
-    let t2 = Instant::now();
-    println!("Part 2: {} {:?}", left, t2 - t1);
+    aoc::run((part_one, part_two), &load(include_str!("../input.txt")));
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -2,20 +2,7 @@ use std::collections::HashMap;
 use pathfinding::matrix::Matrix;
 
 fn main() {
-    use std::{fs, time::Instant};
-    
-    let input = fs::read_to_string("./input.txt").unwrap();
-    let rules = load(&input);
-
-    let t1 = Instant::now();
-    let pixels = part_one(&rules);
-    let t2 = Instant::now();
-    println!("Part 1: {} {:?}", pixels, t2 - t1);
-
-    let t1 = Instant::now();
-    let pixels = part_two(&rules);
-    let t2 = Instant::now();
-    println!("Part 2: {} {:?}", pixels, t2 - t1);
+    aoc::run((part_one, part_two), &load(include_str!("../input.txt")));
 }
 
 type Rules = HashMap<Matrix<char>, Matrix<char>>;
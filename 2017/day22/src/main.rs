@@ -1,20 +1,7 @@
 use std::collections::HashSet;
 
 fn main() {
-    use std::{fs, time::Instant};
-    
-    let input = fs::read_to_string("./input.txt").unwrap();
-    let nodes = load(&input);
-
-    let t1 = Instant::now();
-    let infections = part_one(&nodes);
-    let t2 = Instant::now();
-    println!("Part 1: {} {:?}", infections, t2 - t1);
-
-    let t1 = Instant::now();
-    let infections = part_two(&nodes);
-    let t2 = Instant::now();
-    println!("Part 2: {} {:?}", infections, t2 - t1);
+    aoc::run((part_one, part_two), &load(include_str!("../input.txt")));
 }
 
 type Nodes = HashSet<(i32, i32)>;
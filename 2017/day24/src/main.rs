@@ -1,14 +1,6 @@
 
 fn main() {
-    use std::{fs, time::Instant};
-    
-    let input = load(&fs::read_to_string("./input.txt").unwrap());
-
-    let t = Instant::now();
-    println!("Part 1: {} {:?}", part_one(&input), t.elapsed());
-
-    let t = Instant::now();
-    println!("Part 2: {} {:?}", part_two(&input), t.elapsed());
+    aoc::run((part_one, part_two), &load(include_str!("../input.txt")));
 }
 
 fn load(input: &str) -> Vec<[i32;2]> {
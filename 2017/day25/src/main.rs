@@ -1,11 +1,6 @@
 
 fn main() {
-    use std::time::Instant;
-
-    let t1 = Instant::now();
-    let checksum = part_one(12425180);
-    let t2 = Instant::now();
-    println!("Part 1: {} {:?}", checksum, t2 - t1);
+    aoc::run((|&n: &i32| part_one(n),), &12425180);
 }
 
 fn part_one(steps: i32) -> usize {
@@ -0,0 +1,292 @@
+//! Register machine from days 18 and 23. The same instructions get
+//! read two ways: as sound effects (play a sound, recover the last
+//! one played) or as a duet, where programs send each other values
+//! and block waiting to receive them.
+use std::collections::VecDeque;
+
+pub type Registers = [i64;26];
+
+/// How `snd` and `rcv` behave.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Dialect {
+    /// `snd` plays a sound, `rcv` recovers the last sound played if
+    /// its value isn't zero.
+    Sound,
+    /// `snd` sends a value to the outbox, `rcv` takes one from the
+    /// inbox, waiting if there isn't one.
+    Duet,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Value {
+    Number(i64),
+    Register(usize)
+}
+
+impl Value {
+    fn get(&self, reg: &Registers) -> i64 {
+        match self {
+            Value::Number(n)   => *n,
+            Value::Register(r) => reg[*r],
+        }
+    }
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Cmd {
+    snd(Value),
+    rcv(Value),
+    set(Value, Value),
+    add(Value, Value),
+    sub(Value, Value),
+    mul(Value, Value),
+    rem(Value, Value),
+    jgz(Value, Value),
+    jnz(Value, Value),
+}
+
+const MNEMONICS: [&str;9] = ["snd", "rcv", "set", "add", "sub", "mul", "mod", "jgz", "jnz"];
+
+impl Cmd {
+    fn index(&self) -> usize {
+        use Cmd::*;
+
+        match self {
+            snd(_) => 0,
+            rcv(_) => 1,
+            set(..) => 2,
+            add(..) => 3,
+            sub(..) => 4,
+            mul(..) => 5,
+            rem(..) => 6,
+            jgz(..) => 7,
+            jnz(..) => 8,
+        }
+    }
+}
+
+pub fn load(input: &str) -> Vec<Cmd> {
+    use Cmd::*;
+
+    input.lines().map(|s| {
+        let mut it = s.split(' ');
+        let cmd = it.next().unwrap();
+        let p1  = get_value(it.next().unwrap());
+        let mut p2 = || get_value(it.next().unwrap());
+
+        match cmd {
+            "snd" => snd(p1),
+            "rcv" => rcv(p1),
+            "set" => set(p1, p2()),
+            "add" => add(p1, p2()),
+            "sub" => sub(p1, p2()),
+            "mul" => mul(p1, p2()),
+            "mod" => rem(p1, p2()),
+            "jgz" => jgz(p1, p2()),
+            "jnz" => jnz(p1, p2()),
+            _ => panic!("Unknown command: {cmd}")
+        }
+    })
+    .collect()
+}
+
+fn get_value(s: &str) -> Value {
+    if let Ok(n) = s.parse::<i64>() {
+        Value::Number(n)
+    } else {
+        Value::Register((s.as_bytes()[0] - b'a') as usize)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum State {
+    Ready,
+    /// Waiting on `rcv` with nothing in the inbox.
+    Blocked,
+    /// A sound was recovered (`Dialect::Sound` only).
+    Recovered(i64),
+    Done,
+}
+
+#[derive(Clone, Debug)]
+pub struct Program {
+    cmds: Vec<Cmd>,
+    dialect: Dialect,
+    ip: i64,
+    reg: Registers,
+    sound: Option<i64>,
+    counts: [usize;9],
+    pub inbox: VecDeque<i64>,
+    pub outbox: VecDeque<i64>,
+}
+
+impl Program {
+    pub fn new(cmds: &[Cmd], dialect: Dialect) -> Self {
+        Program {
+            cmds: cmds.to_vec(),
+            dialect,
+            ip: 0,
+            reg: [0;26],
+            sound: None,
+            counts: [0;9],
+            inbox: VecDeque::new(),
+            outbox: VecDeque::new(),
+        }
+    }
+
+    pub fn register(&self, r: char) -> i64 {
+        self.reg[(r as u8 - b'a') as usize]
+    }
+
+    pub fn set_register(&mut self, r: char, v: i64) {
+        self.reg[(r as u8 - b'a') as usize] = v
+    }
+
+    /// Number of times the instruction (by mnemonic) has been executed.
+    pub fn executed(&self, mnemonic: &str) -> usize {
+        MNEMONICS.iter()
+            .position(|m| *m == mnemonic)
+            .map_or(0, |i| self.counts[i])
+    }
+
+    /// Total number of instructions executed.
+    pub fn steps(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.ip < 0 || self.ip as usize >= self.cmds.len()
+    }
+
+    /// Runs until the program blocks, recovers a sound or finishes.
+    pub fn run(&mut self) -> State {
+        loop {
+            match self.step() {
+                State::Ready => (),
+                state => break state
+            }
+        }
+    }
+
+    pub fn step(&mut self) -> State {
+        use Cmd::*;
+
+        if self.is_done() {
+            return State::Done
+        }
+
+        let cmd = self.cmds[self.ip as usize];
+        let reg = &self.reg;
+        let mut state = State::Ready;
+        let mut write = None;
+        let jump = match cmd {
+            set(a, b) => { write = Some((a, b.get(reg))); 1 },
+            add(a, b) => { write = Some((a, a.get(reg) + b.get(reg))); 1 },
+            sub(a, b) => { write = Some((a, a.get(reg) - b.get(reg))); 1 },
+            mul(a, b) => { write = Some((a, a.get(reg) * b.get(reg))); 1 },
+            rem(a, b) => { write = Some((a, a.get(reg) % b.get(reg))); 1 },
+            jgz(a, b) => if a.get(reg) > 0 { b.get(reg) } else { 1 },
+            jnz(a, b) => if a.get(reg) != 0 { b.get(reg) } else { 1 },
+            snd(a) => {
+                let v = a.get(reg);
+                match self.dialect {
+                    Dialect::Sound => self.sound = Some(v),
+                    Dialect::Duet  => self.outbox.push_back(v),
+                }
+                1
+            },
+            rcv(a) => match self.dialect {
+                Dialect::Sound => {
+                    if let (true, Some(v)) = (a.get(reg) != 0, self.sound) {
+                        state = State::Recovered(v)
+                    }
+                    1
+                },
+                Dialect::Duet => match self.inbox.pop_front() {
+                    Some(v) => { write = Some((a, v)); 1 },
+                    None => return State::Blocked,
+                },
+            },
+        };
+        if let Some((Value::Register(r), v)) = write {
+            self.reg[r] = v
+        }
+        self.counts[cmd.index()] += 1;
+        self.ip += jump;
+
+        if state == State::Ready && self.is_done() {
+            State::Done
+        } else {
+            state
+        }
+    }
+}
+
+/// Runs the programs in turn until none of them can make progress,
+/// either because they've finished or they're all waiting on values
+/// which aren't coming. Each program's outbox is delivered to the
+/// next program around the ring, so a pair talk to each other.
+pub fn schedule(programs: &mut [Program]) {
+    let n = programs.len();
+
+    let mut progress = true;
+    while progress {
+        progress = false;
+        for i in 0..n {
+            let steps = programs[i].steps();
+            programs[i].run();
+            progress |= programs[i].steps() != steps;
+
+            let values = std::mem::take(&mut programs[i].outbox);
+            programs[(i + 1) % n].inbox.extend(values);
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sound() {
+        let input = "set a 1\nadd a 2\nmul a a\nmod a 5\nsnd a\nset a 0\nrcv a\njgz a -1\nset a 1\njgz a -2";
+        let mut program = Program::new(&load(input), Dialect::Sound);
+
+        assert_eq!(program.run(), State::Recovered(4));
+        assert_eq!(program.executed("snd"), 1);
+        assert_eq!(program.executed("rcv"), 2);
+    }
+
+    #[test]
+    fn duet() {
+        let input = "snd 1\nsnd 2\nsnd p\nrcv a\nrcv b\nrcv c\nrcv d";
+        let cmds = load(input);
+
+        let mut programs = [Program::new(&cmds, Dialect::Duet), Program::new(&cmds, Dialect::Duet)];
+        programs[1].set_register('p', 1);
+        schedule(&mut programs);
+
+        for (id, p) in programs.iter_mut().enumerate() {
+            assert_eq!(p.executed("snd"), 3);
+            assert_eq!(p.run(), State::Blocked);
+            assert_eq!([p.register('a'), p.register('b'), p.register('c')], [1, 2, 1 - id as i64]);
+        }
+    }
+
+    #[test]
+    fn counters() {
+        // b = 3 * 4 by repeated addition, then a multiply
+        let input = "set b 0\nset c 4\nadd b 3\nsub c 1\njnz c -2\nmul b 2";
+        let mut program = Program::new(&load(input), Dialect::Duet);
+
+        assert_eq!(program.run(), State::Done);
+        assert_eq!(program.register('b'), 24);
+        assert_eq!(program.executed("add"), 4);
+        assert_eq!(program.executed("jnz"), 4);
+        assert_eq!(program.executed("mul"), 1);
+        assert_eq!(program.executed("tgl"), 0);
+        assert_eq!(program.steps(), 15);
+    }
+}
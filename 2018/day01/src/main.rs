@@ -1,19 +1,9 @@
 
 fn main() {
-    use std::time::Instant;
-
     let input = load(include_str!("./input.txt"))
         .expect("Loading failed: ");
 
-    let t1 = Instant::now();
-    let freq = part_one(&input);
-    let t2 = Instant::now();
-    println!("Part 1: {freq} ({:?})", t2 - t1);
-
-    let t1 = Instant::now();
-    let freq = part_two(&input);
-    let t2 = Instant::now();
-    println!("Part 2: {freq} ({:?})", t2 - t1);
+    aoc::run((part_one, part_two), &input[..]);
 }
 
 fn load(input: &str) -> Result<Vec<i32>, std::num::ParseIntError> {
@@ -1,19 +1,7 @@
 use std::str::FromStr;
 
 fn main() {
-    use std::time::Instant;
-
-    let claims = load(include_str!("./input.txt"));
-
-    let t1 = Instant::now();
-    let overlaps = part_one(&claims);
-    let t2 = Instant::now();
-    println!("Part 1: {} ({:?})", overlaps, t2 - t1);
-
-    let t1 = Instant::now();
-    let fabric = part_two(&claims);
-    let t2 = Instant::now();
-    println!("Part 2: {} ({:?})", fabric, t2 - t1);
+    aoc::run((part_one, part_two), &load(include_str!("./input.txt")));
 }
 
 fn part_one(claims: &[Claim]) -> usize {
@@ -2,19 +2,7 @@ use std::cmp::Ordering;
 use chrono::prelude::*;
 
 fn main() {
-    use std::time::Instant;
-
-    let events = load(include_str!("./input.txt"));
-
-    let t1 = Instant::now();
-    let guard = part_one(&events);
-    let t2 = Instant::now();
-    println!("Part 1: {guard}  ({:?})", t2 - t1);
-
-    let t1 = Instant::now();
-    let guard = part_two(&events);
-    let t2 = Instant::now();
-    println!("Part 2: {guard}  ({:?})", t2 - t1);
+    aoc::run((part_one, part_two), &load(include_str!("./input.txt")));
 }
 
 fn part_one(events: &[Event]) -> u32 {
@@ -1,15 +1,8 @@
 fn main() {
-    use std::time::Instant;
-
-    let input = include_str!("./input.txt");
-
-    let t = Instant::now();
-    let units = part_one(input);
-    println!("Part 1: {}  ({:?})", units.len(), t.elapsed());
-
-    let t = Instant::now();
-    let units = part_two(&units);
-    println!("Part 2: {}  ({:?})", units, t.elapsed());
+    aoc::run((
+        |input: &str| part_one(input).len(),
+        |input: &str| part_two(&part_one(input)),
+    ), include_str!("./input.txt"));
 }
 
 fn part_one(input: &str) -> Vec<u8> {
@@ -1,17 +1,5 @@
 fn main() {
-    use std::time::Instant;
-
-    let coords = load(include_str!("./input.txt"));
-
-    let t1 = Instant::now();
-    let area = part_one(&coords);
-    let t2 = Instant::now();
-    println!("Part 1: {}  ({:?})", area, t2 - t1);
-
-    let t1 = Instant::now();
-    let area = part_two(&coords);
-    let t2 = Instant::now();
-    println!("Part 2: {}  ({:?})", area, t2 - t1);
+    aoc::run((part_one, part_two), &load(include_str!("./input.txt")));
 }
 
 fn part_one(coords: &Coords) -> u32 {
@@ -1,19 +1,7 @@
 use std::collections::BTreeSet;
 
 fn main() {
-    use std::time::Instant;
-
-    let steps = load(include_str!("./input.txt"));
-
-    let t1 = Instant::now();
-    let dance = part_one(&steps);
-    let t2 = Instant::now();
-    println!("Part 1: {dance}  ({:?})", t2 - t1);
-
-    let t1 = Instant::now();
-    let seconds = part_two(&steps);
-    let t2 = Instant::now();
-    println!("Part 2: {seconds}  ({:?})", t2 - t1);
+    aoc::run((part_one, part_two), &load(include_str!("./input.txt")));
 }
 
 fn part_one(steps: &[Step]) -> String {
@@ -1,19 +1,10 @@
 use indextree::{Arena, NodeId};
 
 fn main() {
-    use std::time::Instant;
-
-    let (arena, root) = load(include_str!("./input.txt"));
-
-    let t1 = Instant::now();
-    let meta = part_one(&arena);
-    let t2 = Instant::now();
-    println!("Part 1: {}  ({:?})", meta, t2 - t1);
-
-    let t1 = Instant::now();
-    let value = part_two(&root, &arena);
-    let t2 = Instant::now();
-    println!("Part 2: {}  ({:?})", value, t2 - t1);
+    aoc::run((
+        |(arena, _): &(Arena<Meta>, NodeId)| part_one(arena),
+        |(arena, root): &(Arena<Meta>, NodeId)| part_two(root, arena),
+    ), &load(include_str!("./input.txt")));
 }
 
 fn part_one(arena: &Arena<Meta>) -> u32 {
@@ -1,26 +1,38 @@
+use bitmatrix::ocr;
+
 fn main() {
-    let mut points = load(include_str!("./input.txt"));
+    aoc::run((part_one, part_two), &load(include_str!("./input.txt")));
+}
 
-    // Y axis sort for printing
-    let mut min_x = points[0];
-    let mut min_y = points[0];
-    points.sort_by(|p1, p2| {
-        min_x = if p1.x() < min_x.x() { *p1 } else { min_x };
-        min_y = if p1.y() < min_y.y() { *p1 } else { min_y };
+fn part_one(points: &[Point]) -> String {
+    let secs = align(points);
+    let pos = points.iter().map(|p| p.pos_at(secs)).collect::<Vec<_>>();
 
-        let t1 = (p1.pos.1, p1.pos.0);
-        let t2 = (p2.pos.1, p2.pos.0);
-        t1.partial_cmp(&t2).unwrap()
-    });
+    let min_x = pos.iter().map(|p| p.0).min().unwrap();
+    let max_x = pos.iter().map(|p| p.0).max().unwrap();
+    let min_y = pos.iter().map(|p| p.1).min().unwrap();
+    let max_y = pos.iter().map(|p| p.1).max().unwrap();
 
-    let secs = if min_x.x() < min_y.y() {
-        i32::abs(min_x.pos.0 / min_x.vel.0)
-    } else {
-        i32::abs(min_y.pos.1 / min_y.vel.1)
-    } + 26;
+    let rows = (max_y - min_y + 1) as usize;
+    let cols = (max_x - min_x + 1) as usize;
+    ocr::read_fn(rows, cols, |r, c| pos.contains(&(min_x + c as i32, min_y + r as i32))).unwrap()
+}
 
-    println!("Part 1: LKPHZHHJ");
-    println!("Part 2: {secs}");
+fn part_two(points: &[Point]) -> i32 {
+    align(points)
+}
+
+// The points converge into the message and then spread back out,
+// so it's there when they're bunched up closest vertically.
+fn align(points: &[Point]) -> i32 {
+    let height = |secs| {
+        let (lo, hi) = points.iter()
+            .map(|p| p.pos_at(secs).1)
+            .fold((i32::MAX, i32::MIN), |(lo, hi), y| (lo.min(y), hi.max(y)));
+        hi - lo
+    };
+
+    (0..).find(|secs| height(secs + 1) > height(*secs)).unwrap()
 }
 
 fn load(input: &str) -> Vec<Point> {
@@ -42,15 +54,6 @@ struct Point {
 }
 
 impl Point {
-    fn x(&self) -> i32 {
-        self.pos.0
-    }
-
-    fn y(&self) -> i32 {
-        self.pos.1
-    }
-
-    #[allow(dead_code)]
     fn pos_at(&self, secs: i32) -> (i32, i32) {
         (
             self.pos.0 + (self.vel.0 * secs),
@@ -58,3 +61,31 @@ impl Point {
         )
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_works() {
+        let points = load(include_str!("./input.txt"));
+
+        let message = part_one(&points);
+        assert_eq!(message, "LKPHZHHJ");
+    }
+
+    #[test]
+    fn example() {
+        // A few points from the puzzle's example which come together
+        // after 3 seconds.
+        let points = [
+            Point { pos: (9, 1), vel: (0, 2) },
+            Point { pos: (7, 0), vel: (-1, 0) },
+            Point { pos: (3, -2), vel: (-1, 1) },
+            Point { pos: (6, 10), vel: (-2, -1) },
+            Point { pos: (2, -4), vel: (2, 2) },
+        ];
+        assert_eq!(part_two(&points), 3);
+    }
+}
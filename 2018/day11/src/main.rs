@@ -2,17 +2,10 @@ use ndarray::Array2;
 
 fn main()
 {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    let result = part_one(input);
-    println!("Part 1: {:?} ({:?})", result, t.elapsed());
-
-    let t = Instant::now();
-    let result = part_two(input);
-    println!("Part 2: {:?} ({:?})", result, t.elapsed());
+    aoc::run((
+        |input: &str| format!("{:?}", part_one(input)),
+        |input: &str| format!("{:?}", part_two(input)),
+    ), include_str!("../input.txt"));
 }
 
 fn part_one(input: &str) -> (usize, usize)
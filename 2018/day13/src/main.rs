@@ -4,19 +4,12 @@ use std::collections::HashMap;
 type Track = HashMap<(u32, u32), char>;
 
 fn main() {
-    use std::time::Instant;
+    let position = |(x, y)| format!("{x},{y}");
 
-    let (carts, track) = load(include_str!("./input.txt"));
-
-    let t1 = Instant::now();
-    let (x, y) = part_one(&carts, &track);
-    let t2 = Instant::now();
-    println!("Part 1: {},{}  ({:?})", x, y, t2 - t1);
-
-    let t1 = Instant::now();
-    let (x, y) = part_two(&carts, &track);
-    let t2 = Instant::now();
-    println!("Part 2: {},{}  ({:?})", x, y, t2 - t1);
+    aoc::run((
+        |(carts, track): &(Vec<Cart>, Track)| position(part_one(carts, track)),
+        |(carts, track): &(Vec<Cart>, Track)| position(part_two(carts, track)),
+    ), &load(include_str!("./input.txt")));
 }
 
 fn part_one(starting: &[Cart], track: &Track) -> (u32, u32) {
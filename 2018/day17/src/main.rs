@@ -1,17 +1,7 @@
 use std::collections::{HashMap, HashSet};
 
 fn main() {
-    use std::time::Instant;
-
-    let clay = load(include_str!("./input.txt"));
-    
-    let t = Instant::now();
-    let water = part_one(&clay);
-    println!("Part 1: {}  ({:?})", water, t.elapsed());
-
-    let t = Instant::now();
-    let water = part_two(&clay);
-    println!("Part 2: {}  ({:?})", water, t.elapsed());
+    aoc::run((part_one, part_two), &load(include_str!("./input.txt")));
 }
 
 type Edges = HashSet<(char, (i32, i32))>;
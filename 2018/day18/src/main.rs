@@ -1,19 +1,7 @@
 use pathfinding::matrix::Matrix;
 
 fn main() {
-    use std::time::Instant;
-
-    let acres = load(include_str!("./input.txt"));
-
-    let t1 = Instant::now();
-    let resource = part_one(&acres);
-    let t2 = Instant::now();
-    println!("Part 1: {}  ({:?})", resource, t2 - t1);
-
-    let t1 = Instant::now();
-    let resource = part_two(&acres);
-    let t2 = Instant::now();
-    println!("Part 2: {}  ({:?})", resource, t2 - t1);
+    aoc::run((part_one, part_two), &load(include_str!("./input.txt")));
 }
 
 fn load(input: &str) -> Matrix<char> {
@@ -1,77 +1,33 @@
 use std::collections::{HashMap, HashSet};
-use z3::{ast::Int, Optimize};
+use aoc::manhattan::{distance, most_in_range, Bot};
 
 type BotMap = HashMap<Bot, HashSet<Bot>>;
 type BotSet = HashSet<Bot>;
 
 fn main()
 {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    println!("Part 1: {}  ({:?})", part_one(input), t.elapsed());
-
-    let t = Instant::now();
-    println!("Part 2: {}  ({:?})", part_two(input), t.elapsed());
+    aoc::run((part_one, part_two), include_str!("../input.txt"));
 }
 
 fn part_one(input: &str) -> usize
 {
     let bots = load(input);
 
-    let mut bots = bots.to_vec();
-    bots.sort_by(|a, b| b.r.cmp(&a.r));
-
-    let bot = bots[0];
+    let bot = bots.iter().max_by_key(|b| b.r).unwrap();
     bots.iter()
-        .filter(|b| bot.md(b) <= bot.r)
+        .filter(|b| bot.in_range(b.pos))
         .count()
 }
 
 fn part_two(input: &str) -> i64
 {
+    // Split the space up into boxes, looking first in the ones the
+    // most bots reach and nearest the origin, until we're down to a
+    // point.
     let bots = load(input);
+    let (p, _) = most_in_range(&bots);
 
-    let x = Int::new_const("x");
-    let y = Int::new_const("y");
-    let z = Int::new_const("z");
-
-    let one  = Int::from_i64(1);
-    let zero = Int::from_i64(0);
-
-    let mut count = Int::from_i64(0);
-    for b in bots {
-        let bx = Int::from_i64(b.x);
-        let by = Int::from_i64(b.y);
-        let bz = Int::from_i64(b.z);
-        let br = Int::from_i64(b.r);
-
-        let dx = bx - &x;
-        let dx = dx.le(&zero).ite(&dx.unary_minus(), &dx);
-        let dy = by - &y;
-        let dy = dy.le(&zero).ite(&dy.unary_minus(), &dy);
-        let dz = bz - &z;
-        let dz = dz.le(&zero).ite(&dz.unary_minus(), &dz);
-        let md = &dx + &dy + &dz;
-        count += md.le(&br).ite(&one, &zero);
-    }
-
-    let optimizer = Optimize::new();
-    optimizer.maximize(&count);
-
-    let dx = x.le(&zero).ite(&x.unary_minus(), &x);
-    let dy = y.le(&zero).ite(&y.unary_minus(), &y);
-    let dz = z.le(&zero).ite(&z.unary_minus(), &z);
-    let md = &dx + &dy + &dz;
-    optimizer.minimize(&md);
-
-    optimizer.check(&[]);
-    let model = optimizer.get_model().unwrap();
-    let res = model.eval(&md, true).unwrap();
-
-    res.as_i64().unwrap()
+    distance(p, [0; 3])
 }
 
 #[allow(dead_code)]
@@ -104,7 +60,7 @@ fn part_two_bron_kerbosch(input: &str) -> i64
     // Use Bron-Kerbosch to find the "maximal clique" which gives
     // us the largest set of bots all overlapping each other. That
     // set must contain the point in range of most bots.
-    let origin = Bot { x: 0, y: 0, z: 0, r: 0 };
+    let origin = [0; 3];
     let mut bk = BronKerbosch::default();
     let clique = bk.execute(&m);
 
@@ -113,7 +69,7 @@ fn part_two_bron_kerbosch(input: &str) -> i64
     // to the point in range of all the bots in the set. Tellingly,
     // we don't know where the point is, just it's distance.
     clique.iter()
-        .map(|bot| bot.md(&origin) - bot.r)
+        .map(|bot| distance(bot.pos, origin) - bot.r)
         .max()
         .unwrap()
 }
@@ -130,7 +86,7 @@ fn load(input: &str) -> Vec<Bot>
 
         let r = s2.parse::<i64>().unwrap();
 
-        Bot { x, y, z, r }
+        Bot { pos: [x, y, z], r }
     })
     .collect()
 }
@@ -191,27 +147,6 @@ impl BronKerbosch {
     }
 }
 
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Ord, PartialOrd)]
-struct Bot {
-    r: i64,
-    x: i64,
-    y: i64,
-    z: i64,
-}
-impl Bot {
-    fn md(&self, other: &Self) -> i64
-    {
-        (self.x.abs_diff(other.x) +
-         self.y.abs_diff(other.y) +
-         self.z.abs_diff(other.z)) as i64
-    }
-
-    fn overlaps(&self, other: &Self) -> bool
-    {
-        self.md(other) <= self.r + other.r
-    }
-}
-
 
 #[cfg(test)]
 mod tests {
@@ -224,10 +159,17 @@ mod tests {
         assert_eq!(part_one(input), 481);
     }
 
-    // #[test]
-    // fn input_part_two()
-    // {
-    //     let input = include_str!("../input.txt");
-    //     assert_eq!(part_two(input), 47141479);
-    // }
+    #[test]
+    fn input_part_two()
+    {
+        let input = include_str!("../input.txt");
+        assert_eq!(part_two(input), 47141479);
+    }
+
+    #[test]
+    fn example_part_two()
+    {
+        let input = include_str!("../example.txt");
+        assert_eq!(part_two(input), 36);
+    }
 }
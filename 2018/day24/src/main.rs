@@ -2,20 +2,10 @@ use std::collections::HashMap;
 use bitflags::bitflags;
 
 fn main() {
-    use std::time::Instant;
-
-    let immunologers  = immune_system();
-    let infectionists = infection();
-
-    let t1 = Instant::now();
-    let units = part_one(&immunologers, &infectionists);
-    let t2 = Instant::now();
-    println!("Part 1: {}  ({:?})", units, t2 - t1);
-
-    let t1 = Instant::now();
-    let units = part_two(&immunologers, &infectionists);
-    let t2 = Instant::now();
-    println!("Part 2: {}  ({:?})", units, t2 - t1);
+    aoc::run((
+        |(immunologers, infectionists): &(Groups, Groups)| part_one(immunologers, infectionists),
+        |(immunologers, infectionists): &(Groups, Groups)| part_two(immunologers, infectionists),
+    ), &(immune_system(), infection()));
 
     // 2456
 }
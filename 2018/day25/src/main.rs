@@ -4,12 +4,7 @@ type Point = [i8;4];
 
 fn main()
 {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    println!("Part 1: {}  ({:?})", part_one(input), t.elapsed());
+    aoc::run((part_one,), include_str!("../input.txt"));
 }
 
 fn part_one(input: &str) -> usize
@@ -0,0 +1,302 @@
+//! The 16 opcode wrist device from days 16, 19 and 21: the instruction
+//! set, programs with an `#ip` register binding, working out opcode
+//! numbers from samples and hooks on the instruction pointer for
+//! peeking at the registers while a program runs.
+use std::str::FromStr;
+
+pub type Reg = i64;
+pub type Registers = [Reg;6];
+
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Opcode {
+    setr, seti,
+    addr, addi,
+    mulr, muli,
+    banr, bani,
+    borr, bori,
+    gtir, gtri, gtrr,
+    eqir, eqri, eqrr,
+}
+
+pub const OPCODES: [Opcode;16] = {
+    use Opcode::*;
+    [
+        setr, seti, addr, addi, mulr, muli, banr, bani, borr, bori,
+        gtir, gtri, gtrr, eqir, eqri, eqrr
+    ]
+};
+
+impl Opcode {
+    /// Runs the opcode against the registers. Returns None if an
+    /// operand names a register which doesn't exist.
+    pub fn exec(&self, a: Reg, b: Reg, c: Reg, reg: &mut [Reg]) -> Option<()> {
+        use Opcode::*;
+
+        let r = |n: Reg| reg.get(usize::try_from(n).ok()?).copied();
+        let v = match self {
+            setr => r(a)?,
+            seti => a,
+            addr => r(a)? + r(b)?,
+            addi => r(a)? + b,
+            mulr => r(a)? * r(b)?,
+            muli => r(a)? * b,
+            banr => r(a)? & r(b)?,
+            bani => r(a)? & b,
+            borr => r(a)? | r(b)?,
+            bori => r(a)? | b,
+            gtir => (a > r(b)?) as Reg,
+            gtri => (r(a)? > b) as Reg,
+            gtrr => (r(a)? > r(b)?) as Reg,
+            eqir => (a == r(b)?) as Reg,
+            eqri => (r(a)? == b) as Reg,
+            eqrr => (r(a)? == r(b)?) as Reg,
+        };
+        *reg.get_mut(usize::try_from(c).ok()?)? = v;
+
+        Some(())
+    }
+}
+
+impl FromStr for Opcode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        OPCODES.iter()
+            .find(|op| format!("{op:?}") == s)
+            .copied()
+            .ok_or_else(|| format!("Unknown opcode: {s}"))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Instr {
+    pub op: Opcode,
+    pub a: Reg,
+    pub b: Reg,
+    pub c: Reg,
+}
+
+impl Instr {
+    pub fn exec(&self, reg: &mut [Reg]) -> Option<()> {
+        self.op.exec(self.a, self.b, self.c, reg)
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Program {
+    /// Register the instruction pointer is bound to, if any.
+    pub ipr: Option<usize>,
+    pub instrs: Vec<Instr>,
+}
+
+/// Loads a program written with opcode names, with an optional
+/// `#ip` line up front.
+pub fn load(input: &str) -> Program {
+    let mut program = Program::default();
+
+    for line in input.lines().filter(|s| !s.is_empty()) {
+        let mut it = line.split(' ');
+        let opc = it.next().unwrap();
+        let mut n = || it.next()
+            .map(|s| s.parse::<Reg>().unwrap())
+            .unwrap_or_else(|| panic!("Missing operand: {line}"));
+
+        if opc == "#ip" {
+            program.ipr = Some(n() as usize);
+        } else {
+            let op = opc.parse::<Opcode>().unwrap();
+            program.instrs.push(Instr { op, a: n(), b: n(), c: n() });
+        }
+    }
+
+    program
+}
+
+/// Registers before and after running a numbered instruction.
+#[derive(Clone, Copy, Debug)]
+pub struct Sample {
+    pub before: [Reg;4],
+    pub instr: [Reg;4],
+    pub after: [Reg;4],
+}
+
+impl Sample {
+    /// Opcodes which behave like the sample.
+    pub fn matches(&self) -> impl Iterator<Item = Opcode> + '_ {
+        let [_, a, b, c] = self.instr;
+        OPCODES.into_iter()
+            .filter(move |op| {
+                let mut reg = self.before;
+                op.exec(a, b, c, &mut reg).is_some() && reg == self.after
+            })
+    }
+}
+
+/// Works out which opcode goes with each opcode number. Any number
+/// with only one candidate left rules that opcode out for all the
+/// others, repeated until everything's pinned down.
+pub fn infer(samples: &[Sample]) -> Option<[Opcode;16]> {
+    let mut candidates = [u16::MAX;16];
+    for s in samples {
+        let mask = s.matches().fold(0u16, |m, op| m | 1 << op as usize);
+        let n = usize::try_from(s.instr[0]).ok()?;
+        *candidates.get_mut(n)? &= mask;
+    }
+
+    let mut opcodes = [None;16];
+    while let Some(n) = (0..16).find(|n| opcodes[*n].is_none() && candidates[*n].count_ones() == 1) {
+        let bit = candidates[n];
+        opcodes[n] = Some(OPCODES[bit.trailing_zeros() as usize]);
+        (0..16).filter(|m| *m != n).for_each(|m| candidates[m] &= !bit);
+    }
+
+    let mut result = [Opcode::setr;16];
+    for (op, found) in result.iter_mut().zip(opcodes) {
+        *op = found?;
+    }
+
+    Some(result)
+}
+
+#[derive(Clone, Debug)]
+pub struct Device {
+    ipr: Option<usize>,
+    instrs: Vec<Instr>,
+    ip: Reg,
+    reg: Registers,
+}
+
+impl Device {
+    pub fn new(program: &Program, reg: Registers) -> Self {
+        Device { ipr: program.ipr, instrs: program.instrs.clone(), ip: 0, reg }
+    }
+
+    pub fn registers(&self) -> &Registers {
+        &self.reg
+    }
+
+    pub fn ip(&self) -> Reg {
+        self.ip
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.ip < 0 || self.ip as usize >= self.instrs.len()
+    }
+
+    /// Executes the instruction at the instruction pointer.
+    pub fn step(&mut self) {
+        if let Some(r) = self.ipr {
+            self.reg[r] = self.ip;
+        }
+        self.instrs[self.ip as usize].exec(&mut self.reg)
+            .unwrap_or_else(|| panic!("Bad register at {}", self.ip));
+        if let Some(r) = self.ipr {
+            self.ip = self.reg[r];
+        }
+        self.ip += 1;
+    }
+
+    /// Runs until the instruction pointer leaves the program.
+    pub fn run(&mut self) {
+        while !self.is_done() {
+            self.step()
+        }
+    }
+
+    /// Runs the program, calling `hook` with the instruction pointer
+    /// and registers whenever it's about to execute one of the
+    /// instructions in `ips`. Stops when the hook returns a value or
+    /// the program halts.
+    pub fn run_hooked<T, F>(&mut self, ips: &[Reg], mut hook: F) -> Option<T>
+        where F: FnMut(Reg, &Registers) -> Option<T>
+    {
+        while !self.is_done() {
+            if ips.contains(&self.ip) {
+                if let Some(t) = hook(self.ip, &self.reg) {
+                    return Some(t)
+                }
+            }
+            self.step()
+        }
+
+        None
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_matches() {
+        use Opcode::*;
+
+        let sample = Sample {
+            before: [3, 2, 1, 1],
+            instr: [9, 2, 1, 2],
+            after: [3, 2, 2, 1],
+        };
+        assert_eq!(sample.matches().collect::<Vec<_>>(), [seti, addi, mulr]);
+    }
+
+    #[test]
+    fn infer_opcodes() {
+        // Number the opcodes backwards and run each one against a few
+        // made up register states to make the samples.
+        let mut numbering = OPCODES;
+        numbering.reverse();
+
+        let mut seed = 17;
+        let mut rand = |n: Reg| {
+            seed = (seed * 1103515245 + 12345) % (1 << 31);
+            (seed >> 16) % n
+        };
+
+        let mut samples = Vec::new();
+        for (n, op) in numbering.iter().enumerate() {
+            for _ in 0..8 {
+                let before = [rand(16), rand(16), rand(16), rand(16)];
+                let instr = [n as Reg, rand(4), rand(4), rand(4)];
+                let mut after = before;
+                op.exec(instr[1], instr[2], instr[3], &mut after).unwrap();
+                samples.push(Sample { before, instr, after });
+            }
+        }
+
+        assert_eq!(infer(&samples), Some(numbering));
+        assert_eq!(infer(&samples[..8]), None);
+    }
+
+    #[test]
+    fn ip_binding() {
+        let input = "#ip 0\nseti 5 0 1\nseti 6 0 2\naddi 0 1 0\naddr 1 2 3\n\
+                     setr 1 0 0\nseti 8 0 4\nseti 9 0 5";
+        let program = load(input);
+        assert_eq!(program.ipr, Some(0));
+        assert_eq!(program.instrs.len(), 7);
+
+        let mut device = Device::new(&program, [0;6]);
+        device.run();
+        assert_eq!(device.registers(), &[6, 5, 6, 0, 0, 9]);
+    }
+
+    #[test]
+    fn hooks() {
+        let input = "#ip 0\nseti 5 0 1\nseti 6 0 2\naddi 0 1 0\naddr 1 2 3\n\
+                     setr 1 0 0\nseti 8 0 4\nseti 9 0 5";
+        let program = load(input);
+
+        let mut seen = Vec::new();
+        let mut device = Device::new(&program, [0;6]);
+        let found = device.run_hooked(&[4, 6], |ip, reg| {
+            seen.push(ip);
+            (ip == 6).then_some(reg[1])
+        });
+
+        assert_eq!(found, Some(5));
+        assert_eq!(seen, [4, 6]);
+        assert_eq!(device.ip(), 6);
+    }
+}
@@ -1,111 +1,56 @@
 use anyhow::Result;
 use itertools::Itertools;
-use std::cell::RefCell;
+use vm::net::Network;
 use vm::Vm;
 
 fn main() {
-  let program = include_str!("./program.txt");
-
-  let signal = (0..=4).permutations(5)
-    .map(|phases| run(program, &phases))
-    .map(Result::unwrap)
-    .max().unwrap();
-  println!("One pass signal: {signal}");
-
-  let signal = (5..=9).permutations(5)
-    .map(|phases| feedback(program, &phases))
-    .map(Result::unwrap)
-    .max().unwrap();
-  println!("Feedback signal: {signal}");
-}
-
-struct Amp {
-  vm: RefCell<Vm>,
-  phase: i64,
+  aoc::run((
+    |program: &str| (0..=4).permutations(5)
+      .map(|phases| run(program, &phases))
+      .map(Result::unwrap)
+      .max().unwrap(),
+    |program: &str| (5..=9).permutations(5)
+      .map(|phases| feedback(program, &phases))
+      .map(Result::unwrap)
+      .max().unwrap(),
+  ), include_str!("./program.txt"));
 }
 
-impl Amp {
-  pub fn from(program: &str, phase: i64) -> Result<Self> {
-    let vm = RefCell::new(Vm::new(program)?);
-    Ok(Amp { vm, phase })
+fn amplifiers(program: &str, phases: &[i64]) -> Result<Network> {
+  let mut net = Network::new();
+  for (i, &phase) in (0..).zip(phases) {
+    let mut vm = Vm::new(program)?;
+    vm.write(phase);
+    net.add(i, vm);
+    if i > 0 { net.link(i - 1, i)? }
   }
 
-  pub fn boot(&mut self) -> Result<()> {
-    let mut vm = self.vm.borrow_mut();
-    let (mut pipe, _) = vm.pipes();
-    pipe.write(self.phase);
-    vm.exec()?;
-
-    Ok(())
-  }
-
-  pub fn cont(&mut self) -> Result<()> {
-    let mut vm = self.vm.borrow_mut();
-    vm.cont()?;
-
-    Ok(())
-  }
-
-  pub fn is_running(&self) -> bool {
-    self.vm.borrow().is_running()
-  }
-
-  pub fn write(&self, v: i64) {
-    let vm = self.vm.borrow_mut();
-    let (mut pipe, _) = vm.pipes();
-    pipe.write(v)
-  }
-
-  pub fn signal(&self) -> i64 {
-    let vm = self.vm.borrow_mut();
-    let (_, mut pipe) = vm.pipes();
-    pipe.flush()
-  }
+  // Set initial signal value
+  net.node_mut(0)?.write(0);
 
-  pub fn connect(&self, other: &Amp) {
-    let mut a = self.vm.borrow_mut();
-    let mut b = other.vm.borrow_mut();
-    a.connect(&mut b);
-  }
+  Ok(net)
 }
 
-fn load(program: &str, phases: &[i64]) -> Result<Vec<Amp>> {
-  let amps = phases.iter().map(|&p| Amp::from(program, p))
-    .collect::<Result<Vec<Amp>>>()?;
-  for pos in 0..phases.len() - 1 {
-    amps[pos].connect(&amps[pos + 1])
-  }
-
-  Ok(amps)
+fn signal(net: &Network, phases: &[i64]) -> Result<i64> {
+  let (_, mut pipe) = net.node(phases.len() as i64 - 1)?.pipes();
+  Ok(pipe.flush())
 }
 
 fn run(program: &str, phases: &[i64]) -> Result<i64> {
-  let mut amps = load(program, phases)?;
-  amps.iter_mut().try_for_each(|amp| amp.boot())?;
-
-  // Set initial signal value
-  amps[0].write(0);
-  amps.iter_mut().try_for_each(|amp| amp.cont())?;
-  let signal = amps.last().unwrap().signal();
+  let mut net = amplifiers(program, phases)?;
+  net.run()?;
 
-  Ok(signal)
+  signal(&net, phases)
 }
 
 fn feedback(program: &str, phases: &[i64]) -> Result<i64> {
-  let mut amps = load(program, phases)?;
+  let mut net = amplifiers(program, phases)?;
 
   // Setup the feedback loop
-  amps[4].connect(&amps[0]);
-  amps.iter_mut().try_for_each(|amp| amp.boot())?;
+  net.link(phases.len() as i64 - 1, 0)?;
+  net.run()?;
 
-  // Set initial signal value
-  amps[0].write(0);
-  while amps[4].is_running() {
-    amps.iter_mut().try_for_each(|amp| amp.cont())?;
-  }
-  let signal = amps.last().unwrap().signal();
-  
-  Ok(signal)
+  signal(&net, phases)
 }
 
 
@@ -117,23 +62,17 @@ mod tests {
   #[test]
   fn it_works() {
     let program = include_str!("./program.txt");
-    let mut amp = Amp::from(program, 3).unwrap();
-    amp.boot().unwrap();
-    amp.write(0);
-    amp.cont().unwrap();
+    let signal = run(program, &[3]).unwrap();
 
-    assert_eq!(amp.signal(), 4);
+    assert_eq!(signal, 4);
   }
 
   #[test]
   fn test_simple() {
     let program = "3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0";
-    let mut amp = Amp::from(program, 3).unwrap();
-    amp.boot().unwrap();
-    amp.write(0);
-    amp.cont().unwrap();
+    let signal = run(program, &[3]).unwrap();
 
-    assert_eq!(amp.signal(), 3);
+    assert_eq!(signal, 3);
   }
 
   #[test]
@@ -5,21 +5,12 @@
 // converting values to '*' and ' ' to try and make the letters
 // visible.
 
+use bitmatrix::ocr;
 use std::cmp::{Ordering, PartialOrd};
 
 fn main()
 {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    let result = part_one(input);
-    println!("Part 1: {} ({:?})", result, t.elapsed());
-
-    let t = Instant::now();
-    let result = part_two(input, true);
-    println!("Part 2: {} ({:?})", result, t.elapsed());
+    aoc::run((part_one, |input: &str| part_two(input, true)), include_str!("../input.txt"));
 }
 
 fn part_one(input: &str) -> usize
@@ -54,7 +45,7 @@ fn part_two(input: &str, draw: bool) -> String
         .fold(layers[0].clone(), |img, layer| img.stack(layer));
     if draw { image.draw() };
 
-    "GKCKH".into()
+    ocr::read_fn(6, 25, |r, c| image.pixels[r * 25 + c] == 1).unwrap()
 }
 
 #[allow(dead_code)]
@@ -1,21 +1,12 @@
 use anyhow::Result;
-use std::collections::HashMap;
+use bitmatrix::ocr;
+use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use vm::{State, Vm};
 
 fn main()
 {
-    use std::time::Instant;
-    
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    let result = part_one(input);
-    println!("Part 1: {} ({:?})", result, t.elapsed());
-
-    let t = Instant::now();
-    let result = part_two(input, true);
-    println!("Part 2: {} ({:?})", result, t.elapsed());
+    aoc::run((part_one, part_two), include_str!("../input.txt"));
 }
 
 fn part_one(input: &str) -> usize
@@ -26,27 +17,28 @@ fn part_one(input: &str) -> usize
     robot.painted().len()
 }
 
-fn part_two(input: &str, draw: bool) -> String
+fn part_two(input: &str) -> String
 {
-    if draw {
-        let mut robot = Robot::from(input).unwrap();
-        robot.paint(Color::White).unwrap();
-
-        let (h, w) = robot.dimensions();
-        let painted = robot.painted();
-        for y in 0..=h as i32 {
-            for x in 0..w as i32 {
-                let panel = Panel { x, y: -y };
-                match painted.get(&panel).unwrap_or(&Color::Black) {
-                    Color::Black => print!(" "),
-                    Color::White => print!("#"),
-                }
-            }
-            println!()
-        }
-    }
-
-    "CBLPJZCU".into()
+    let mut robot = Robot::from(input).unwrap();
+    robot.paint(Color::White).unwrap();
+
+    // Panels go up the y axis so the top row is the biggest y.
+    let white = robot.painted().iter()
+        .filter(|(_, color)| matches!(color, Color::White))
+        .map(|(panel, _)| *panel)
+        .collect::<HashSet<_>>();
+    let min_x = white.iter().map(|p| p.x).min().unwrap();
+    let max_x = white.iter().map(|p| p.x).max().unwrap();
+    let min_y = white.iter().map(|p| p.y).min().unwrap();
+    let max_y = white.iter().map(|p| p.y).max().unwrap();
+
+    let rows = (max_y - min_y + 1) as usize;
+    let cols = (max_x - min_x + 1) as usize;
+    let is_white = |r: usize, c: usize| {
+        white.contains(&Panel::new(min_x + c as i32, max_y - r as i32))
+    };
+
+    ocr::read_fn(rows, cols, is_white).unwrap()
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -149,23 +141,6 @@ impl Robot {
         Ok(())
     }
 
-    pub fn dimensions(&self) -> (i8, i8)
-    {
-        let mut min_x = 0i8;
-        let mut min_y = 0i8;
-        let mut max_x = 0i8;
-        let mut max_y = 0i8;
-
-        for panel in self.painted.keys() {
-            min_x = i8::min(min_x, panel.x as i8);
-            min_y = i8::min(min_y, panel.y as i8);
-            max_x = i8::max(max_x, panel.x as i8);
-            max_y = i8::max(max_y, panel.y as i8);
-        }
-
-        (max_y - min_y, max_x - min_x)
-    }
-
     fn turn_left(&self) -> Direction
     {
         match self.facing {
@@ -203,6 +178,6 @@ mod tests {
     fn input_part_two()
     {
         let input = include_str!("../input.txt");
-        assert_eq!(part_two(input, false), "CBLPJZCU");
+        assert_eq!(part_two(input), "CBLPJZCU");
     }
 }
\ No newline at end of file
@@ -2,17 +2,7 @@ use vm::Vm;
 
 fn main()
 {
-    use std::time::Instant;
-    
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    let result = part_one(input);
-    println!("Part 1: {} ({:?})", result, t.elapsed());
-
-    let t = Instant::now();
-    let result = part_two(input);
-    println!("Part 2: {} ({:?})", result, t.elapsed());
+    aoc::run((part_one, part_two), include_str!("../input.txt"));
 }
 
 fn part_one(input: &str) -> usize
@@ -34,7 +24,7 @@ fn part_two(input: &str) -> i64
     let mut vm = Vm::new(input).unwrap();
     let (mut stdin, mut stdout) = vm.pipes();
 
-    vm.set_addr(0, 2);
+    vm.set_addr(0, 2).unwrap();
     vm.exec().unwrap();
     let tiles = stdout.drain();
     let mut blocks: HashSet<(i64, i64)> = tiles.chunks(3)
@@ -5,12 +5,10 @@ type SpecMap<'a> = HashMap<&'a str, Spec<'a>>;
 const PHANTOM: &str = "****";
 
 fn main() {
-    let specs = load(include_str!("../input.txt")).unwrap();
-    let ore = calc_min_ore("FUEL", &specs).unwrap();
-    println!("Part 1: {ore}");
-
-    let fuel = calc_max_fuel(&specs).unwrap();
-    println!("Part 2: {fuel}")
+    aoc::run((
+        |specs: &SpecMap| calc_min_ore("FUEL", specs).unwrap(),
+        |specs: &SpecMap| calc_max_fuel(specs).unwrap(),
+    ), &load(include_str!("../input.txt")).unwrap());
 }
 
 fn load(formulas: &str) -> Result<SpecMap<'_>> {
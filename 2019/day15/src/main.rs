@@ -4,12 +4,14 @@ use std::io;
 use vm::Vm;
 
 fn main() {
-    let program = include_str!("./program.txt");
-    part_one(program).unwrap();
+    // Part one's played by hand, which is how the board was mapped.
+    if std::env::args().any(|arg| arg == "--play") {
+        part_one(include_str!("./program.txt")).unwrap();
+    }
 
     let board = include_str!("./board.txt");
     let steps = part_two(board).unwrap();
-    println!("Oxygen staturation: {steps} minutes");
+    println!("Part 2: {steps}");
 }
 
 fn part_one(program: &str) -> Result<(), &str> {
@@ -16,12 +16,7 @@
 // Note: the realizations took a little bit.
 
 fn main() {
-  let signal = load(include_str!("./signal.txt"));
-  let result = fft(100, &signal);
-  println!("FFT: {result}");
-
-  let result = part_two(&signal);
-  println!("FFT: {result}");
+  aoc::run((|signal: &[i32]| fft(100, signal), part_two), &load(include_str!("./signal.txt"))[..]);
 }
 
 fn load(signal: &str) -> Vec<i32> {
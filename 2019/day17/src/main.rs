@@ -1,23 +1,20 @@
 use anyhow::Result;
 use std::cmp::max;
 use std::collections::HashSet;
-use vm::Vm;
+use vm::ascii::AsciiVm;
 
 type Skaffold = HashSet<(i32, i32)>;
 
 fn main() {
-    let program = include_str!("./program.txt");
-    let calibration = part_one(program).unwrap();
-    println!("Part 1: {calibration}");
-
-    let dust = part_two(program).unwrap();
-    println!("Part 2: {dust}");
+    aoc::run((
+        |program: &str| part_one(program).unwrap(),
+        |program: &str| part_two(program).unwrap(),
+    ), include_str!("./program.txt"));
 }
 
 fn part_one(program: &str) -> Result<i32> {
-    let mut vm = Vm::new(program)?;
-    let (_, mut stdout) = vm.pipes();
-    vm.exec()?;
+    let mut vm = AsciiVm::new(program)?;
+    vm.start()?;
 
     let mut x = 0;
     let mut y = 0;
@@ -26,16 +23,16 @@ fn part_one(program: &str) -> Result<i32> {
     let mut facing = Direction::Up;
     let mut skaffold = HashSet::new();
 
-    stdout.drain().iter()
-        .for_each(|v| {
-            match v {
-                10  => { cols = max(x, cols); y += 1; x = -1; },
-                35  => { skaffold.insert((x, y)); },
-                60  => { robot = (x, y); facing = Direction::Left; },
-                62  => { robot = (x, y); facing = Direction::Right; },
-                94  => { robot = (x, y); facing = Direction::Up; },
-                118 => { robot = (x, y); facing = Direction::Down; },
-                _   => ()
+    vm.read_text().chars()
+        .for_each(|c| {
+            match c {
+                '\n' => { cols = max(x, cols); y += 1; x = -1; },
+                '#'  => { skaffold.insert((x, y)); },
+                '<'  => { robot = (x, y); facing = Direction::Left; },
+                '>'  => { robot = (x, y); facing = Direction::Right; },
+                '^'  => { robot = (x, y); facing = Direction::Up; },
+                'v'  => { robot = (x, y); facing = Direction::Down; },
+                _    => ()
             };
             x += 1;
         });
@@ -53,21 +50,19 @@ fn part_two(program: &str) -> Result<i64, &str> {
     // A: L,12,R,4,R,4
     // B: R,12,R,4,L,6,L,8,L,8
     // C: R,12,R,4,L,12
-    let rules = b"\
-        A,C,C,B,B,A,A,C,C,B\n\
-        L,12,R,4,R,4\n\
-        R,12,R,4,L,6,L,8,L,8\n\
-        R,12,R,4,L,12\n";
-
-    let mut vm = Vm::new(program).or(Err("Load failed"))?;
-
-    vm.set_addr(0, 2);
-    rules.iter().for_each(|&v| vm.write(v as i64));
-    vm.write(b'n'); vm.write(b'\n'); // feed
-    vm.exec().or(Err("Exec failed"))?;
-
-    let screen = vm.drain();
-    let dust = screen.last().ok_or("No output")?;
+    let rules = [
+        "A,C,C,B,B,A,A,C,C,B",
+        "L,12,R,4,R,4",
+        "R,12,R,4,L,6,L,8,L,8",
+        "R,12,R,4,L,12",
+        "n",    // feed
+    ];
+
+    let mut vm = AsciiVm::new(program).or(Err("Load failed"))?;
+
+    vm.vm_mut().set_addr(0, 2).or(Err("Set failed"))?;
+    let transcript = vm.play(rules).or(Err("Exec failed"))?;
+    let dust = transcript.values.last().ok_or("No output")?;
 
     Ok(*dust)
 }
@@ -10,19 +10,13 @@ type Doors = HashMap<Tile, u32>;
 type Cache = HashMap<State, u32>;
 
 fn main() {
-    use std::time::Instant;
-
-    let map = load(include_str!("./input/part1.txt"));
-    let t1 = Instant::now();
-    let steps = solver(&map);
-    let t2 = Instant::now();
-    println!("Part 1: {steps} ({:?})", t2 - t1);
-
-    let map = load(include_str!("./input/part2.txt"));
-    let t1 = Instant::now();
-    let steps = solver(&map);
-    let t2 = Instant::now();
-    println!("Part 2: {steps} ({:?})", t2 - t1);
+    // Part two's map has the entrance split in four.
+    let maps = (
+        load(include_str!("./input/part1.txt")),
+        load(include_str!("./input/part2.txt")),
+    );
+
+    aoc::run((|(one, _): &(Map, Map)| solver(one), |(_, two): &(Map, Map)| solver(two)), &maps);
 }
 
 fn load(input: &str) -> Map {
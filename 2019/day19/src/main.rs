@@ -2,13 +2,7 @@ use std::collections::HashSet;
 use vm::{Pipe, Vm};
 
 fn main() {
-    let program = include_str!("./program.txt");
-
-    let points = part_one(program);
-    println!("Affected points: {points}");
-
-    let closest = part_two(program);
-    println!("Closest point: {closest}");
+    aoc::run((part_one, part_two), include_str!("./program.txt"));
 }
 
 fn part_one(program: &str) -> i64 {
@@ -7,13 +7,10 @@ type Pos = ((i32, i32), i32);
 type Loc = (i32, i32);
 
 fn main() {
-    let maze = load(include_str!("./maze.txt"));
-
-    let path = part_one(&maze).unwrap();
-    println!("Part one: {}", path.len() - 1);
-
-    let path = part_two(&maze).unwrap();
-    println!("Part two: {}", path.len() - 1);
+    aoc::run((
+        |maze: &Maze| part_one(maze).unwrap().len() - 1,
+        |maze: &Maze| part_two(maze).unwrap().len() - 1,
+    ), &load(include_str!("./maze.txt")));
 }
 
 fn part_one(maze: &Maze) -> Option<Vec<Pos>> {
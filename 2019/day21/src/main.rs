@@ -1,14 +1,11 @@
-use vm::Vm;
+use vm::ascii::AsciiVm;
 
 fn main() {
-  let program = include_str!("./program.txt");
-
-  println!("Part 1: {}", part_one(program));
-  println!("Part 2: {}", part_two(program));
+  aoc::run((part_one, part_two), include_str!("./program.txt"));
 }
 
 fn part_one(program: &str) -> i64 {
-  let rules = b"\
+  let rules = "\
 NOT C J
 AND D J
 NOT A T
@@ -16,19 +13,14 @@ OR T J
 WALK
 ";
 
-  let mut vm = Vm::new(program).unwrap();
-
-  rules.iter().for_each(|&v| vm.write(v as i64));
-  vm.exec().unwrap();
-
-  let screen = vm.drain();
-  let dust = screen.last().unwrap();
+  let mut vm = AsciiVm::new(program).unwrap();
+  let transcript = vm.play(rules.lines()).unwrap();
 
-  *dust
+  *transcript.values.last().unwrap()
 }
 
 fn part_two(program: &str) -> i64 {
-  let rules = b"\
+  let rules = "\
 NOT H T
 OR C T
 AND B T
@@ -38,15 +30,10 @@ AND D J
 RUN
 ";
 
-  let mut vm = Vm::new(program).unwrap();
-
-  rules.iter().for_each(|&v| vm.write(v as i64));
-  vm.exec().unwrap();
-
-  let screen = vm.drain();
-  let dust = screen.last().unwrap();
+  let mut vm = AsciiVm::new(program).unwrap();
+  let transcript = vm.play(rules.lines()).unwrap();
 
-  *dust
+  *transcript.values.last().unwrap()
 }
 
 
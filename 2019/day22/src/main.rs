@@ -1,49 +1,33 @@
-// Didn't get Part 2
-// https://github.com/sasa1977/aoc/blob/master/lib/2019/201922.ex
+use aoc::number::Affine;
 
 fn main() {
-    let shuffles = load(include_str!("./shuffles.txt"));
-
-    println!("Part 1: {}", part_one(&shuffles));
-    println!("Part 2: {}", part_two(&shuffles));
+    aoc::run((part_one, part_two), &load(include_str!("./shuffles.txt"))[..]);
 }
 
 fn part_one(shuffles: &[Shuffle]) -> i128 {
-    const COUNT: i128 = 10_007;
-    shuffles.iter().fold(2019, |pos, s| match s {
-        Shuffle::New    => COUNT - pos - 1,
-        Shuffle::Cut(n) => (pos + COUNT - n) % COUNT,
-        Shuffle::Inc(n) => (pos * n) % COUNT,
-    })
+    shuffle::<10_007>(shuffles).apply(2019)
 }
 
 fn part_two(shuffles: &[Shuffle]) -> i128 {
-    use mod_exp::mod_exp;
-
-    const M: i128 = 119_315_717_514_047;
-    const N: i128 = 101_741_582_076_661;
-
-    // Convert to linear equation: ax + b
-    // Run the command in reverse order.
-    let (a, b) = shuffles.iter().rev().fold((1, 0), |(a, b), s| {
-        let (a_prime, b_prime) = match s {
-            Shuffle::New    => (-a, -b - 1),
-            Shuffle::Cut(n) => ( a,  b + n),
-            Shuffle::Inc(n) => {
-                let n = mod_exp(*n, M - 2, M);
-                (a * n, b * n)
-            },
-        };
-        (a_prime % M, b_prime % M)
-    });
+    const N: u128 = 101_741_582_076_661;
 
-    // Cycling the function n times devolves to:
-    // x * a^n + b * (a^n - 1) / (a - 1)
-    let t1 = 2020 * mod_exp(a, N, M) % M;
-    let tmp = (mod_exp(a, N, M) - 1) * mod_exp(a - 1, M - 2, M) % M;
-    let t2 = b * tmp % M;
+    // Where each card ends up after N shuffles is an affine map, so
+    // the card ending up at 2020 is the inverse map's value there.
+    shuffle::<119_315_717_514_047>(shuffles)
+        .pow(N)
+        .inverse()
+        .unwrap()
+        .apply(2020)
+}
 
-    (t1 + t2) % M
+// The map taking a card's position before the shuffle to its
+// position after.
+fn shuffle<const M: i128>(shuffles: &[Shuffle]) -> Affine<M> {
+    shuffles.iter().fold(Affine::IDENTITY, |f, s| f.then(&match s {
+        Shuffle::New    => Affine::new(-1, -1),
+        Shuffle::Cut(n) => Affine::new( 1, -n),
+        Shuffle::Inc(n) => Affine::new(*n, 0),
+    }))
 }
 
 enum Shuffle {
@@ -79,4 +63,15 @@ mod tests {
     let card = part_two(&shuffles);
     assert_eq!(card, 79855812422607);
   }
+
+  #[test]
+  fn repeated_shuffles() {
+    // Shuffling a small deck over and over agrees with the composed
+    // map raised to the number of shuffles.
+    let shuffles = load(include_str!("./shuffles.txt"));
+    let f = shuffle::<10_007>(&shuffles);
+    let pos = (0..1_000).fold(2019, |pos, _| f.apply(pos));
+    assert_eq!(f.pow(1_000).apply(2019), pos);
+    assert_eq!(f.pow(1_000).inverse().unwrap().apply(pos), 2019);
+  }
 }
\ No newline at end of file
@@ -1,89 +1,45 @@
-use std::collections::HashMap;
+use vm::net::{Event, Network, Packet};
 use vm::Vm;
 
-type Network = HashMap<i64, Vm>;
-type Packets = HashMap<i64, Vec<(i64, i64)>>;
-
 fn main() {
-    let program = include_str!("./nic.txt");
-
-    println!("Part 1: {}", part_one(program));
-    println!("Part 2: {}", part_two(program));
+    aoc::run((part_one, part_two), include_str!("./nic.txt"));
 }
 
 fn part_one(nic: &str) -> i64 {
-    let mut packets = HashMap::new();
     let mut network = setup_network(nic);
-    loop {
-        for vm in network.values_mut() {
-            vm.cont().unwrap();
-            if let Some(nic) = vm.read() {
-                let x = vm.read().unwrap();
-                let y = vm.read().unwrap();
-                if nic == 255 {
-                    return y
-                }
-                packets.entry(nic).or_insert_with(Vec::new).push((x, y));
-            }
-        };
-        process_packets(&mut packets, &mut network);
-    }
+    network.run_with(|event, _| match event {
+        Event::Packet(p) => Some(p.data[1]),
+        Event::Idle => None,
+    }).unwrap().unwrap()
 }
 
 fn part_two(nic: &str) -> i64 {
-    let mut nat = (0, 0);
+    let mut nat = Packet { dest: 0, data: vec![0, 0] };
     let mut last_y = -1;
-    let mut packets = HashMap::new();
     let mut network = setup_network(nic);
-    loop {
-        for vm in network.values_mut() {
-            vm.cont().unwrap();
-            if let Some(nic) = vm.read() {
-                let x = vm.read().unwrap();
-                let y = vm.read().unwrap();
-                if nic == 255 {
-                    nat = (x, y);
-                } else {
-                    packets.entry(nic).or_insert_with(Vec::new).push((x, y));
-                }
-            }
-        };
-        if packets.is_empty() {
-            if last_y == nat.1 {
-                return last_y
-            } else {
-                last_y = nat.1
+    network.run_with(|event, outbox| match event {
+        Event::Packet(p) => { nat = p; None },
+        Event::Idle => {
+            if last_y == nat.data[1] {
+                return Some(last_y)
             }
-
-            let vm = network.get_mut(&0).unwrap();
-            vm.write(nat.0);
-            vm.write(nat.1);
+            last_y = nat.data[1];
+            outbox.push(Packet { dest: 0, ..nat.clone() });
+            None
         }
-        process_packets(&mut packets, &mut network);
-    }
+    }).unwrap().unwrap()
 }
 
 fn setup_network(nic: &str) -> Network {
-    (0..50)
-        .map(|n| (n, Vm::new(nic).unwrap()))
-        .map(|(n, mut vm)| {
-            vm.exec().unwrap();
-            vm.write(n);
-            (n, vm)
-        })
-        .collect()
-}
+    let mut network = Network::with_packets(3);
+    network.set_idle_input(-1);
+    for n in 0..50 {
+        let mut vm = Vm::new(nic).unwrap();
+        vm.write(n);
+        network.add(n, vm);
+    }
 
-fn process_packets(packets: &mut Packets, network: &mut Network) {
-    network.iter_mut().for_each(|(nic, vm)| {
-        match packets.remove(nic) {
-            Some(v) => v.iter().for_each(|&(x, y)| {
-                vm.write(x);
-                vm.write(y);
-            }),
-            None => vm.write(-1),
-        }
-    });
+    network
 }
 
 
@@ -5,10 +5,7 @@ type Tile = (i32, i32);
 type Pt = (i32, i32, i32);
 
 fn main() {
-    let map = load(include_str!("./eris.txt"));
-
-    println!("Part 1: {}", part_one(&map));
-    println!("Part 2: {}", part_two(&map));
+    aoc::run((part_one, part_two), &load(include_str!("./eris.txt")));
 }
 
 fn part_one(eris: &MapState) -> u64 {
@@ -1,29 +1,210 @@
 // Password: 8462464
+use anyhow::{bail, Context, Result};
+use std::collections::HashSet;
+use std::io;
+use vm::ascii::AsciiVm;
 
-fn main() {
-  use std::io;
-  use vm::Vm;
+// Items which end the game or leave the droid stuck
+const DANGEROUS: [&str; 5] = [
+  "escape pod",
+  "giant electromagnet",
+  "infinite loop",
+  "molten lava",
+  "photons",
+];
 
+fn main() {
   let program = include_str!("./program.txt");
-  let mut vm = Vm::new(program).unwrap();
-  vm.exec().unwrap();
 
-  loop {
-    let prompt: String = vm.drain().iter().map(|v| (*v as u8) as char).collect();
-    print!("{prompt}");
+  if std::env::args().any(|arg| arg == "--play") {
+    play(program).unwrap();
+  } else {
+    aoc::run((|program: &str| part_one(program).unwrap(),), program);
+  }
+}
+
+fn play(program: &str) -> Result<()> {
+  let mut vm = AsciiVm::new(program)?;
+  vm.start()?;
+
+  while !vm.is_done() {
+    print!("{}", vm.read_text());
 
     let mut buf = String::new();
-    io::stdin().read_line(&mut buf).unwrap();
-    let cmd = match buf.as_str() {
-      "n\n" => "north\n",
-      "s\n" => "south\n",
-      "e\n" => "east\n",
-      "w\n" => "west\n",
-      "q\n" => break,
-      "quit\n" => break,
-      _ => &buf
+    io::stdin().read_line(&mut buf)?;
+    let cmd = match buf.trim_end() {
+      "n" => "north",
+      "s" => "south",
+      "e" => "east",
+      "w" => "west",
+      "q" | "quit" => break,
+      cmd => cmd,
     };
-    cmd.as_bytes().iter().for_each(|&b| vm.write(b));
-    vm.cont().unwrap();
+    vm.write_line(cmd)?;
+  }
+  print!("{}", vm.read_text());
+
+  Ok(())
+}
+
+fn part_one(program: &str) -> Result<String> {
+  let mut vm = AsciiVm::new(program)?;
+  vm.start()?;
+  let room = Room::parse(&vm.read_text()).context("no starting room")?;
+
+  let mut explorer = Explorer {
+    vm,
+    visited: HashSet::new(),
+    items: Vec::new(),
+    path: Vec::new(),
+    checkpoint: None,
   };
+  explorer.explore(&room)?;
+  explorer.crack()
+}
+
+struct Room {
+  name: String,
+  doors: Vec<String>,
+  items: Vec<String>,
+}
+
+impl Room {
+  // Parses the last room described in the text, which is where
+  // the droid ends up.
+  fn parse(text: &str) -> Option<Room> {
+    let i = text.rfind("== ")?;
+    let mut lines = text[i..].lines();
+    let name = lines.next()?.trim_matches(|c| c == '=' || c == ' ').to_string();
+
+    let mut doors = Vec::new();
+    let mut items = Vec::new();
+    let mut list = None;
+    for line in lines {
+      match line {
+        "Doors here lead:" => list = Some(&mut doors),
+        "Items here:" => list = Some(&mut items),
+        _ => match (line.strip_prefix("- "), list.as_mut()) {
+          (Some(s), Some(v)) => v.push(s.to_string()),
+          _ => list = None,
+        }
+      }
+    }
+
+    Some(Room { name, doors, items })
+  }
+}
+
+struct Explorer {
+  vm: AsciiVm,
+  visited: HashSet<String>,
+  items: Vec<String>,
+  path: Vec<String>,
+  checkpoint: Option<(Vec<String>, String)>,
+}
+
+impl Explorer {
+  // Walks every room picking up everything safe, noting the way
+  // to the security checkpoint and the door to the pressure plate.
+  fn explore(&mut self, room: &Room) -> Result<()> {
+    self.visited.insert(room.name.clone());
+
+    for item in room.items.iter().filter(|s| !DANGEROUS.contains(&s.as_str())) {
+      self.command(&format!("take {item}"))?;
+      self.items.push(item.clone());
+    }
+
+    for door in &room.doors {
+      let next = Room::parse(&self.command(door)?).context("no room")?;
+      if next.name == room.name {
+        // Bounced back by the pressure-sensitive floor
+        self.checkpoint = Some((self.path.clone(), door.clone()));
+      } else if self.visited.contains(&next.name) {
+        self.command(back(door)?)?;
+      } else {
+        self.path.push(door.clone());
+        self.explore(&next)?;
+        self.path.pop();
+        self.command(back(door)?)?;
+      }
+    }
+
+    Ok(())
+  }
+
+  // Goes to the checkpoint and tries every combination of items
+  // on the floor, branching each attempt off the same machine.
+  fn crack(&mut self) -> Result<String> {
+    let (path, floor) = self.checkpoint.clone().context("no checkpoint found")?;
+    for door in &path {
+      self.command(door)?;
+    }
+
+    for mask in 0..1u32 << self.items.len() {
+      let mut vm = self.vm.fork();
+      for (i, item) in self.items.iter().enumerate() {
+        if mask & (1 << i) == 0 {
+          vm.write_line(&format!("drop {item}"))?;
+        }
+      }
+      vm.read_text();
+      vm.write_line(&floor)?;
+
+      let text = vm.read_text();
+      if let Some(i) = text.find("typing ") {
+        let password = text[i + 7..].chars()
+          .take_while(|c| c.is_ascii_digit())
+          .collect();
+        return Ok(password)
+      }
+    }
+
+    bail!("no combination of items worked")
+  }
+
+  fn command(&mut self, cmd: &str) -> Result<String> {
+    self.vm.write_line(cmd)?;
+    if self.vm.is_done() {
+      bail!("game over after {cmd:?}: {}", self.vm.read_text())
+    }
+
+    Ok(self.vm.read_text())
+  }
+}
+
+fn back(door: &str) -> Result<&'static str> {
+  let dir = match door {
+    "north" => "south",
+    "south" => "north",
+    "east"  => "west",
+    "west"  => "east",
+    _ => bail!("unknown door {door:?}"),
+  };
+
+  Ok(dir)
+}
+
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn it_works() {
+    let program = include_str!("./program.txt");
+
+    let password = part_one(program).unwrap();
+    assert_eq!(password, "8462464");
+  }
+
+  #[test]
+  fn parse_room() {
+    let text = "\n\n\n== Hull Breach ==\nYou got in through a hole in the floor here.\n\n\
+                Doors here lead:\n- north\n- west\n\nItems here:\n- mug\n- dark matter\n\nCommand?\n";
+    let room = Room::parse(text).unwrap();
+
+    assert_eq!(room.name, "Hull Breach");
+    assert_eq!(room.doors, ["north", "west"]);
+    assert_eq!(room.items, ["mug", "dark matter"]);
+  }
 }
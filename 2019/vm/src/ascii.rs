@@ -0,0 +1,177 @@
+//! Text adapter for Intcode programs which talk ASCII. Output values
+//! outside the ASCII range (answers like the hull damage) are kept
+//! apart from the text.
+use anyhow::Result;
+use std::fmt;
+use crate::{State, Vm};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Entry {
+    Output(String),
+    Input(String),
+}
+
+/// Record of a scripted session.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Transcript {
+    pub entries: Vec<Entry>,
+    pub values: Vec<i64>,
+}
+
+impl Transcript {
+    /// All the text output, without the commands.
+    pub fn output(&self) -> String {
+        self.entries.iter()
+            .filter_map(|e| match e {
+                Entry::Output(s) => Some(s.as_str()),
+                Entry::Input(_) => None,
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Transcript {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for entry in &self.entries {
+            match entry {
+                Entry::Output(s) => write!(f, "{}", s)?,
+                Entry::Input(s) => writeln!(f, "{}", s)?,
+            }
+        }
+        self.values.iter().try_for_each(|v| writeln!(f, "{}", v))
+    }
+}
+
+#[derive(Debug)]
+pub struct AsciiVm {
+    vm: Vm,
+    text: String,
+    values: Vec<i64>,
+}
+
+impl From<Vm> for AsciiVm {
+    fn from(vm: Vm) -> Self {
+        AsciiVm { vm, text: String::new(), values: Vec::new() }
+    }
+}
+
+impl AsciiVm {
+    pub fn new(program: &str) -> Result<Self> {
+        Ok(Vm::new(program)?.into())
+    }
+
+    pub fn vm(&self) -> &Vm {
+        &self.vm
+    }
+
+    pub fn vm_mut(&mut self) -> &mut Vm {
+        &mut self.vm
+    }
+
+    pub fn into_inner(self) -> Vm {
+        self.vm
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.vm.is_done()
+    }
+
+    /// Independent copy of the Vm along with any unread output.
+    pub fn fork(&self) -> AsciiVm {
+        AsciiVm {
+            vm: self.vm.fork(),
+            text: self.text.clone(),
+            values: self.values.clone(),
+        }
+    }
+
+    /// Runs the program from the start until it wants input.
+    pub fn start(&mut self) -> Result<State> {
+        let state = self.vm.exec()?;
+        self.collect();
+
+        Ok(state)
+    }
+
+    /// Queues a line of input without running the program.
+    pub fn queue_line(&mut self, line: &str) {
+        line.bytes().for_each(|b| self.vm.write(b));
+        self.vm.write(b'\n');
+    }
+
+    /// Sends a line of input and runs the program until it wants more
+    /// (starting it if need be).
+    pub fn write_line(&mut self, line: &str) -> Result<State> {
+        self.queue_line(line);
+        let state = if self.vm.state() == State::Ready {
+            self.vm.exec()?
+        } else {
+            self.vm.cont()?
+        };
+        self.collect();
+
+        Ok(state)
+    }
+
+    /// Next complete line of output, without the newline.
+    pub fn read_line(&mut self) -> Option<String> {
+        let i = self.text.find('\n')?;
+        let line = self.text.drain(..=i).collect::<String>();
+
+        Some(line[..i].to_string())
+    }
+
+    /// Output up to and including the prompt, if the prompt has
+    /// been printed.
+    pub fn read_until_prompt(&mut self, prompt: &str) -> Option<String> {
+        let i = self.text.find(prompt)? + prompt.len();
+        Some(self.text.drain(..i).collect())
+    }
+
+    /// All output not yet read.
+    pub fn read_text(&mut self) -> String {
+        std::mem::take(&mut self.text)
+    }
+
+    /// Output values which weren't ASCII.
+    pub fn take_values(&mut self) -> Vec<i64> {
+        std::mem::take(&mut self.values)
+    }
+
+    /// Plays a list of commands, one per line, recording everything.
+    /// Stops early if the program finishes.
+    pub fn play<I, S>(&mut self, commands: I) -> Result<Transcript>
+        where
+            I: IntoIterator<Item = S>,
+            S: AsRef<str>,
+    {
+        let mut transcript = Transcript::default();
+        if self.vm.state() == State::Ready {
+            self.start()?;
+        }
+        transcript.entries.push(Entry::Output(self.read_text()));
+
+        for cmd in commands {
+            if self.is_done() {
+                break
+            }
+            let cmd = cmd.as_ref();
+            self.write_line(cmd)?;
+            transcript.entries.push(Entry::Input(cmd.to_string()));
+            transcript.entries.push(Entry::Output(self.read_text()));
+        }
+        transcript.values = self.take_values();
+
+        Ok(transcript)
+    }
+
+    fn collect(&mut self) {
+        for v in self.vm.drain() {
+            if (0..128).contains(&v) {
+                self.text.push(v as u8 as char)
+            } else {
+                self.values.push(v)
+            }
+        }
+    }
+}
@@ -0,0 +1,204 @@
+//! Intcode assembler. Accepts the listing format produced by
+//! `disasm::listing`:
+//!
+//! ```text
+//! loop:   IN -> pos count         ; comments run to end of line
+//!         ADD [pos count], #-1 -> rel+2
+//!         JT [rel+2], #loop
+//!         HLT
+//! count:  db 0, 1, -2, loop+1
+//! ```
+//!
+//! Operands are `#n` (immediate), `[pos n]` and `[rel+n]` for reads,
+//! `pos n` and `rel+n` for the destination after `->`. Numbers may be
+//! replaced by a label with an optional `+n`/`-n` offset.
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use crate::disasm::join;
+use crate::{Mode, Op};
+
+#[derive(Debug)]
+enum Expr<'a> {
+    Num(i64),
+    Label(&'a str, i64),
+}
+
+#[derive(Debug)]
+struct Operand<'a> {
+    mode: Mode,
+    expr: Expr<'a>,
+}
+
+#[derive(Debug)]
+enum Stmt<'a> {
+    Instr(Op, Vec<Operand<'a>>),
+    Data(Vec<Expr<'a>>),
+}
+
+/// Assembles source into the comma separated text `Vm::new` accepts.
+pub fn assemble(source: &str) -> Result<String> {
+    Ok(join(&assemble_words(source)?))
+}
+
+/// Assembles source into raw Intcode words.
+pub fn assemble_words(source: &str) -> Result<Vec<i64>> {
+    let mut addr = 0;
+    let mut labels = HashMap::new();
+    let mut stmts = Vec::new();
+
+    for (n, line) in source.lines().enumerate() {
+        let line = line.split(';').next().unwrap_or("").trim();
+        let rest = take_labels(line, |name| {
+            if labels.insert(name, addr as i64).is_some() {
+                bail!("Duplicate label {:?}", name)
+            }
+            Ok(())
+        }).with_context(|| format!("line {}", n + 1))?;
+
+        if !rest.is_empty() {
+            let stmt = statement(rest).with_context(|| format!("line {}: {}", n + 1, line))?;
+            addr += match &stmt {
+                Stmt::Instr(op, _) => 1 + op.params(),
+                Stmt::Data(v) => v.len(),
+            };
+            stmts.push((n, stmt));
+        }
+    }
+
+    let resolve = |n: usize, e: &Expr| match e {
+        Expr::Num(v) => Ok(*v),
+        Expr::Label(name, offset) => labels.get(name)
+            .map(|v| v + offset)
+            .with_context(|| format!("line {}: unknown label {:?}", n + 1, name)),
+    };
+
+    let mut words = Vec::with_capacity(addr);
+    for (n, stmt) in &stmts {
+        match stmt {
+            Stmt::Instr(op, operands) => {
+                let modes = operands.iter()
+                    .rev()
+                    .fold(0, |acc, o| acc * 10 + o.mode as i64);
+                words.push(*op as i64 + modes * 100);
+                for o in operands {
+                    words.push(resolve(*n, &o.expr)?);
+                }
+            },
+            Stmt::Data(exprs) => {
+                for e in exprs {
+                    words.push(resolve(*n, e)?);
+                }
+            },
+        }
+    }
+
+    Ok(words)
+}
+
+fn take_labels<'a, F>(line: &'a str, mut add: F) -> Result<&'a str>
+    where F: FnMut(&'a str) -> Result<()>
+{
+    let mut rest = line;
+    while let Some((name, tail)) = rest.split_once(':') {
+        let name = name.trim();
+        if !is_ident(name) {
+            bail!("Invalid label {:?}", name)
+        }
+        add(name)?;
+        rest = tail.trim();
+    }
+
+    Ok(rest)
+}
+
+fn statement(s: &str) -> Result<Stmt<'_>> {
+    let (head, tail) = s.split_once(char::is_whitespace).unwrap_or((s, ""));
+    if head.eq_ignore_ascii_case("db") {
+        let exprs = tail.split(',')
+            .map(expr)
+            .collect::<Result<Vec<_>>>()?;
+        return Ok(Stmt::Data(exprs))
+    }
+
+    let op = Op::from_mnemonic(head)
+        .with_context(|| format!("Unknown mnemonic {:?}", head))?;
+    let (srcs, dest) = match tail.split_once("->") {
+        Some((srcs, dest)) => (srcs, Some(dest)),
+        None => (tail, None),
+    };
+
+    let mut operands = srcs.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(operand)
+        .collect::<Result<Vec<_>>>()?;
+
+    match (op.dest(), dest) {
+        (Some(_), Some(d)) => {
+            let o = operand(d)?;
+            if o.mode == Mode::Immediate {
+                bail!("Cannot write to an immediate operand")
+            }
+            operands.push(o)
+        },
+        (Some(_), None) => bail!("{} needs a destination", op.mnemonic()),
+        (None, Some(_)) => bail!("{} takes no destination", op.mnemonic()),
+        (None, None) => (),
+    }
+    if operands.len() != op.params() {
+        bail!("{} takes {} operands, found {}", op.mnemonic(), op.params(), operands.len())
+    }
+
+    Ok(Stmt::Instr(op, operands))
+}
+
+fn operand(s: &str) -> Result<Operand<'_>> {
+    let s = s.trim();
+    if let Some(e) = s.strip_prefix('#') {
+        return Ok(Operand { mode: Mode::Immediate, expr: expr(e)? })
+    }
+
+    let inner = s.strip_prefix('[')
+        .and_then(|s| s.strip_suffix(']'))
+        .unwrap_or(s)
+        .trim();
+    if let Some(e) = inner.strip_prefix("pos").filter(|e| e.starts_with(char::is_whitespace)) {
+        Ok(Operand { mode: Mode::Position, expr: expr(e)? })
+    } else if let Some(e) = inner.strip_prefix("rel") {
+        let e = e.replace(char::is_whitespace, "");
+        let v = if e.is_empty() { 0 } else {
+            e.parse::<i64>().with_context(|| format!("Invalid relative offset {:?}", e))?
+        };
+        Ok(Operand { mode: Mode::Relative, expr: Expr::Num(v) })
+    } else {
+        bail!("Invalid operand {:?}", s)
+    }
+}
+
+fn expr(s: &str) -> Result<Expr<'_>> {
+    let s = s.trim();
+    if let Ok(v) = s.parse::<i64>() {
+        return Ok(Expr::Num(v))
+    }
+
+    let (name, offset) = match s.rfind(['+', '-']) {
+        Some(i) => {
+            let offset = s[i..].replace(char::is_whitespace, "");
+            let offset = offset.parse::<i64>()
+                .with_context(|| format!("Invalid offset in {:?}", s))?;
+            (s[..i].trim(), offset)
+        },
+        None => (s, 0),
+    };
+    if !is_ident(name) {
+        bail!("Invalid value {:?}", s)
+    }
+
+    Ok(Expr::Label(name, offset))
+}
+
+fn is_ident(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
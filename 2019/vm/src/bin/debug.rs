@@ -0,0 +1,180 @@
+//! Interactive Intcode debugger.
+//!
+//! Usage: debug <program.txt> [input values...]
+use anyhow::{bail, Context, Result};
+use std::io::{self, BufRead, Write};
+use vm::{Pipe, State, Vm, Watch};
+
+const HELP: &str = "\
+step|s [n]           execute n instructions (default 1)
+continue|c           run until a breakpoint, watchpoint, input wait or halt
+break|b [addr]       set a breakpoint, or list them
+delete|d addr        remove a breakpoint or watchpoint
+watch|w addr [r|w|rw] stop when addr is read and/or written (default rw)
+mem|m addr [n]       show n memory cells (default 8)
+poke|p addr value    write a memory cell
+regs|r               show ip, relative base, state and next instruction
+trace|t [n]          show the last n traced instructions; `trace on n` to
+                     keep the last n, `trace off` to stop recording
+input|i values...    queue integer input
+ascii|a text         queue text input followed by a newline
+reset                restart the program
+quit|q               exit";
+
+fn main() -> Result<()> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let Some(path) = args.first() else {
+        bail!("usage: debug <program.txt> [input values...]")
+    };
+    let program = std::fs::read_to_string(path)
+        .with_context(|| format!("reading {}", path))?;
+
+    let mut vm = Vm::new(&program)?;
+    let (mut stdin, mut stdout) = vm.pipes();
+    for v in &args[1..] {
+        stdin.write(v.parse::<i64>().with_context(|| format!("bad input {:?}", v))?);
+    }
+    vm.set_trace(64);
+    vm.reset();
+    regs(&vm);
+
+    let mut line = String::new();
+    let mut input = io::stdin().lock();
+    loop {
+        print!("> ");
+        io::stdout().flush()?;
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            break
+        }
+
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        let Some(&cmd) = words.first() else { continue };
+        match command(&mut vm, &mut stdin, cmd, &words[1..]) {
+            Ok(true) => (),
+            Ok(false) => break,
+            Err(e) => println!("error: {:#}", e),
+        }
+        output(&mut stdout);
+    }
+
+    Ok(())
+}
+
+fn command(vm: &mut Vm, stdin: &mut Pipe, cmd: &str, args: &[&str]) -> Result<bool> {
+    match cmd {
+        "step" | "s" => {
+            let n = arg(args, 0)?.unwrap_or(1);
+            for _ in 0..n {
+                if vm.step()? != State::Paused { break }
+            }
+            regs(vm);
+        },
+        "continue" | "c" => {
+            vm.cont()?;
+            regs(vm);
+        },
+        "break" | "b" => match arg(args, 0)? {
+            Some(addr) => vm.set_breakpoint(addr),
+            None => {
+                let mut v = vm.breakpoints().collect::<Vec<_>>();
+                v.sort_unstable();
+                v.iter().for_each(|a| println!("break {}", a));
+                let mut v = vm.watchpoints().collect::<Vec<_>>();
+                v.sort_unstable_by_key(|(a, _)| *a);
+                v.iter().for_each(|(a, w)| println!("watch {} {:?}", a, w));
+            },
+        },
+        "delete" | "d" => {
+            let addr = arg(args, 0)?.context("address required")?;
+            if !vm.clear_breakpoint(addr) && !vm.clear_watchpoint(addr) {
+                bail!("nothing set at {}", addr)
+            }
+        },
+        "watch" | "w" => {
+            let addr = arg(args, 0)?.context("address required")?;
+            let watch = match args.get(1).copied() {
+                Some("r") => Watch::Read,
+                Some("w") => Watch::Write,
+                Some("rw") | None => Watch::Access,
+                Some(s) => bail!("unknown access {:?}", s),
+            };
+            vm.set_watchpoint(addr, watch);
+        },
+        "mem" | "m" => {
+            let addr = arg(args, 0)?.context("address required")?;
+            let n = arg(args, 1)?.unwrap_or(8);
+            for row in (addr..addr + n).collect::<Vec<_>>().chunks(8) {
+                let values = row.iter()
+                    .map(|a| format!("{:>8}", vm.peek(*a)))
+                    .collect::<String>();
+                println!("{:>6}:{}", row[0], values);
+            }
+        },
+        "poke" | "p" => {
+            let addr = arg(args, 0)?.context("address required")?;
+            let v = args.get(1).context("value required")?.parse::<i64>()?;
+            vm.poke(addr, v);
+        },
+        "regs" | "r" => regs(vm),
+        "trace" | "t" => match args.first().copied() {
+            Some("on") => vm.set_trace(arg(args, 1)?.unwrap_or(64)),
+            Some("off") => vm.set_trace(0),
+            _ => {
+                let n = arg(args, 0)?.unwrap_or(16);
+                let trace = vm.trace().collect::<Vec<_>>();
+                trace[trace.len().saturating_sub(n)..].iter().for_each(|t| println!("{}", t));
+            },
+        },
+        "input" | "i" => {
+            for s in args {
+                stdin.write(s.parse::<i64>().with_context(|| format!("bad input {:?}", s))?);
+            }
+        },
+        "ascii" | "a" => {
+            args.join(" ").bytes().for_each(|b| stdin.write(b));
+            stdin.write(b'\n');
+        },
+        "reset" => {
+            vm.reset();
+            regs(vm);
+        },
+        "help" | "h" | "?" => println!("{}", HELP),
+        "quit" | "q" => return Ok(false),
+        _ => bail!("unknown command {:?}, try help", cmd),
+    }
+
+    Ok(true)
+}
+
+fn arg(args: &[&str], i: usize) -> Result<Option<usize>> {
+    args.get(i)
+        .map(|s| s.parse::<usize>().with_context(|| format!("bad number {:?}", s)))
+        .transpose()
+}
+
+fn regs(vm: &Vm) {
+    let next = vm.instr(vm.ip())
+        .map(|instr| instr.to_string())
+        .unwrap_or_else(|| format!("?? {}", vm.peek(vm.ip())));
+    let stop = vm.stop_reason()
+        .map(|s| format!(" ({:?})", s))
+        .unwrap_or_default();
+    println!("ip={} base={} state={:?}{}", vm.ip(), vm.base(), vm.state(), stop);
+    println!("{:>5}: {}", vm.ip(), next);
+}
+
+fn output(stdout: &mut Pipe) {
+    let values = stdout.drain();
+    if values.is_empty() {
+        return
+    }
+
+    // Show ASCII output as text, anything else as numbers
+    if values.iter().all(|v| (32..127).contains(v) || *v == 10) {
+        print!("{}", values.iter().map(|v| *v as u8 as char).collect::<String>());
+        if values.last() != Some(&10) { println!() }
+    } else {
+        println!("output: {:?}", values);
+    }
+}
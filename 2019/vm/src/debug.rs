@@ -0,0 +1,85 @@
+//! Breakpoints, watchpoints and execution tracing for the Vm.
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use crate::disasm::Instr;
+
+/// Kind of memory access a watchpoint triggers on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Watch {
+    Read,
+    Write,
+    Access,
+}
+
+impl Watch {
+    fn matches(&self, write: bool) -> bool {
+        match self {
+            Watch::Read   => !write,
+            Watch::Write  => write,
+            Watch::Access => true,
+        }
+    }
+}
+
+/// Why the Vm paused.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stop {
+    /// About to execute the instruction at this address.
+    Breakpoint(usize),
+    /// The instruction at `ip` read or wrote a watched address.
+    Watch { ip: usize, addr: usize, write: bool },
+    /// Single step completed.
+    Step,
+}
+
+/// One executed instruction along with the operand values it read
+/// and (last) the value it wrote, if any.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Trace {
+    pub instr: Instr,
+    pub base: i64,
+    pub values: Vec<i64>,
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let values = self.values.iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(f, "{:>5}: {:<40} base={} [{}]", self.instr.addr, self.instr, self.base, values)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Debugger {
+    pub breakpoints: HashSet<usize>,
+    pub watchpoints: HashMap<usize, Watch>,
+    pub trace: VecDeque<Trace>,
+    pub trace_len: usize,
+    pub stop: Option<Stop>,
+    pub hit: Option<Stop>,
+    pub values: Vec<i64>,
+}
+
+impl Debugger {
+    pub fn tracing(&self) -> bool {
+        self.trace_len > 0
+    }
+
+    /// Records a memory access, noting a hit if it's being watched.
+    pub fn access(&mut self, ip: usize, addr: usize, write: bool) {
+        if let Some(w) = self.watchpoints.get(&addr) {
+            if w.matches(write) && self.hit.is_none() {
+                self.hit = Some(Stop::Watch { ip, addr, write })
+            }
+        }
+    }
+
+    pub fn record(&mut self, t: Trace) {
+        if self.trace.len() == self.trace_len {
+            self.trace.pop_front();
+        }
+        self.trace.push_back(t)
+    }
+}
@@ -0,0 +1,272 @@
+//! Intcode disassembler producing annotated mnemonic listings that
+//! the `asm` module can assemble back into the original program.
+use anyhow::{Context, Result};
+use std::collections::{BTreeSet, HashSet};
+use std::convert::TryFrom;
+use std::fmt::Write;
+use crate::{getm, getop, Mode, Op};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Param {
+    pub mode: Mode,
+    pub value: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Instr {
+    pub addr: usize,
+    pub op: Op,
+    pub params: Vec<Param>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Item {
+    Code(Instr),
+    Data { addr: usize, values: Vec<i64> },
+}
+
+impl Instr {
+    /// Number of memory cells the instruction occupies.
+    pub fn size(&self) -> usize {
+        1 + self.params.len()
+    }
+
+    /// Raw Intcode words for the instruction.
+    pub fn encode(&self) -> Vec<i64> {
+        let modes = self.params.iter()
+            .rev()
+            .fold(0, |acc, p| acc * 10 + p.mode as i64);
+
+        let mut v = vec![self.op as i64 + modes * 100];
+        v.extend(self.params.iter().map(|p| p.value));
+        v
+    }
+
+    /// Formats the instruction, naming jump targets found in `labels`.
+    pub fn render(&self, labels: &BTreeSet<usize>) -> String {
+        let dest = self.op.dest();
+        let jump = matches!(self.op, Op::Jt | Op::Jf);
+
+        let srcs = self.params.iter()
+            .enumerate()
+            .filter(|(i, _)| Some(*i) != dest)
+            .map(|(i, p)| match p.mode {
+                Mode::Immediate if jump && i == 1 && is_label(labels, p.value) =>
+                    format!("#L{}", p.value),
+                Mode::Immediate => format!("#{}", p.value),
+                Mode::Position  => format!("[pos {}]", p.value),
+                Mode::Relative  => format!("[{}]", rel(p.value)),
+            })
+            .collect::<Vec<_>>();
+
+        let mut s = self.op.mnemonic().to_string();
+        if !srcs.is_empty() {
+            s.push(' ');
+            s.push_str(&srcs.join(", "));
+        }
+        if let Some(p) = dest.map(|i| self.params[i]) {
+            s.push_str(" -> ");
+            match p.mode {
+                Mode::Relative => s.push_str(&rel(p.value)),
+                _ => s.push_str(&format!("pos {}", p.value)),
+            }
+        }
+
+        s
+    }
+}
+
+impl std::fmt::Display for Instr {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.pad(&self.render(&BTreeSet::new()))
+    }
+}
+
+/// Parses a comma separated Intcode program.
+pub fn parse(program: &str) -> Result<Vec<i64>> {
+    let program = program.trim();
+    if program.is_empty() {
+        return Ok(vec![])
+    }
+
+    program.split(',')
+        .enumerate()
+        .map(|(i, s)| s.trim().parse::<i64>()
+            .with_context(|| format!("Invalid value at address {}: {:?}", i, s)))
+        .collect()
+}
+
+/// Decodes the instruction at `addr`. Only words which re-encode to
+/// exactly the same value (no stray mode digits, no immediate
+/// destinations) and whose parameters fit in the program decode.
+pub fn decode(words: &[i64], addr: usize) -> Option<Instr> {
+    let inst = *words.get(addr)?;
+    if inst < 0 {
+        return None
+    }
+
+    let (op, mut modes) = getop(inst).ok()?;
+    let mut params = Vec::with_capacity(op.params());
+    for i in 0..op.params() {
+        let (mode, rest) = getm(modes).ok()?;
+        if op.dest() == Some(i) && mode == Mode::Immediate {
+            return None
+        }
+        params.push(Param { mode, value: *words.get(addr + i + 1)? });
+        modes = rest;
+    }
+
+    (modes == 0).then_some(Instr { addr, op, params })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Cell {
+    Unknown,
+    Start,
+    Operand,
+}
+
+/// Splits the program into code and data. Code is found by following
+/// control flow from address 0; immediate operands pointing at
+/// otherwise unreached instructions (return addresses pushed on the
+/// relative stack, for example) are treated as further entry points.
+/// Everything left over is data.
+pub fn disassemble(words: &[i64]) -> Vec<Item> {
+    let mut cells = vec![Cell::Unknown; words.len()];
+    let mut tried = HashSet::new();
+    let mut entries = vec![0];
+
+    while !entries.is_empty() {
+        tried.extend(entries.iter().copied());
+        trace(words, &mut cells, entries);
+
+        entries = (0..words.len())
+            .filter(|&a| cells[a] == Cell::Start)
+            .flat_map(|a| decode(words, a))
+            .flat_map(|instr| instr.params)
+            .filter(|p| p.mode == Mode::Immediate)
+            .flat_map(|p| usize::try_from(p.value))
+            .filter(|&a| a < words.len() && cells[a] == Cell::Unknown)
+            .filter(|a| !tried.contains(a))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+    }
+
+    let mut items = Vec::new();
+    let mut addr = 0;
+    while addr < words.len() {
+        if cells[addr] == Cell::Start {
+            let instr = decode(words, addr).unwrap();
+            addr += instr.size();
+            items.push(Item::Code(instr));
+        } else {
+            let start = addr;
+            while addr < words.len() && cells[addr] != Cell::Start {
+                addr += 1;
+            }
+            items.push(Item::Data { addr: start, values: words[start..addr].to_vec() });
+        }
+    }
+
+    items
+}
+
+/// Disassembles a comma separated program into a listing with jump
+/// labels and the address and raw words of each line as comments.
+pub fn listing(program: &str) -> Result<String> {
+    let words = parse(program)?;
+    let items = disassemble(&words);
+    let labels = labels(&items);
+
+    let mut s = String::new();
+    for item in &items {
+        match item {
+            Item::Code(instr) => {
+                if labels.contains(&instr.addr) {
+                    writeln!(s, "L{}:", instr.addr)?;
+                }
+                let raw = join(&instr.encode());
+                writeln!(s, "    {:<40}; {:>5}: {}", instr.render(&labels), instr.addr, raw)?;
+            },
+            Item::Data { addr, values } => {
+                for (i, chunk) in values.chunks(8).enumerate() {
+                    let text = format!("db {}", chunk.iter()
+                        .map(|v| v.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", "));
+                    writeln!(s, "    {:<40}; {:>5}", text, addr + i * 8)?;
+                }
+            },
+        }
+    }
+
+    Ok(s)
+}
+
+/// Immediate jump targets which start an instruction.
+fn labels(items: &[Item]) -> BTreeSet<usize> {
+    let starts = items.iter()
+        .filter_map(|item| match item {
+            Item::Code(instr) => Some(instr.addr),
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+
+    items.iter()
+        .filter_map(|item| match item {
+            Item::Code(instr) if matches!(instr.op, Op::Jt | Op::Jf) => Some(instr.params[1]),
+            _ => None,
+        })
+        .filter(|p| p.mode == Mode::Immediate)
+        .flat_map(|p| usize::try_from(p.value))
+        .filter(|a| starts.contains(a))
+        .collect()
+}
+
+fn trace(words: &[i64], cells: &mut [Cell], mut stack: Vec<usize>) {
+    while let Some(mut addr) = stack.pop() {
+        while let Some(instr) = decode(words, addr) {
+            let span = addr..addr + instr.size();
+            if span.clone().any(|a| cells[a] != Cell::Unknown) {
+                // Already traced or overlapping other code
+                break
+            }
+            cells[addr] = Cell::Start;
+            span.skip(1).for_each(|a| cells[a] = Cell::Operand);
+
+            match instr.op {
+                Op::Halt => break,
+                Op::Jt | Op::Jf => {
+                    let (cond, target) = (instr.params[0], instr.params[1]);
+                    if target.mode == Mode::Immediate {
+                        if let Ok(a) = usize::try_from(target.value) {
+                            stack.push(a)
+                        }
+                    }
+                    let taken = (instr.op == Op::Jt) == (cond.value != 0);
+                    if cond.mode == Mode::Immediate && taken {
+                        break
+                    }
+                },
+                _ => (),
+            }
+            addr += instr.size();
+        }
+    }
+}
+
+fn is_label(labels: &BTreeSet<usize>, v: i64) -> bool {
+    usize::try_from(v).is_ok_and(|a| labels.contains(&a))
+}
+
+fn rel(v: i64) -> String {
+    if v < 0 { format!("rel{}", v) } else { format!("rel+{}", v) }
+}
+
+pub(crate) fn join(words: &[i64]) -> String {
+    words.iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
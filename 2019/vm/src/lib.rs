@@ -1,49 +1,124 @@
 use anyhow::{bail, Result};
-use std::cell::RefCell;
 use std::collections::VecDeque;
-use std::rc::Rc;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::Duration;
+use debug::Debugger;
+use disasm::{Instr, Param};
+
+mod debug;
+mod memory;
+pub use debug::{Stop, Trace, Watch};
+pub use memory::{Memory, DEFAULT_LIMIT};
+
+pub mod ascii;
+pub mod asm;
+pub mod disasm;
+pub mod net;
 
 #[cfg(test)]
 mod test;
 
+#[derive(Debug, Default)]
+struct Queue {
+    q: Mutex<VecDeque<i64>>,
+    ready: Condvar,
+}
+
+/// Shared queue of values between Vms. Pipes can be handed to other
+/// threads; a reader can block in `wait` until something arrives.
 #[derive(Debug)]
 pub struct Pipe {
-    q: Rc<RefCell<VecDeque<i64>>>
+    q: Arc<Queue>
 }
 
 impl Pipe {
     pub fn new() -> Self {
-        Pipe { q: Rc::new(RefCell::new(VecDeque::new())) }
+        Pipe { q: Arc::new(Queue::default()) }
     }
 
     pub fn from(other: &Pipe) -> Self {
-        Pipe { q: Rc::clone(&other.q) }
+        Pipe { q: Arc::clone(&other.q) }
     }
 
     pub fn read(&mut self) -> Option<i64> {
-        (*self.q).borrow_mut().pop_front()
+        self.lock().pop_front()
     }
 
     pub fn write<T>(&mut self, v: T) 
         where T: std::convert::Into<i64>
     {
-        (*self.q).borrow_mut().push_back(v.into())
+        self.lock().push_back(v.into());
+        self.q.ready.notify_all();
+    }
+
+    /// Writes all the values at once, so readers never see only some
+    /// of them and writes from other threads can't come in between.
+    pub fn write_all(&mut self, values: &[i64]) {
+        self.lock().extend(values);
+        self.q.ready.notify_all();
+    }
+
+    /// Writes the value only if the pipe is empty, checking and writing
+    /// under the same lock. Returns true if it was written.
+    pub fn write_if_empty(&mut self, v: i64) -> bool {
+        let mut q = self.lock();
+        let empty = q.is_empty();
+        if empty {
+            q.push_back(v);
+            drop(q);
+            self.q.ready.notify_all();
+        }
+        empty
     }
 
     pub fn flush(&mut self) -> i64 {
-        let mut q = (*self.q).borrow_mut();
+        let mut q = self.lock();
         let v = q.pop_back().unwrap_or(-1i64);
         q.clear();
         v
     }
 
     pub fn drain(&mut self) -> Vec<i64> {
-        let mut q = (*self.q).borrow_mut();
+        let mut q = self.lock();
         q.drain(..).collect::<Vec<i64>>()
     }
 
     pub fn connect(&mut self, other: &Pipe) {
-        self.q = Rc::clone(&other.q)
+        self.q = Arc::clone(&other.q)
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Blocks until the pipe has something to read or the timeout
+    /// expires. Returns true if there's something to read.
+    pub fn wait(&self, timeout: Duration) -> bool {
+        let q = self.lock();
+        let (q, _) = self.q.ready
+            .wait_timeout_while(q, timeout, |q| q.is_empty())
+            .unwrap_or_else(|e| e.into_inner());
+        !q.is_empty()
+    }
+
+    /// Copy of everything waiting in the pipe.
+    fn contents(&self) -> VecDeque<i64> {
+        self.lock().clone()
+    }
+
+    fn replace(&mut self, values: VecDeque<i64>) {
+        *self.lock() = values;
+        self.q.ready.notify_all();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, VecDeque<i64>> {
+        // A panic while holding the lock can't leave the queue
+        // half updated so just carry on with it.
+        self.q.q.lock().unwrap_or_else(|e| e.into_inner())
     }
 }
 
@@ -56,7 +131,21 @@ pub enum State {
     Ready,
     Running,
     Waiting,
-  Done,
+    Paused,
+    Done,
+}
+
+/// Saved machine state, including anything waiting in the input
+/// and output pipes. Memory is shared copy-on-write with the Vm so
+/// taking a snapshot is cheap.
+#[derive(Clone, Debug)]
+pub struct Snapshot {
+    ip: usize,
+    base: i64,
+    state: State,
+    memory: Memory,
+    input: VecDeque<i64>,
+    output: VecDeque<i64>,
 }
 
 #[derive(Debug)]
@@ -66,27 +155,29 @@ pub struct Vm {
     state: State,
     input: Pipe,
     output: Pipe,
-    memory: [i64; 8192],
-    backup: [i64; 8192],
+    memory: Memory,
+    backup: Memory,
+    debug: Debugger,
 }
 
 impl Vm {
     pub fn new(program: &str) -> Result<Self> {
         let mut vm = Vm { 
-            memory: [0; 8192],
-            backup: [0; 8192],
+            memory: Memory::default(),
+            backup: Memory::default(),
             ip: 0,
             base: 0,
             state: State::Ready,
             input: Pipe::new(),
-            output: Pipe::new()
+            output: Pipe::new(),
+            debug: Debugger::default(),
         };
 
         // Copy instructions into backup memory
         program.split(',')
             .enumerate()
-            .flat_map(|(i, s)| s.parse::<i64>().map(|n| (i, n)))
-            .for_each(|(i, n)| vm.backup[i] = n);
+            .flat_map(|(i, s)| s.trim().parse::<i64>().map(|n| (i, n)))
+            .for_each(|(i, n)| vm.backup.poke(i, n));
 
         Ok(vm)
     }
@@ -101,7 +192,7 @@ impl Vm {
 
     pub fn is_running(&self) -> bool {
         use State::*;
-        self.state == Running || self.state == Waiting
+        self.state == Running || self.state == Waiting || self.state == Paused
     }
 
     pub fn write<T>(&mut self, v: T) 
@@ -123,11 +214,77 @@ impl Vm {
     }
 
     pub fn exec(&mut self) -> Result<State> {
-        // Restore the program to original state
-        self.memory = self.backup;
+        self.reset();
+        self.run()?;
+
+        Ok(self.state)
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            ip: self.ip,
+            base: self.base,
+            state: self.state,
+            memory: self.memory.clone(),
+            input: self.input.contents(),
+            output: self.output.contents(),
+        }
+    }
+
+    /// Puts the Vm (and its pipes) back the way they were when the
+    /// snapshot was taken.
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        self.ip = snapshot.ip;
+        self.base = snapshot.base;
+        self.state = snapshot.state;
+        self.memory = snapshot.memory.clone();
+        self.input.replace(snapshot.input.clone());
+        self.output.replace(snapshot.output.clone());
+        self.debug.stop = None;
+    }
+
+    /// Creates an independent copy of the Vm in its current state with
+    /// its own pipes holding copies of whatever is waiting in this one's.
+    pub fn fork(&self) -> Vm {
+        let mut input = Pipe::new();
+        let mut output = Pipe::new();
+        input.replace(self.input.contents());
+        output.replace(self.output.contents());
+
+        Vm {
+            ip: self.ip,
+            base: self.base,
+            state: self.state,
+            input,
+            output,
+            memory: self.memory.clone(),
+            backup: self.backup.clone(),
+            debug: self.debug.clone(),
+        }
+    }
+
+    /// Restores the program to its original state, paused before the
+    /// first instruction.
+    pub fn reset(&mut self) {
+        self.memory = self.backup.clone();
         self.ip = 0;
         self.base = 0;
-        self.run()?;
+        self.state = State::Paused;
+        self.debug.stop = None;
+        self.debug.trace.clear();
+    }
+
+    /// Executes a single instruction, ignoring breakpoints.
+    pub fn step(&mut self) -> Result<State> {
+        if self.is_running() {
+            self.debug.stop = None;
+            self.state = State::Running;
+            self.execute()?;
+            if self.state == State::Running {
+                self.debug.stop = Some(Stop::Step);
+                self.state = State::Paused;
+            }
+        }
 
         Ok(self.state)
     }
@@ -140,112 +297,269 @@ impl Vm {
         Ok(self.state)
     }
 
-    pub fn set_addr(&mut self, addr: usize, v: i64) {
-        self.backup[addr] = v
+    /// Sets a cell of the program the Vm (re)starts from. Fails the
+    /// same as a running program would past the memory limit.
+    pub fn set_addr(&mut self, addr: usize, v: i64) -> Result<()> {
+        self.backup.set(addr, v)
+    }
+
+    /// Limits the addressable memory; programs reaching past the
+    /// limit (or using negative addresses) cause `run` to fail.
+    pub fn set_memory_limit(&mut self, limit: usize) {
+        self.memory.set_limit(limit);
+        self.backup.set_limit(limit);
+    }
+
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    pub fn ip(&self) -> usize {
+        self.ip
+    }
+
+    pub fn base(&self) -> i64 {
+        self.base
+    }
+
+    /// Reason for the last pause, if the Vm is paused.
+    pub fn stop_reason(&self) -> Option<Stop> {
+        if self.state == State::Paused { self.debug.stop } else { None }
+    }
+
+    /// Reads live memory, without triggering watchpoints.
+    pub fn peek(&self, addr: usize) -> i64 {
+        self.memory.peek(addr)
+    }
+
+    /// Writes live memory, without triggering watchpoints.
+    pub fn poke(&mut self, addr: usize, v: i64) {
+        self.memory.poke(addr, v)
+    }
+
+    /// Decodes the instruction at addr in live memory.
+    pub fn instr(&self, addr: usize) -> Option<Instr> {
+        let words = (addr..addr + 4).map(|a| self.memory.peek(a)).collect::<Vec<_>>();
+        disasm::decode(&words, 0).map(|instr| Instr { addr, ..instr })
+    }
+
+    pub fn set_breakpoint(&mut self, addr: usize) {
+        self.debug.breakpoints.insert(addr);
+    }
+
+    pub fn clear_breakpoint(&mut self, addr: usize) -> bool {
+        self.debug.breakpoints.remove(&addr)
+    }
+
+    pub fn breakpoints(&self) -> impl Iterator<Item = usize> + '_ {
+        self.debug.breakpoints.iter().copied()
+    }
+
+    pub fn set_watchpoint(&mut self, addr: usize, watch: Watch) {
+        self.debug.watchpoints.insert(addr, watch);
+    }
+
+    pub fn clear_watchpoint(&mut self, addr: usize) -> bool {
+        self.debug.watchpoints.remove(&addr).is_some()
+    }
+
+    pub fn watchpoints(&self) -> impl Iterator<Item = (usize, Watch)> + '_ {
+        self.debug.watchpoints.iter().map(|(a, w)| (*a, *w))
+    }
+
+    /// Keeps the last `len` executed instructions; zero turns
+    /// tracing off.
+    pub fn set_trace(&mut self, len: usize) {
+        self.debug.trace_len = len;
+        while self.debug.trace.len() > len {
+            self.debug.trace.pop_front();
+        }
+    }
+
+    /// Recorded instructions, oldest first.
+    pub fn trace(&self) -> impl Iterator<Item = &Trace> {
+        self.debug.trace.iter()
     }
 
     fn run(&mut self) -> Result<State> {
+        // Don't stop again on the breakpoint we're resuming from, at
+        // least not until it's been stepped over.
+        let mut resume = match self.debug.stop.take() {
+            Some(Stop::Breakpoint(addr)) => Some(addr),
+            _ => None,
+        };
+
         self.state = State::Running;
         while self.state == State::Running {
-            let inst = self.memory[self.ip];
-            let (opc, inst) = getop(inst)?;
-            let (m_a, inst) = getm(inst)?;
-            let (m_b, inst) = getm(inst)?;
-            let (m_c, _) = getm(inst)?;
-
-            self.ip = match opc  {
-                Op::Add => {
-                    let a = self.getv(m_a, self.ip + 1);
-                    let b = self.getv(m_b, self.ip + 2);
-                    self.setv(m_c, self.ip + 3, a+b)?;
-                    self.ip + 4
-                },
-                Op::Mul => {
-                    let a = self.getv(m_a, self.ip + 1);
-                    let b = self.getv(m_b, self.ip + 2);
-                    self.setv(m_c, self.ip + 3, a*b)?;
-                    self.ip + 4
-                },
-                Op::Read => {
-                    // read-from-input
-                    if let Some(v) = self.input.read() {
-                        self.setv(m_a, self.ip + 1, v)?;
-                        self.ip + 2
-                    } else {
-                        self.state = State::Waiting;
-                        self.ip
-                    }
-                },
-                Op::Jt => {
-                    // jump-if-true
-                    let a = self.getv(m_a, self.ip + 1);
-                    let b = self.getv(m_b, self.ip + 2);
-                    if a != 0 { b as usize } else { self.ip + 3 }
-                },
-                Op::Jf => {
-                    // jump-if-false
-                    let a = self.getv(m_a, self.ip + 1);
-                    let b = self.getv(m_b, self.ip + 2);
-                    if a == 0 { b as usize } else { self.ip + 3 }
-                },
-                Op::Lt => {
-                    let a = self.getv(m_a, self.ip + 1);
-                    let b = self.getv(m_b, self.ip + 2);
-                    let v = i64::from(a < b);
-                    self.setv(m_c, self.ip + 3, v)?;
-                    self.ip + 4
-                },
-                Op::Eq => {
-                    let a = self.getv(m_a, self.ip + 1);
-                    let b = self.getv(m_b, self.ip + 2);
-                    let v = i64::from(a == b);
-                    self.setv(m_c, self.ip + 3, v)?;
-                    self.ip + 4
-                },
-                Op::Arb => {
-                    // adjust-relative-base
-                    let a = self.getv(m_a, self.ip + 1);
-                    self.base += a;
-                    self.ip + 2
-                },
-                Op::Write => {
-                    // write-to-output
-                    let a = self.getv(m_a, self.ip + 1);
-                    self.output.write(a);
+            if self.debug.breakpoints.contains(&self.ip) && resume != Some(self.ip) {
+                self.debug.stop = Some(Stop::Breakpoint(self.ip));
+                self.state = State::Paused;
+            } else {
+                self.execute()?;
+                resume = None;
+            }
+        }
+
+        Ok(self.state)
+    }
+
+    /// Executes the instruction at ip.
+    fn execute(&mut self) -> Result<()> {
+        let ip = self.ip;
+        let inst = self.memory.get(ip)?;
+        let (opc, inst) = getop(inst)?;
+        let (m_a, inst) = getm(inst)?;
+        let (m_b, inst) = getm(inst)?;
+        let (m_c, _) = getm(inst)?;
+
+        let instr = if self.debug.tracing() {
+            self.debug.values.clear();
+            let params = [m_a, m_b, m_c].iter()
+                .take(opc.params())
+                .enumerate()
+                .map(|(i, &mode)| Param { mode, value: self.memory.peek(ip + i + 1) })
+                .collect();
+            Some(Instr { addr: ip, op: opc, params })
+        } else {
+            None
+        };
+
+        self.ip = match opc  {
+            Op::Add => {
+                let a = self.getv(m_a, self.ip + 1)?;
+                let b = self.getv(m_b, self.ip + 2)?;
+                self.setv(m_c, self.ip + 3, a+b)?;
+                self.ip + 4
+            },
+            Op::Mul => {
+                let a = self.getv(m_a, self.ip + 1)?;
+                let b = self.getv(m_b, self.ip + 2)?;
+                self.setv(m_c, self.ip + 3, a*b)?;
+                self.ip + 4
+            },
+            Op::Read => {
+                // read-from-input
+                if let Some(v) = self.input.read() {
+                    self.setv(m_a, self.ip + 1, v)?;
                     self.ip + 2
-                },
-                Op::Halt => {
-                    self.state = State::Done;
-                    0
+                } else {
+                    self.state = State::Waiting;
+                    self.ip
                 }
+            },
+            Op::Jt => {
+                // jump-if-true
+                let a = self.getv(m_a, self.ip + 1)?;
+                let b = self.getv(m_b, self.ip + 2)?;
+                if a != 0 { self.memory.addr(b)? } else { self.ip + 3 }
+            },
+            Op::Jf => {
+                // jump-if-false
+                let a = self.getv(m_a, self.ip + 1)?;
+                let b = self.getv(m_b, self.ip + 2)?;
+                if a == 0 { self.memory.addr(b)? } else { self.ip + 3 }
+            },
+            Op::Lt => {
+                let a = self.getv(m_a, self.ip + 1)?;
+                let b = self.getv(m_b, self.ip + 2)?;
+                let v = i64::from(a < b);
+                self.setv(m_c, self.ip + 3, v)?;
+                self.ip + 4
+            },
+            Op::Eq => {
+                let a = self.getv(m_a, self.ip + 1)?;
+                let b = self.getv(m_b, self.ip + 2)?;
+                let v = i64::from(a == b);
+                self.setv(m_c, self.ip + 3, v)?;
+                self.ip + 4
+            },
+            Op::Arb => {
+                // adjust-relative-base
+                let a = self.getv(m_a, self.ip + 1)?;
+                self.base = self.relative(a)?;
+                self.ip + 2
+            },
+            Op::Write => {
+                // write-to-output
+                let a = self.getv(m_a, self.ip + 1)?;
+                self.output.write(a);
+                self.ip + 2
+            },
+            Op::Halt => {
+                self.state = State::Done;
+                0
+            }
+        };
+
+        if let Some(instr) = instr {
+            // Instructions stalled on input haven't executed yet
+            if self.state != State::Waiting {
+                let values = self.debug.values.drain(..).collect();
+                self.debug.record(Trace { instr, base: self.base, values });
+            }
+        }
+        if let Some(stop) = self.debug.hit.take() {
+            self.debug.stop = Some(stop);
+            if self.state == State::Running {
+                self.state = State::Paused;
             }
         }
 
-        Ok(self.state)
+        Ok(())
     }
 
-    fn getv(&self, mode: Mode, pos: usize) -> i64 {
-        let v = self.memory[pos];
-        match mode {
-            Mode::Position  => self.memory[v as usize],
-            Mode::Relative  => self.memory[(v + self.base) as usize],
+    fn getv(&mut self, mode: Mode, pos: usize) -> Result<i64> {
+        let v = self.memory.get(pos)?;
+        let v = match mode {
+            Mode::Position  => self.load(v)?,
+            Mode::Relative  => self.load(self.relative(v)?)?,
             Mode::Immediate => v,
+        };
+        if self.debug.tracing() {
+            self.debug.values.push(v);
         }
+
+        Ok(v)
     }
 
     fn setv(&mut self, mode: Mode, pos: usize, val: i64) -> Result<()> {
-        let v = self.memory[pos];
-        match mode {
-            Mode::Position  => self.memory[v as usize] = val,
-            Mode::Relative  => self.memory[(v + self.base) as usize] = val,
+        let v = self.memory.get(pos)?;
+        let addr = match mode {
+            Mode::Position  => self.memory.addr(v)?,
+            Mode::Relative  => self.memory.addr(self.relative(v)?)?,
             Mode::Immediate => bail!("Immediate mode not allowed for setting values"),
         };
-        Ok(())
+        if !self.debug.watchpoints.is_empty() {
+            self.debug.access(self.ip, addr, true);
+        }
+        if self.debug.tracing() {
+            self.debug.values.push(val);
+        }
+
+        self.memory.set(addr, val)
+    }
+
+    /// Offsets `v` by the relative base, failing as an address out of
+    /// range would rather than overflowing.
+    fn relative(&self, v: i64) -> Result<i64> {
+        match v.checked_add(self.base) {
+            Some(addr) => Ok(addr),
+            None => bail!("Address {} + {} exceeds memory limit {}", self.base, v, self.memory.limit()),
+        }
+    }
+
+    fn load(&mut self, addr: i64) -> Result<i64> {
+        let addr = self.memory.addr(addr)?;
+        if !self.debug.watchpoints.is_empty() {
+            self.debug.access(self.ip, addr, false);
+        }
+
+        self.memory.get(addr)
     }
 }
 
-#[derive(Debug)]
-enum Op {
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Op {
     Add = 1,
     Mul = 2,
     Read = 3,
@@ -258,14 +572,68 @@ enum Op {
     Halt = 99,
 }
 
-#[derive(Clone, Copy, Debug)]
-enum Mode {
+impl Op {
+    /// Number of parameters following the instruction word.
+    pub fn params(&self) -> usize {
+        match self {
+            Op::Add | Op::Mul | Op::Lt | Op::Eq => 3,
+            Op::Jt | Op::Jf => 2,
+            Op::Read | Op::Write | Op::Arb => 1,
+            Op::Halt => 0,
+        }
+    }
+
+    /// Index of the parameter the instruction writes to, if any.
+    pub fn dest(&self) -> Option<usize> {
+        match self {
+            Op::Add | Op::Mul | Op::Lt | Op::Eq => Some(2),
+            Op::Read => Some(0),
+            _ => None,
+        }
+    }
+
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            Op::Add   => "ADD",
+            Op::Mul   => "MUL",
+            Op::Read  => "IN",
+            Op::Write => "OUT",
+            Op::Jt    => "JT",
+            Op::Jf    => "JF",
+            Op::Lt    => "LT",
+            Op::Eq    => "EQ",
+            Op::Arb   => "ARB",
+            Op::Halt  => "HLT",
+        }
+    }
+
+    pub fn from_mnemonic(s: &str) -> Option<Op> {
+        let op = match s.to_ascii_uppercase().as_str() {
+            "ADD" => Op::Add,
+            "MUL" => Op::Mul,
+            "IN"  => Op::Read,
+            "OUT" => Op::Write,
+            "JT"  => Op::Jt,
+            "JF"  => Op::Jf,
+            "LT"  => Op::Lt,
+            "EQ"  => Op::Eq,
+            "ARB" => Op::Arb,
+            "HLT" => Op::Halt,
+            _ => return None,
+        };
+
+        Some(op)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
     Position = 0,
     Immediate = 1,
     Relative = 2,
 }
 
-fn getop(inst: i64) -> Result<(Op, i64)> {
+pub(crate) fn getop(inst: i64) -> Result<(Op, i64)> {
     let op = match inst % 100 {
         1 => Op::Add,
         2 => Op::Mul,
@@ -283,7 +651,7 @@ fn getop(inst: i64) -> Result<(Op, i64)> {
     Ok((op, inst / 100))
 }
 
-fn getm(inst: i64) -> Result<(Mode, i64)> {
+pub(crate) fn getm(inst: i64) -> Result<(Mode, i64)> {
     let mode = match inst % 10 {
         0 => Mode::Position,
         1 => Mode::Immediate,
@@ -0,0 +1,95 @@
+use anyhow::{bail, Result};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Addresses below this are kept in the dense vector, anything
+/// past it lands in the sparse map.
+const DENSE_LIMIT: usize = 1 << 16;
+
+/// Default upper bound on addressable memory.
+pub const DEFAULT_LIMIT: usize = 1 << 30;
+
+/// Intcode memory: a dense region holding the program image (and
+/// any nearby scratch space) plus a sparse map for far addresses.
+/// Unwritten cells read as zero. Both regions are shared copy-on-write
+/// so cloning memory (for a snapshot or fork) is cheap.
+#[derive(Clone, Debug)]
+pub struct Memory {
+    dense: Arc<Vec<i64>>,
+    sparse: Arc<HashMap<usize, i64>>,
+    limit: usize,
+}
+
+impl Memory {
+    pub fn new(limit: usize) -> Self {
+        Memory { dense: Arc::default(), sparse: Arc::default(), limit }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit
+    }
+
+    /// Validates an address calculated by the program.
+    pub fn addr(&self, addr: i64) -> Result<usize> {
+        if addr < 0 {
+            bail!("Negative address: {}", addr)
+        }
+        let addr = addr as usize;
+        if addr >= self.limit {
+            bail!("Address {} exceeds memory limit {}", addr, self.limit)
+        }
+
+        Ok(addr)
+    }
+
+    pub fn get(&self, addr: usize) -> Result<i64> {
+        if addr >= self.limit {
+            bail!("Address {} exceeds memory limit {}", addr, self.limit)
+        }
+
+        Ok(self.peek(addr))
+    }
+
+    pub fn set(&mut self, addr: usize, v: i64) -> Result<()> {
+        if addr >= self.limit {
+            bail!("Address {} exceeds memory limit {}", addr, self.limit)
+        }
+        self.poke(addr, v);
+
+        Ok(())
+    }
+
+    /// Reads a cell without checking the limit.
+    pub fn peek(&self, addr: usize) -> i64 {
+        if addr < self.dense.len() {
+            self.dense[addr]
+        } else {
+            self.sparse.get(&addr).copied().unwrap_or(0)
+        }
+    }
+
+    /// Writes a cell without checking the limit.
+    pub fn poke(&mut self, addr: usize, v: i64) {
+        if addr < self.dense.len() {
+            Arc::make_mut(&mut self.dense)[addr] = v
+        } else if addr < DENSE_LIMIT {
+            let dense = Arc::make_mut(&mut self.dense);
+            dense.resize(addr + 1, 0);
+            dense[addr] = v
+        } else if v == 0 {
+            if self.sparse.contains_key(&addr) {
+                Arc::make_mut(&mut self.sparse).remove(&addr);
+            }
+        } else {
+            Arc::make_mut(&mut self.sparse).insert(addr, v);
+        }
+    }
+}
+
+impl Default for Memory {
+    fn default() -> Self { Self::new(DEFAULT_LIMIT) }
+}
@@ -0,0 +1,336 @@
+//! Networks of Vms wired together by address, either through direct
+//! pipe links (amplifier chains) or by routing fixed size packets whose
+//! first value is the destination address (NICs).
+use anyhow::{anyhow, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+use crate::{Pipe, State, Vm};
+
+const TICK: Duration = Duration::from_millis(1);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Packet {
+    pub dest: i64,
+    pub data: Vec<i64>,
+}
+
+/// Things the router hook gets to see.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Event {
+    /// A packet addressed to a node not on the network.
+    Packet(Packet),
+    /// Every node is waiting on input and nothing is in flight.
+    Idle,
+}
+
+#[derive(Debug, Default)]
+pub struct Network {
+    nodes: BTreeMap<i64, Vm>,
+    packet_len: usize,
+    idle_input: Option<i64>,
+}
+
+impl Network {
+    /// A network of directly linked nodes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A network routing packets of `len` values (including the
+    /// destination address) between nodes.
+    pub fn with_packets(len: usize) -> Self {
+        Network { packet_len: len, ..Self::default() }
+    }
+
+    /// Value handed to nodes waiting on an empty input, so they can
+    /// poll rather than block (-1 for the NICs).
+    pub fn set_idle_input(&mut self, v: i64) {
+        self.idle_input = Some(v)
+    }
+
+    /// Adds a Vm at the given address. Vms which haven't been started
+    /// yet are started the first time the network runs.
+    pub fn add(&mut self, addr: i64, vm: Vm) {
+        self.nodes.insert(addr, vm);
+    }
+
+    /// Feeds the output of one node directly into the input of another.
+    /// Anything already queued for the receiving node is carried over.
+    pub fn link(&mut self, from: i64, to: i64) -> Result<()> {
+        let (_, output) = self.node(from)?.pipes();
+        let vm = self.node_mut(to)?;
+        let pending = vm.input.drain();
+        vm.input.connect(&output);
+        pending.into_iter().for_each(|v| vm.input.write(v));
+
+        Ok(())
+    }
+
+    pub fn node(&self, addr: i64) -> Result<&Vm> {
+        self.nodes.get(&addr).ok_or_else(|| anyhow!("No node at {}", addr))
+    }
+
+    pub fn node_mut(&mut self, addr: i64) -> Result<&mut Vm> {
+        self.nodes.get_mut(&addr).ok_or_else(|| anyhow!("No node at {}", addr))
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.nodes.values().all(|vm| vm.is_done())
+    }
+
+    /// Delivers a packet to a node on the network.
+    pub fn send(&mut self, packet: &Packet) -> Result<()> {
+        let vm = self.node_mut(packet.dest)?;
+        vm.pipes().0.write_all(&packet.data);
+
+        Ok(())
+    }
+
+    /// Runs the nodes round robin until they're all done or the network
+    /// goes idle. Packets for unknown addresses are dropped.
+    pub fn run(&mut self) -> Result<()> {
+        self.run_with(|_, _| None::<()>).map(|_| ())
+    }
+
+    /// Runs the nodes round robin, passing packets for unknown addresses
+    /// and idle notifications to `router`. The router can queue packets
+    /// for delivery and stops the network by returning a value. If the
+    /// network is idle and the router has nothing to send, the run ends.
+    pub fn run_with<T, F>(&mut self, mut router: F) -> Result<Option<T>>
+        where F: FnMut(Event, &mut Vec<Packet>) -> Option<T>
+    {
+        let mut partial = HashMap::new();
+        let mut outbox = Vec::new();
+
+        while !self.is_done() {
+            let mut starved = true;
+            for (addr, vm) in self.nodes.iter_mut() {
+                if vm.is_done() {
+                    continue
+                }
+                let (mut input, _) = vm.pipes();
+                let fed = input.is_empty() && vm.state == State::Waiting;
+                if let (true, Some(v)) = (fed, self.idle_input) {
+                    input.write(v)
+                }
+
+                let state = start(vm)?;
+                let sent = if self.packet_len > 0 {
+                    let buf = partial.entry(*addr).or_insert_with(Vec::new);
+                    packets(vm, buf, self.packet_len, &mut outbox)
+                } else {
+                    false
+                };
+                starved &= state == State::Waiting && !sent && (fed || self.idle_input.is_none());
+            }
+            // Nodes later in the round may have written to earlier ones
+            starved &= self.nodes.values()
+                .filter(|vm| !vm.is_done())
+                .all(|vm| vm.input.is_empty());
+
+            let mut event = if starved { Some(Event::Idle) } else { None };
+            loop {
+                for packet in std::mem::take(&mut outbox) {
+                    if self.nodes.contains_key(&packet.dest) {
+                        self.send(&packet)?;
+                    } else if let Some(t) = router(Event::Packet(packet), &mut outbox) {
+                        return Ok(Some(t))
+                    }
+                }
+                match event.take() {
+                    Some(e) => {
+                        if let Some(t) = router(e, &mut outbox) {
+                            return Ok(Some(t))
+                        }
+                        if outbox.is_empty() {
+                            return Ok(None)
+                        }
+                    },
+                    None => break,
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Runs each node on its own thread until all are done or the
+    /// network goes idle. Packets for unknown addresses are dropped.
+    pub fn run_parallel(&mut self) -> Result<()> {
+        self.run_parallel_with(|_, _| None::<()>).map(|_| ())
+    }
+
+    /// Runs each node on its own thread, calling `router` on this
+    /// thread as for `run_with`.
+    pub fn run_parallel_with<T, F>(&mut self, mut router: F) -> Result<Option<T>>
+        where F: FnMut(Event, &mut Vec<Packet>) -> Option<T>
+    {
+        let packet_len = self.packet_len;
+        let idle_input = self.idle_input;
+        let inputs = self.nodes.iter()
+            .map(|(addr, vm)| (*addr, vm.pipes().0))
+            .collect::<HashMap<_, _>>();
+
+        let halt = AtomicBool::new(false);
+        let activity = AtomicUsize::new(0);
+        let starved = self.nodes.keys()
+            .map(|addr| (*addr, AtomicBool::new(false)))
+            .collect::<HashMap<_, _>>();
+        let (tx, rx) = mpsc::channel();
+
+        thread::scope(|s| {
+            let mut handles = Vec::new();
+            for (addr, vm) in self.nodes.iter_mut() {
+                let tx = tx.clone();
+                let (halt, activity, inputs) = (&halt, &activity, &inputs);
+                let starved = &starved[addr];
+                handles.push(s.spawn(move || {
+                    let result = node(vm, packet_len, idle_input, inputs, &tx, starved, activity, halt);
+                    if result.is_err() {
+                        halt.store(true, Ordering::SeqCst);
+                    }
+                    result
+                }));
+            }
+            drop(tx);
+
+            let result = coordinate(&mut router, &rx, &inputs, &halt, &activity, &starved);
+            halt.store(true, Ordering::SeqCst);
+            for h in handles {
+                h.join().map_err(|_| anyhow!("Network node panicked"))??;
+            }
+
+            result
+        })
+    }
+}
+
+/// Runs a single node on its own thread.
+#[allow(clippy::too_many_arguments)]
+fn node(
+    vm: &mut Vm,
+    packet_len: usize,
+    idle_input: Option<i64>,
+    inputs: &HashMap<i64, Pipe>,
+    tx: &mpsc::Sender<Packet>,
+    starved: &AtomicBool,
+    activity: &AtomicUsize,
+    halt: &AtomicBool,
+) -> Result<()> {
+    let (mut input, _) = vm.pipes();
+    let mut buf = Vec::new();
+    while !halt.load(Ordering::SeqCst) && !vm.is_done() {
+        // Other threads deliver whole packets at any time, so the idle
+        // value only goes in if the pipe is still empty as it's written.
+        let fed = vm.state == State::Waiting && match idle_input {
+            Some(v) => input.write_if_empty(v),
+            None => input.is_empty(),
+        };
+        if !fed {
+            starved.store(false, Ordering::SeqCst);
+            activity.fetch_add(1, Ordering::SeqCst);
+        } else if idle_input.is_none() {
+            starved.store(true, Ordering::SeqCst);
+            input.wait(TICK);
+            continue
+        }
+
+        start(vm)?;
+        let mut out = Vec::new();
+        if packet_len > 0 && packets(vm, &mut buf, packet_len, &mut out) {
+            starved.store(false, Ordering::SeqCst);
+            activity.fetch_add(1, Ordering::SeqCst);
+            for packet in out {
+                match inputs.get(&packet.dest) {
+                    Some(pipe) => deliver(pipe, &packet),
+                    None => { let _ = tx.send(packet); },
+                }
+            }
+        } else if fed {
+            starved.store(true, Ordering::SeqCst);
+            thread::yield_now();
+        }
+    }
+    starved.store(true, Ordering::SeqCst);
+
+    Ok(())
+}
+
+/// Watches the network from the calling thread, handing unroutable
+/// packets and idle notifications to the router.
+fn coordinate<T, F>(
+    router: &mut F,
+    rx: &mpsc::Receiver<Packet>,
+    inputs: &HashMap<i64, Pipe>,
+    halt: &AtomicBool,
+    activity: &AtomicUsize,
+    starved: &HashMap<i64, AtomicBool>,
+) -> Result<Option<T>>
+    where F: FnMut(Event, &mut Vec<Packet>) -> Option<T>
+{
+    let mut outbox = Vec::new();
+    let mut last = usize::MAX;
+    loop {
+        if halt.load(Ordering::SeqCst) {
+            return Ok(None)
+        }
+        let event = match rx.recv_timeout(TICK) {
+            Ok(packet) => Event::Packet(packet),
+            Err(mpsc::RecvTimeoutError::Disconnected) => return Ok(None),
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                // Idle if nothing has happened for a whole tick.
+                let seen = activity.load(Ordering::SeqCst);
+                let idle = seen == last
+                    && starved.values().all(|s| s.load(Ordering::SeqCst))
+                    && inputs.values().all(|p| p.is_empty());
+                last = seen;
+                if !idle {
+                    continue
+                }
+                Event::Idle
+            },
+        };
+
+        let was_idle = event == Event::Idle;
+        if let Some(t) = router(event, &mut outbox) {
+            return Ok(Some(t))
+        }
+        if was_idle && outbox.is_empty() {
+            return Ok(None)
+        }
+        for packet in outbox.drain(..) {
+            if let Some(pipe) = inputs.get(&packet.dest) {
+                deliver(pipe, &packet)
+            }
+        }
+        last = usize::MAX;
+    }
+}
+
+fn deliver(pipe: &Pipe, packet: &Packet) {
+    Pipe::from(pipe).write_all(&packet.data);
+}
+
+/// Starts a Vm which hasn't run yet, otherwise continues it.
+fn start(vm: &mut Vm) -> Result<State> {
+    if vm.state == State::Ready { vm.exec() } else { vm.cont() }
+}
+
+/// Collects complete packets from a node's output, holding on to any
+/// partial packet in `buf`. Returns true if anything was output.
+fn packets(vm: &mut Vm, buf: &mut Vec<i64>, len: usize, out: &mut Vec<Packet>) -> bool {
+    let values = vm.drain();
+    let sent = !values.is_empty();
+    buf.extend(values);
+
+    let n = buf.len() / len * len;
+    out.extend(buf.drain(..n)
+        .collect::<Vec<_>>()
+        .chunks(len)
+        .map(|c| Packet { dest: c[0], data: c[1..].to_vec() }));
+
+    sent
+}
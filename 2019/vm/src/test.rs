@@ -236,3 +236,583 @@ fn large_output() {
   assert_eq!(vm.exec().unwrap(), State::Done);
   assert_eq!(stdout.flush(), 1219070632396864);
 }
+
+#[test]
+fn far_addresses() {
+  // store at 100000 and 10^9, read both back
+  let program = "1101,7,0,100000,1101,9,0,1000000000,4,100000,4,1000000000,99";
+  let mut vm = Vm::new(program).unwrap();
+  let (_, mut stdout) = vm.pipes();
+
+  assert_eq!(vm.exec().unwrap(), State::Done);
+  assert_eq!(stdout.drain(), [7, 9]);
+}
+
+#[test]
+fn relative_scratch_area() {
+  let program = "109,20000,21101,3,4,5,204,5,99";
+  let mut vm = Vm::new(program).unwrap();
+  let (_, mut stdout) = vm.pipes();
+
+  assert_eq!(vm.exec().unwrap(), State::Done);
+  assert_eq!(stdout.flush(), 7);
+}
+
+#[test]
+fn negative_address() {
+  let program = "109,-5,204,0,99";
+  let mut vm = Vm::new(program).unwrap();
+
+  assert!(vm.exec().is_err());
+}
+
+#[test]
+fn relative_overflow() {
+  // Bases and offsets too big to add fail like any far address
+  for program in [
+    "109,9223372036854775807,109,1,99",
+    "109,9223372036854775807,204,1,99",
+    "109,9223372036854775807,21101,0,0,1,99",
+  ] {
+    let mut vm = Vm::new(program).unwrap();
+    let err = vm.exec().unwrap_err().to_string();
+    assert!(err.contains("exceeds memory limit"), "{}", err);
+  }
+}
+
+#[test]
+fn memory_limit() {
+  let program = "1101,1,1,500,99";
+  let mut vm = Vm::new(program).unwrap();
+  vm.set_memory_limit(100);
+
+  assert!(vm.exec().is_err());
+
+  vm.set_memory_limit(1000);
+  assert_eq!(vm.exec().unwrap(), State::Done);
+}
+
+#[test]
+fn set_addr_past_image() {
+  let program = "4,9000,99";
+  let mut vm = Vm::new(program).unwrap();
+  let (_, mut stdout) = vm.pipes();
+
+  vm.set_addr(9000, 42).unwrap();
+  assert_eq!(vm.exec().unwrap(), State::Done);
+  assert_eq!(stdout.flush(), 42);
+}
+
+#[test]
+fn set_addr_past_limit() {
+  let program = "4,500,99";
+  let mut vm = Vm::new(program).unwrap();
+  vm.set_memory_limit(100);
+
+  let err = vm.set_addr(500, 42).unwrap_err().to_string();
+  assert!(err.contains("exceeds memory limit"), "{}", err);
+
+  vm.set_memory_limit(1000);
+  vm.set_addr(500, 42).unwrap();
+  assert_eq!(vm.exec().unwrap(), State::Done);
+  assert_eq!(vm.pipes().1.flush(), 42);
+}
+
+fn round_trip(program: &str) {
+  let listing = disasm::listing(program).unwrap();
+  let assembled = asm::assemble(&listing).unwrap();
+  assert_eq!(assembled, program.trim(), "\n{}", listing);
+}
+
+#[test]
+fn round_trip_program() {
+  round_trip(PROGRAM);
+}
+
+#[test]
+fn round_trip_examples() {
+  round_trip("3,9,8,9,10,9,4,9,99,-1,8");
+  round_trip("3,3,1105,-1,9,1101,0,0,12,4,12,99,1");
+  round_trip("109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99");
+  round_trip("3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31,\
+              1106,0,36,98,0,0,1002,21,125,20,4,20,1105,1,46,104,\
+              999,1105,1,46,1101,1000,1,20,4,20,1105,1,46,98,99");
+  round_trip("1,2,3");
+  round_trip("");
+}
+
+#[test]
+fn disasm_operands() {
+  let words = disasm::parse("21201,9,3,2").unwrap();
+  let instr = disasm::decode(&words, 0).unwrap();
+  assert_eq!(instr.to_string(), "ADD [rel+9], #3 -> rel+2");
+
+  let words = disasm::parse("1001,9,3,-2").unwrap();
+  let instr = disasm::decode(&words, 0).unwrap();
+  assert_eq!(instr.to_string(), "ADD [pos 9], #3 -> pos -2");
+
+  // immediate destinations and stray mode digits are not code
+  assert!(disasm::decode(&[11101, 1, 2, 3], 0).is_none());
+  assert!(disasm::decode(&[1199], 0).is_none());
+}
+
+#[test]
+fn disasm_data_regions() {
+  let listing = disasm::listing("1105,1,7,5,-3,1000,77,104,42,99").unwrap();
+  let mut lines = listing.lines().map(|l| l.split(';').next().unwrap().trim());
+  assert_eq!(lines.next(), Some("JT #1, #L7"));
+  assert_eq!(lines.next(), Some("db 5, -3, 1000, 77"));
+  assert_eq!(lines.next(), Some("L7:"));
+  assert_eq!(lines.next(), Some("OUT #42"));
+  assert_eq!(lines.next(), Some("HLT"));
+}
+
+#[test]
+fn assemble_labels() {
+  let source = "
+    start:  IN -> pos count       ; read a count
+    loop:   OUT [pos count]
+            ADD [pos count], #-1 -> pos count
+            JT [pos count], #loop
+            HLT
+    count:  db 0
+  ";
+  let program = asm::assemble(source).unwrap();
+  assert_eq!(program, "3,12,4,12,1001,12,-1,12,1005,12,2,99,0");
+
+  let mut vm = Vm::new(&program).unwrap();
+  let (mut stdin, mut stdout) = vm.pipes();
+  stdin.write(3);
+  assert_eq!(vm.exec().unwrap(), State::Done);
+  assert_eq!(stdout.drain(), [3, 2, 1]);
+}
+
+#[test]
+fn assemble_errors() {
+  assert!(asm::assemble("FOO #1").is_err());
+  assert!(asm::assemble("ADD #1, #2 -> #3").is_err());
+  assert!(asm::assemble("ADD #1 -> pos 0").is_err());
+  assert!(asm::assemble("JT #1, #nowhere").is_err());
+  assert!(asm::assemble("a: HLT\na: HLT").is_err());
+}
+
+#[test]
+fn single_step() {
+  let program = "1101,2,3,7,4,7,99,0";
+  let mut vm = Vm::new(program).unwrap();
+  let (_, mut stdout) = vm.pipes();
+
+  vm.reset();
+  assert_eq!(vm.step().unwrap(), State::Paused);
+  assert_eq!(vm.stop_reason(), Some(Stop::Step));
+  assert_eq!(vm.ip(), 4);
+  assert_eq!(vm.peek(7), 5);
+  assert_eq!(vm.step().unwrap(), State::Paused);
+  assert_eq!(stdout.read(), Some(5));
+  assert_eq!(vm.step().unwrap(), State::Done);
+  assert_eq!(vm.stop_reason(), None);
+}
+
+#[test]
+fn breakpoints() {
+  let program = "1101,2,3,7,4,7,99,0";
+  let mut vm = Vm::new(program).unwrap();
+  let (_, mut stdout) = vm.pipes();
+
+  vm.set_breakpoint(4);
+  assert_eq!(vm.exec().unwrap(), State::Paused);
+  assert_eq!(vm.stop_reason(), Some(Stop::Breakpoint(4)));
+  assert_eq!(stdout.read(), None);
+
+  // resuming doesn't trip over the same breakpoint
+  assert_eq!(vm.cont().unwrap(), State::Done);
+  assert_eq!(stdout.read(), Some(5));
+
+  assert!(vm.clear_breakpoint(4));
+  assert_eq!(vm.exec().unwrap(), State::Done);
+}
+
+#[test]
+fn breakpoint_in_loop() {
+  // counts down from 3, outputting each value at 4
+  let program = "1101,0,3,14,4,14,1001,14,-1,14,1005,14,4,99,0";
+  let mut vm = Vm::new(program).unwrap();
+  let (_, mut stdout) = vm.pipes();
+
+  vm.set_breakpoint(4);
+  assert_eq!(vm.exec().unwrap(), State::Paused);
+  for n in [3, 2, 1] {
+    assert_eq!(vm.stop_reason(), Some(Stop::Breakpoint(4)));
+    assert_eq!(stdout.read(), None);
+    let state = vm.cont().unwrap();
+    assert_eq!(stdout.read(), Some(n));
+    assert_eq!(state, if n == 1 { State::Done } else { State::Paused });
+  }
+}
+
+#[test]
+fn watchpoints() {
+  let program = "1101,2,3,9,1001,9,1,9,99,0";
+  let mut vm = Vm::new(program).unwrap();
+
+  vm.set_watchpoint(9, Watch::Read);
+  assert_eq!(vm.exec().unwrap(), State::Paused);
+  assert_eq!(vm.stop_reason(), Some(Stop::Watch { ip: 4, addr: 9, write: false }));
+  assert_eq!(vm.ip(), 8);
+  assert_eq!(vm.peek(9), 6);
+
+  vm.set_watchpoint(9, Watch::Write);
+  assert_eq!(vm.exec().unwrap(), State::Paused);
+  assert_eq!(vm.stop_reason(), Some(Stop::Watch { ip: 0, addr: 9, write: true }));
+  assert_eq!(vm.cont().unwrap(), State::Paused);
+  assert_eq!(vm.stop_reason(), Some(Stop::Watch { ip: 4, addr: 9, write: true }));
+  assert_eq!(vm.cont().unwrap(), State::Done);
+}
+
+#[test]
+fn trace_ring() {
+  let program = "1101,2,3,11,1001,11,1,11,4,11,99,0";
+  let mut vm = Vm::new(program).unwrap();
+
+  vm.set_trace(2);
+  assert_eq!(vm.exec().unwrap(), State::Done);
+
+  let trace = vm.trace().collect::<Vec<_>>();
+  assert_eq!(trace.len(), 2);
+  assert_eq!(trace[0].instr.to_string(), "OUT [pos 11]");
+  assert_eq!(trace[0].values, [6]);
+  assert_eq!(trace[1].instr.op, Op::Halt);
+
+  vm.set_trace(8);
+  vm.exec().unwrap();
+  let trace = vm.trace().collect::<Vec<_>>();
+  assert_eq!(trace.len(), 4);
+  assert_eq!(trace[0].instr.to_string(), "ADD #2, #3 -> pos 11");
+  assert_eq!(trace[0].values, [2, 3, 5]);
+  assert_eq!(trace[1].values, [5, 1, 6]);
+}
+
+const NIC: &str = "
+        IN -> pos addr
+loop:   IN -> pos x
+        EQ [pos x], #-1 -> pos t
+        JT [pos t], #loop
+        IN -> pos y
+        ADD [pos addr], #1 -> pos d
+        OUT [pos d]
+        OUT [pos x]
+        ADD [pos y], #1 -> pos y
+        OUT [pos y]
+        JT #1, #loop
+addr:   db 0
+x:      db 0
+y:      db 0
+t:      db 0
+d:      db 0
+";
+
+fn amplifiers(program: &str, phases: &[i64]) -> net::Network {
+  let mut network = net::Network::new();
+  for i in 0..phases.len() as i64 {
+    network.add(i, Vm::new(program).unwrap());
+    if i > 0 { network.link(i - 1, i).unwrap() }
+  }
+  for (i, &phase) in phases.iter().enumerate() {
+    network.node_mut(i as i64).unwrap().write(phase);
+  }
+  network.node_mut(0).unwrap().write(0);
+
+  network
+}
+
+fn nics(n: i64) -> net::Network {
+  let program = asm::assemble(NIC).unwrap();
+  let mut network = net::Network::with_packets(3);
+  network.set_idle_input(-1);
+  for addr in 0..n {
+    let mut vm = Vm::new(&program).unwrap();
+    vm.write(addr);
+    network.add(addr, vm);
+  }
+
+  network
+}
+
+fn nat(network: &mut net::Network, parallel: bool) -> Option<i64> {
+  let mut last = None;
+  let router = |event, outbox: &mut Vec<net::Packet>| match event {
+    net::Event::Packet(p) => {
+      last = Some(p);
+      None
+    },
+    net::Event::Idle => {
+      let p = last.take()?;
+      if p.data[1] >= 9 { return Some(p.data[1]) }
+      outbox.push(net::Packet { dest: 0, ..p });
+      None
+    },
+  };
+
+  network.send(&net::Packet { dest: 0, data: vec![7, 0] }).unwrap();
+  if parallel {
+    network.run_parallel_with(router).unwrap()
+  } else {
+    network.run_with(router).unwrap()
+  }
+}
+
+#[test]
+fn vm_is_send() {
+  fn is_send<T: Send>() {}
+  is_send::<Vm>();
+  is_send::<Pipe>();
+}
+
+#[test]
+fn network_chain() {
+  let program = "3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0";
+  let mut network = amplifiers(program, &[4,3,2,1,0]);
+  network.run().unwrap();
+  assert!(network.is_done());
+  assert_eq!(network.node(4).unwrap().pipes().1.flush(), 43210);
+
+  let mut network = amplifiers(program, &[4,3,2,1,0]);
+  network.run_parallel().unwrap();
+  assert_eq!(network.node(4).unwrap().pipes().1.flush(), 43210);
+}
+
+#[test]
+fn network_feedback() {
+  let program = "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,\
+                 27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5";
+  for parallel in [false, true] {
+    let mut network = amplifiers(program, &[9,8,7,6,5]);
+    network.link(4, 0).unwrap();
+    if parallel { network.run_parallel().unwrap() } else { network.run().unwrap() }
+    assert!(network.is_done());
+    assert_eq!(network.node(4).unwrap().pipes().1.flush(), 139629729);
+  }
+}
+
+#[test]
+fn network_packets() {
+  assert_eq!(nat(&mut nics(3), false), Some(9));
+  assert_eq!(nat(&mut nics(3), true), Some(9));
+}
+
+// Sends 100 packets to node 0, each x with -x as its y.
+const SENDER: &str = "
+        IN -> pos x
+        MUL [pos x], #1000 -> pos x
+loop:   ADD [pos x], #1 -> pos x
+        MUL [pos x], #-1 -> pos y
+        OUT #0
+        OUT [pos x]
+        OUT [pos y]
+        ADD [pos n], #-1 -> pos n
+        JT [pos n], #loop
+        HLT
+x:      db 0
+y:      db 0
+n:      db 100
+";
+
+// Passes every packet it gets on to address 99.
+const SINK: &str = "
+        IN -> pos x
+loop:   IN -> pos x
+        EQ [pos x], #-1 -> pos t
+        JT [pos t], #loop
+        IN -> pos y
+        OUT #99
+        OUT [pos x]
+        OUT [pos y]
+        JT #1, #loop
+x:      db 0
+y:      db 0
+t:      db 0
+";
+
+#[test]
+fn network_many_senders() {
+  // Packets from different senders mustn't interleave, nor the idle
+  // value get in between a packet's x and y.
+  for parallel in [false, true] {
+    let mut network = net::Network::with_packets(3);
+    network.set_idle_input(-1);
+    for addr in 0..48 {
+      let program = asm::assemble(if addr == 0 { SINK } else { SENDER }).unwrap();
+      let mut vm = Vm::new(&program).unwrap();
+      vm.write(addr);
+      network.add(addr, vm);
+    }
+
+    let mut received = Vec::new();
+    let router = |event, _: &mut Vec<net::Packet>| {
+      if let net::Event::Packet(p) = event { received.push(p.data) }
+      None::<()>
+    };
+    if parallel {
+      network.run_parallel_with(router).unwrap();
+    } else {
+      network.run_with(router).unwrap();
+    }
+
+    received.sort();
+    let mut expected = (1..48)
+      .flat_map(|addr| (1..=100).map(move |i| vec![addr * 1000 + i, -(addr * 1000 + i)]))
+      .collect::<Vec<_>>();
+    expected.sort();
+    assert_eq!(received, expected);
+  }
+}
+
+#[test]
+fn network_idle() {
+  // Nothing to do and the router has nothing to send
+  let mut network = nics(3);
+  assert_eq!(network.run_with(|_, _| None::<()>).unwrap(), None);
+  assert!(!network.is_done());
+}
+
+const LOCK: &str = "
+        ARB #code
+loop:   IN -> pos digit
+        EQ [pos digit], [rel+0] -> pos ok
+        JF [pos ok], #fail
+        ADD [pos left], #-1 -> pos left
+        JF [pos left], #open
+        OUT #1
+        ARB #1
+        JT #1, #loop
+fail:   OUT #0
+        HLT
+open:   OUT #2
+        HLT
+digit:  db 0
+ok:     db 0
+left:   db 3
+code:   db 4, 0, 7
+";
+
+#[test]
+fn snapshot_restore() {
+  let program = asm::assemble(LOCK).unwrap();
+  let mut vm = Vm::new(&program).unwrap();
+  let (mut stdin, mut stdout) = vm.pipes();
+
+  assert_eq!(vm.exec().unwrap(), State::Waiting);
+  stdin.write(4);
+  vm.cont().unwrap();
+  let snapshot = vm.snapshot();
+
+  stdin.write(1);
+  assert_eq!(vm.cont().unwrap(), State::Done);
+  assert_eq!(stdout.drain(), [1, 0]);
+
+  vm.restore(&snapshot);
+  assert_eq!(vm.state(), State::Waiting);
+  assert_eq!(stdout.drain(), [1]);
+  stdin.write(0);
+  stdin.write(7);
+  assert_eq!(vm.cont().unwrap(), State::Done);
+  assert_eq!(stdout.drain(), [1, 2]);
+}
+
+#[test]
+fn fork_search() {
+  let program = asm::assemble(LOCK).unwrap();
+  let mut vm = Vm::new(&program).unwrap();
+  vm.exec().unwrap();
+
+  // Breadth first search for the combination, branching from each
+  // paused Vm rather than replaying the digits tried so far.
+  let mut queue = VecDeque::from([(vm, vec![])]);
+  let mut found = None;
+  while let Some((vm, code)) = queue.pop_front() {
+    for d in 0..10 {
+      let mut branch = vm.fork();
+      branch.write(d);
+      branch.cont().unwrap();
+      let mut code = code.clone();
+      code.push(d);
+      match branch.read() {
+        Some(1) => queue.push_back((branch, code)),
+        Some(2) => found = Some(code),
+        _ => (),
+      }
+    }
+  }
+  assert_eq!(found, Some(vec![4, 0, 7]));
+}
+
+#[test]
+fn fork_is_independent() {
+  let program = "3,9,1001,9,1,9,4,9,99,0";
+  let mut vm = Vm::new(program).unwrap();
+  vm.exec().unwrap();
+  vm.write(5);
+
+  let mut other = vm.fork();
+  other.poke(9, 100);
+  let (mut stdin, _) = other.pipes();
+  stdin.write(40);
+
+  assert_eq!(vm.cont().unwrap(), State::Done);
+  assert_eq!(vm.drain(), [6]);
+
+  // the fork kept its own copy of the queued 5
+  assert_eq!(other.cont().unwrap(), State::Done);
+  assert_eq!(other.drain(), [6]);
+  assert_eq!(vm.peek(9), 6);
+}
+
+const ECHO: &str = "
+start:  OUT #62
+        OUT #10
+        IN -> pos c
+        EQ [pos c], #120 -> pos t
+        JT [pos t], #quit
+echo:   OUT [pos c]
+        EQ [pos c], #10 -> pos t
+        JT [pos t], #start
+        IN -> pos c
+        JT #1, #echo
+quit:   OUT #1000
+        HLT
+c:      db 0
+t:      db 0
+";
+
+#[test]
+fn ascii_lines() {
+  let program = asm::assemble(ECHO).unwrap();
+  let mut vm = ascii::AsciiVm::new(&program).unwrap();
+
+  assert_eq!(vm.start().unwrap(), State::Waiting);
+  assert_eq!(vm.read_line(), Some(">".to_string()));
+  assert_eq!(vm.read_line(), None);
+
+  vm.write_line("hello").unwrap();
+  vm.write_line("there").unwrap();
+  assert_eq!(vm.read_until_prompt(">"), Some("hello\n>".to_string()));
+  assert_eq!(vm.read_until_prompt(">"), Some("\nthere\n>".to_string()));
+  assert_eq!(vm.read_until_prompt(">"), None);
+  assert_eq!(vm.read_text(), "\n");
+
+  assert_eq!(vm.write_line("x").unwrap(), State::Done);
+  assert_eq!(vm.read_text(), "");
+  assert_eq!(vm.take_values(), [1000]);
+}
+
+#[test]
+fn ascii_script() {
+  let program = asm::assemble(ECHO).unwrap();
+  let mut vm = ascii::AsciiVm::new(&program).unwrap();
+
+  let transcript = vm.play(["abc", "x", "never sent"]).unwrap();
+  assert_eq!(transcript.output(), ">\nabc\n>\n");
+  assert_eq!(transcript.values, [1000]);
+  assert_eq!(transcript.to_string(), ">\nabc\nabc\n>\nx\n1000\n");
+  assert_eq!(transcript.entries.len(), 5);
+}
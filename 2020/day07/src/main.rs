@@ -4,11 +4,10 @@ type Bags1<'a> = HashMap<(&'a str, &'a str), Vec<(&'a str, &'a str)>>;
 type Bags2<'a> = HashMap<(&'a str, &'a str), Vec<((&'a str, &'a str), u32)>>;
 
 fn main() {
-    let bags = load_one(include_str!("./input.txt"));
-    println!("Part 1: {}", part_one(&bags));
-
-    let bags = load_two(include_str!("./input.txt"));
-    println!("Part 2: {}", part_two(&bags));
+    aoc::run((
+        |input: &str| part_one(&load_one(input)),
+        |input: &str| part_two(&load_two(input)),
+    ), include_str!("./input.txt"));
 }
 
 fn part_one(bags: &Bags1) -> usize {
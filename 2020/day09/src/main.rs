@@ -1,16 +1,9 @@
 fn main()
 {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    let result = part_one(input, 25, 25);
-    println!("Part 1: {} ({:?})", result, t.elapsed());
-
-    let t = Instant::now();
-    let result = part_two(input, 25, 25);
-    println!("Part 2: {} ({:?})", result, t.elapsed());
+    aoc::run((
+        |input: &str| part_one(input, 25, 25),
+        |input: &str| part_two(input, 25, 25),
+    ), include_str!("../input.txt"));
 }
 
 fn part_one(input: &str, preamble: usize, lookback: usize) -> u64
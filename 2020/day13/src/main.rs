@@ -1,16 +1,6 @@
 fn main()
 {
-    use std::time::Instant;
-
-    let input = include_str!("../example.txt");
-
-    let t = Instant::now();
-    let result = part_one(input);
-    println!("Part 1: {} ({:?})", result, t.elapsed());
-
-    let t = Instant::now();
-    let result = part_two(input);
-    println!("Part 2: {} ({:?})", result, t.elapsed());
+    aoc::run((part_one, part_two), include_str!("../example.txt"));
 }
 
 fn part_one(input: &str) -> u32
@@ -25,39 +15,19 @@ fn part_one(input: &str) -> u32
         .map_or(0, |(n, v)| n * v)
 }
 
-fn part_two(input: &str) -> i64
+fn part_two(input: &str) -> i128
 {
+    use aoc::number::crt;
+
+    // Bus n leaving i minutes after t means t = -i modulo n.
     let (_, s2) = input.split_once('\n').unwrap();
-    let (r, m): (Vec<_>, Vec<_>) = s2.split(',')
+    let buses = s2.split(',')
         .zip(0..)
-        .flat_map(|(s, i)| s.parse::<i64>().map(|n| (i, n)))
-        .unzip();
-    let delta = r[r.len() - 1];
-    let r = r.iter().map(|n| delta - n).collect::<Vec<_>>();
-
-    crt(&r, &m) - r[0]
-}
-
-// Chinese Remainder Theorem
-fn crt(residues: &[i64], moduli: &[i64]) -> i64
-{
-    let prod = moduli.iter().product::<i64>();
-    let sum  = residues.iter()
-        .zip(moduli)
-        .fold(0, |acc, (r, m)| {
-            let p = prod / m;
-            acc + (r * modinv(p, *m).unwrap() * p)
-        });
-
-    sum % prod
-}
-
-fn modinv(x: i64, n: i64) -> Option<i64>
-{
-    use num_integer::Integer;
+        .flat_map(|(s, i)| s.parse::<i128>().map(|n| (-i, n)))
+        .collect::<Vec<_>>();
 
-    let e = x.extended_gcd(&n);
-    (e.gcd == 1).then_some((e.x % n + n) % n)
+    let (t, _) = crt(&buses).expect("the buses never line up");
+    t
 }
 
 
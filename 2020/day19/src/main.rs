@@ -4,10 +4,17 @@ use std::collections::HashMap;
 type Rules<'a> = HashMap<u32, Rule<'a>>;
 
 fn main() {
-    let (mut rules, msgs) = load(include_str!("../input.txt"));
-
-    println!("Part 1: {}", part_one(&rules, msgs));
-    println!("Part 2: {}", part_two(&mut rules, msgs));
+    // Part two changes the rules so each part loads its own.
+    aoc::run((
+        |input: &str| {
+            let (rules, msgs) = load(input);
+            part_one(&rules, msgs)
+        },
+        |input: &str| {
+            let (mut rules, msgs) = load(input);
+            part_two(&mut rules, msgs)
+        },
+    ), include_str!("../input.txt"));
 }
 
 fn part_one(rules: &Rules, msgs: &str) -> usize {
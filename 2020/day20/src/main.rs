@@ -1,4 +1,4 @@
-use bitmatrix::BitMatrix;
+use bitmatrix::{BitMatrix, Orientation};
 
 #[derive(Clone, Copy, Debug)]
 struct Edges {
@@ -27,17 +27,7 @@ type Layout = Vec<Tile>;
 
 fn main()
 {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    let result = part_one(input);
-    println!("Part 1: {} ({:?})", result, t.elapsed());
-
-    let t = Instant::now();
-    let result = part_two(input);
-    println!("Part 2: {} ({:?})", result, t.elapsed());
+    aoc::run((part_one, part_two), include_str!("../input.txt"));
 }
 
 fn part_one(input: &str) -> u64
@@ -64,28 +54,10 @@ fn part_two(input: &str) -> usize
 
     // Can be flipped or rotated with respect to sea monsters.
     let image = build_image(&layout, size, &tiles);
-    let images = [
-        image.clone(),
-        image.rotated_cw(1),
-        image.rotated_cw(2),
-        image.rotated_cw(3),
-        image.flipped_vert(),
-        image.flipped_vert().rotated_cw(1),
-        image.flipped_vert().rotated_cw(2),
-        image.flipped_vert().rotated_cw(3),
-    ];
-
-    for img in images {
-        let n = sea_monsters(&img);
-        if n > 0 { 
-            let rough: usize = img.iter()
-                .map(|row| row.filter(|c| *c).count())
-                .sum();
-            return rough - (n * SEA_MONSTER.len())
-         }
-    }
+    let monster = SEA_MONSTER.join("\n").parse::<BitMatrix>().unwrap();
 
-    0
+    image.find_pattern_any_orientation(&monster)
+        .map_or(0, |(_, found)| image.count_ones() - found.len() * monster.count_ones())
 }
 
 fn load(input: &str) -> Tiles
@@ -94,25 +66,14 @@ fn load(input: &str) -> Tiles
         .map(|s| {
             let (s1, s2) = s.split_once('\n').unwrap();
             let id = s1[5..9].parse::<u32>().unwrap();
-            let data = BitMatrix::from_rows(s2.lines().map(|l| l.bytes()), |c| *c == b'#');
+            let data = s2.parse::<BitMatrix>().unwrap();
 
             (id, data)
         })
         .collect::<Vec<_>>();
 
     scans.iter()
-        .map(|(id, tile)| {
-            [
-                make_tile(*id, tile.clone()),
-                make_tile(*id, tile.rotated_cw(1)),
-                make_tile(*id, tile.rotated_cw(2)),
-                make_tile(*id, tile.rotated_cw(3)),
-                make_tile(*id, tile.flipped_vert()),
-                make_tile(*id, tile.flipped_vert().rotated_cw(1)),
-                make_tile(*id, tile.flipped_vert().rotated_cw(2)),
-                make_tile(*id, tile.flipped_vert().rotated_cw(3)),
-            ]
-        })
+        .map(|(id, tile)| Orientation::ALL.map(|o| make_tile(*id, o.apply(tile))))
         .collect()
 }
 
@@ -214,27 +175,12 @@ fn build_image(layout: &Layout, size: usize, tiles: &Tiles) -> BitMatrix
     })
 }
 
-// 3x20
-// ..................#.
-// #....##....##....###
-// .#..#..#..#..#..#...
-const SEA_MONSTER: [(usize, usize);15] = [
-    (0, 18),
-    (1, 0), (1, 5), (1, 6), (1, 11), (1, 12), (1, 17), (1, 18), (1, 19),
-    (2, 1), (2, 4), (2, 7), (2, 10), (2, 13), (2, 16)
+const SEA_MONSTER: [&str; 3] = [
+    "..................#.",
+    "#....##....##....###",
+    ".#..#..#..#..#..#...",
 ];
 
-fn sea_monsters(m: &BitMatrix) -> usize
-{
-    (0..m.rows - 2)
-        .flat_map(|r| (0..m.cols - 19).map(move |c| (r, c)))
-        .filter(|(r, c)| {
-            SEA_MONSTER.iter().all(|(dr, dc)| m.get(r + dr, c + dc))
-        })
-        .count()
-}
-
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -260,6 +206,43 @@ mod tests {
         assert_eq!(part_one(input), 20899048083289);
     }
 
+    #[test]
+    fn example_image()
+    {
+        // The assembled example image as shown in the puzzle, which
+        // is some orientation of what we build.
+        let expected = "\
+.#.#..#.##...#.##..#####
+###....#.#....#..#......
+##.##.###.#.#..######...
+###.#####...#.#####.#..#
+##.#....#.##.####...#.##
+...########.#....#####.#
+....#..#...##..#.#.###..
+.####...#..#.....#......
+#..#.##..#..###.#.##....
+#.####..#.####.#.#.###..
+###.#.#...#.######.#..##
+#.####....##..########.#
+##..##.#...#...#.#.#.#..
+...#..#..#.#.##..###.###
+.#.#....#.##.#...###.##.
+###.#...#..#.##.######..
+.#.#.###.##.##.#..#.##..
+.####.###.#...###.#..#.#
+..#.#..#..#.#.#.####.###
+#..####...#.#.#.###.###.
+#####..#####...###....##
+#.##..#..#...#..####...#
+.#.###..##..##..####.##.
+...###...##...#...#..###";
+
+        let tiles = load(include_str!("../example.txt"));
+        let layout = layout_tiles(0, 3, vec![], &tiles).unwrap();
+        let image = build_image(&layout, 3, &tiles);
+        assert!(image.orientations().any(|(_, m)| m.to_string() == expected));
+    }
+
     #[test]
     fn example_part_two()
     {
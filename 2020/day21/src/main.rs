@@ -1,14 +1,10 @@
 use std::collections::{HashMap, HashSet};
 
 fn main() {
-    let recipes = load(include_str!("../input.txt"));
-    let allergens = find_allergens(&recipes);
-
-    let count = part_one(&recipes, &allergens);
-    println!("Part 1: {count}");
-
-    let ingredients = part_two(&allergens);
-    println!("Part 2: {ingredients}");
+    aoc::run((
+        |recipes: &[Recipe]| part_one(recipes, &find_allergens(recipes)),
+        |recipes: &[Recipe]| part_two(&find_allergens(recipes)),
+    ), &load(include_str!("../input.txt"))[..]);
 }
 
 fn part_one(recipes: &[Recipe], allergens: &HashMap<&str, &str>) -> usize {
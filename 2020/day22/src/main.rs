@@ -9,13 +9,7 @@ enum Player {
 type Deck = VecDeque<usize>;
 
 fn main() {
-    let decks = load(include_str!("../input.txt"));
-
-    let score = part_one(&decks);
-    println!("Part 1: {score}");
-
-    let score = part_two(&decks);
-    println!("Part 2: {score}");
+    aoc::run((part_one, part_two), &load(include_str!("../input.txt"))[..]);
 }
 
 fn load(input: &str) -> Vec<Vec<usize>> {
@@ -1,18 +1,8 @@
-use std::collections::HashSet;
+use bitmatrix::sparse::SparseBits;
 
 fn main()
 {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    let result = part_one(input);
-    println!("Part 1: {} ({:?})", result, t.elapsed());
-
-    let t = Instant::now();
-    let result = part_two(input);
-    println!("Part 2: {} ({:?})", result, t.elapsed());
+    aoc::run((part_one, part_two), include_str!("../input.txt"));
 }
 
 fn part_one(input: &str) -> usize
@@ -25,70 +15,33 @@ fn part_one(input: &str) -> usize
 fn part_two(input: &str) -> usize
 {
     let tiles = load(input);
-    (0..100).fold(tiles, |tiles, _| {
-        let mut white = HashSet::new();
-        let mut black = HashSet::new();
-
-        tiles.iter().for_each(|tile| {
-            let adjacent = NEIGHBORS.iter()
-                .map(|(dx, dy)| (tile.0 + dx, tile.1 + dy))
-                .fold(0, |acc, p|
-                    if tiles.contains(&p) { 
-                        acc + 1
-                    } else {
-                        white.insert(p);
-                        acc
-                    }
-                );
-            if adjacent == 1 || adjacent == 2 {
-                black.insert(*tile);
-            }
-        });
-        white.iter()
-            .filter(|tile| adjacent(&tiles, tile) == 2)
-            .for_each(|tile| { black.insert(*tile); });
-
-        black
-    })
-    .len()
+    (0..100)
+        .fold(tiles, |tiles, _| tiles.step(&[2], &[1, 2], SparseBits::hex))
+        .len()
 }
 
-fn load(input: &str) -> HashSet<(i32, i32)>
+fn load(input: &str) -> SparseBits<2>
 {
-    use std::collections::HashMap;
-
-    input.lines()
-        .fold(HashMap::new(), |mut acc, s| {
-            let (tile, _) = s.chars().fold(((0, 0), '_'), |((x, y), p), c|
-                (match (p, c) {
-                    // Doubled coordinates
-                    ('n', 'e') => (x + 1, y - 1),
-                    ('s', 'e') => (x + 1, y + 1),
-                    ('n', 'w') => (x - 1, y - 1),
-                    ('s', 'w') => (x - 1, y + 1),
-                    ( _ , 'e') => (x + 2, y),
-                    ( _ , 'w') => (x - 2, y),
-                            _ => (x, y)
-                }, c)
-            );
-            *acc.entry(tile).or_insert(0) += 1;
-            acc
-        })
-        .iter()
-        .filter(|(_, &v)| v % 2 != 0)
-        .map(|(p, _)| *p)
-        .collect()
-}
-
-const NEIGHBORS: [(i32, i32); 6] = [
-    (2, 0), (-2, 0), (1, -1), (-1, -1), (1, 1), (-1, 1)
-];
+    let mut black = SparseBits::new();
+    for s in input.lines() {
+        let (tile, _) = s.chars().fold(([0, 0], '_'), |([q, r], p), c|
+            (match (p, c) {
+                // Axial coordinates
+                ('n', 'e') => [q + 1, r - 1],
+                ('s', 'e') => [q, r + 1],
+                ('n', 'w') => [q, r - 1],
+                ('s', 'w') => [q - 1, r + 1],
+                ( _ , 'e') => [q + 1, r],
+                ( _ , 'w') => [q - 1, r],
+                        _ => [q, r]
+            }, c)
+        );
+        black.update(tile, !black.get(tile));
+    }
 
-fn adjacent(black: &HashSet<(i32, i32)>, (x, y): &(i32, i32)) -> usize {
-    NEIGHBORS.iter().filter(|(dx, dy)| black.contains(&(x + dx, y + dy))).count()
+    black
 }
 
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2,19 +2,15 @@ fn main() {
     let public_keys: Vec<_> = include_str!("../input.txt").lines()
         .map(|l| l.parse::<u64>().unwrap()).collect();
 
-    let enc_key = part_one(public_keys[0], public_keys[1]);
-    println!("Part 1: {enc_key}");
+    aoc::run((|keys: &[u64]| part_one(keys[0], keys[1]),), &public_keys[..]);
 }
 
 fn part_one(card: u64, door: u64) -> u64 {
-    let mut loops = 0;
-    let mut value = 1;
-    while value != card {
-        value = (value * 7) % 20201227;
-        loops += 1;
-    }
+    use aoc::number::{discrete_log, modpow};
+    const M: i128 = 20201227;
 
-    (0..loops).fold(1, |v, _| (v * door) % 20201227)
+    let loops = discrete_log(7, card as i128, M).unwrap();
+    modpow(door as i128, loops as u128, M) as u64
 }
 
 
@@ -1,18 +1,5 @@
 fn main() {
-    use std::fs;
-    use std::time::Instant;
-
-    let crabs = load(&fs::read_to_string("./input.txt").unwrap());
-
-    let t1 = Instant::now();
-    let fuel = part_one(&crabs);
-    let t2 = Instant::now();
-    println!("Part 1: {fuel} {:?}", t2 - t1);
-
-    let t1 = Instant::now();
-    let fuel = part_two(&crabs);
-    let t2 = Instant::now();
-    println!("Part 2: {fuel} {:?}", t2 - t1);
+    aoc::run((part_one, part_two), &load(include_str!("../input.txt")));
 }
 
 fn load(input: &str) -> Vec<i32> {
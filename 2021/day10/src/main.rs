@@ -1,20 +1,9 @@
 
 fn main() {
-    use std::fs;
-    use std::time::Instant;
-    
-    let input = fs::read_to_string("./input.txt").unwrap();
-    let lines = load(&input);
-
-    let t1 = Instant::now();
-    let (score, inc) = part_one(&lines);
-    let t2 = Instant::now();
-    println!("Part 1: {score} {:?}", t2 - t1);
-
-    let t1 = Instant::now();
-    let score = part_two(&inc);
-    let t2 = Instant::now();
-    println!("Part 2: {score} {:?}", t2 - t1);
+    aoc::run((
+        |lines: &[&str]| part_one(lines).0,
+        |lines: &[&str]| part_two(&part_one(lines).1),
+    ), &load(include_str!("../input.txt"))[..]);
 }
 
 fn load(input: &str) -> Vec<&str> {
@@ -1,20 +1,6 @@
 
 fn main() {
-    use std::fs;
-    use std::time::Instant;
-    
-    let input = fs::read_to_string("./input.txt").unwrap();
-    let squid = load(&input);
-
-    let t1 = Instant::now();
-    let flashes = part_one(&squid);
-    let t2 = Instant::now();
-    println!("Part 1: {} {:?}", flashes, t2 - t1);
-
-    let t1 = Instant::now();
-    let step = part_two(&squid);
-    let t2 = Instant::now();
-    println!("Part 2: {} {:?}", step, t2 - t1);
+    aoc::run((part_one, part_two), &load(include_str!("../input.txt")));
 }
 
 fn load(input: &str) -> Vec<u32> {
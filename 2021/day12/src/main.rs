@@ -1,21 +1,7 @@
 use std::collections::HashMap;
 
 fn main() {
-    use std::fs;
-    use std::time::Instant;
-
-    let input = fs::read_to_string("./input.txt").unwrap();
-    let caves = load(&input);
-
-    let t1 = Instant::now();
-    let paths = part_one(&caves);
-    let t2 = Instant::now();
-    println!("Part 1: {} {:?}", paths, t2 - t1);
-
-    let t1 = Instant::now();
-    let paths = part_two(&caves);
-    let t2 = Instant::now();
-    println!("Part 2: {} {:?}", paths, t2 - t1);
+    aoc::run((part_one, part_two), &load(include_str!("../input.txt")));
 }
 
 fn load(input: &str) -> HashMap<&str, Vec<&str>> {
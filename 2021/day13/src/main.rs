@@ -1,23 +1,11 @@
+use bitmatrix::ocr;
 use std::collections::HashSet;
 
 fn main() {
-    use std::fs;
-    use std::time::Instant;
-    
-    let input = fs::read_to_string("./input.txt").unwrap();
-    let (dots, folds) = load(&input);
-
-    let t1 = Instant::now();
-    let visible = part_one(&dots, &folds);
-    let t2 = Instant::now();
-    println!("Part 1: {} {:?}", visible, t2 - t1);
-
-    let t1 = Instant::now();
-    let folded = part_two(&dots, &folds);
-    let t2 = Instant::now();
-    println!("Part 2: {} {:?}", folded.len(), t2 - t1);
-
-//    print(&folded);
+    aoc::run((
+        |(dots, folds): &(HashSet<(u32, u32)>, Vec<Fold>)| part_one(dots, folds),
+        |(dots, folds): &(HashSet<(u32, u32)>, Vec<Fold>)| code(&part_two(dots, folds)),
+    ), &load(include_str!("../input.txt")));
 }
 
 #[derive(Debug)]
@@ -73,21 +61,13 @@ fn fold(axis: &Fold, dot: &(u32, u32)) -> (u32, u32) {
     }
 }
 
-#[allow(dead_code)]
-fn print(dots: &HashSet<(u32, u32)>) {
-    let mut v = dots.iter().collect::<Vec<_>>();
-    v.sort();
-    
-    (0..6).for_each(|y| {
-        (0..40).for_each(|x| {
-            print!(".");
-            let c = if dots.contains(&(x, y)) { '#' } else { '.' };
-            print!("{c}");
-        });
-        println!();
-    })
-}
+// Reads the letters the folded dots spell out.
+fn code(dots: &HashSet<(u32, u32)>) -> String {
+    let cols = dots.iter().map(|(x, _)| x + 1).max().unwrap_or(0);
+    let rows = dots.iter().map(|(_, y)| y + 1).max().unwrap_or(0);
 
+    ocr::read_fn(rows as usize, cols as usize, |y, x| dots.contains(&(x as u32, y as u32))).unwrap()
+}
 
 #[cfg(test)]
 mod tests {
@@ -104,7 +84,6 @@ mod tests {
 
         let folded = part_two(&dots, &folds);
         assert_eq!(folded.len(), 95);
-
-        // HKUJGAJZ
+        assert_eq!(code(&folded), "HKUJGAJZ");
     }
 }
\ No newline at end of file
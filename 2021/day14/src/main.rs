@@ -5,21 +5,10 @@ type Cache  = HashMap<(u16, u8), Counts>;
 type Rules  = BTreeMap<u16, u8>;
 
 fn main() {
-    use std::fs;
-    use std::time::Instant;
-    
-    let input = fs::read_to_string("./input.txt").unwrap();
-    let (template, rules) = load(&input);
-
-    let t1 = Instant::now();
-    let diff = part_one(template, &rules);
-    let t2 = Instant::now();
-    println!("Part 1: {} {:?}", diff, t2 - t1);
-
-    let t1 = Instant::now();
-    let diff = part_two(template, &rules);
-    let t2 = Instant::now();
-    println!("Part 2: {} {:?}", diff, t2 - t1);
+    aoc::run((
+        |(template, rules): &(&[u8], Rules)| part_one(template, rules),
+        |(template, rules): &(&[u8], Rules)| part_two(template, rules),
+    ), &load(include_str!("../input.txt")));
 }
 
 fn load(input: &str) -> (&[u8], Rules) {
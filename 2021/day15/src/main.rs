@@ -1,21 +1,7 @@
 use pathfinding::matrix::Matrix;
 
 fn main() {
-    use std::fs;
-    use std::time::Instant;
-    
-    let input = fs::read_to_string("./input.txt").unwrap();
-    let map = load(&input);
-
-    let t1 = Instant::now();
-    let risk = part_one(&map);
-    let t2 = Instant::now();
-    println!("Part 1: {} {:?}", risk, t2 - t1);
-
-    let t1 = Instant::now();
-    let risk = part_two(&map);
-    let t2 = Instant::now();
-    println!("Part 2: {} {:?}", risk, t2 - t1);
+    aoc::run((part_one, part_two), &load(include_str!("../input.txt")));
 }
 
 fn load(input: &str) -> Matrix<u32> {
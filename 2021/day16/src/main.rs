@@ -1,18 +1,6 @@
 
 fn main() {
-    use std::fs;
-    use std::time::Instant;
-    
-    let input = fs::read_to_string("./input.txt").unwrap();
-    let packets = load(&input);
-
-    let t = Instant::now();
-    let versions = part_one(&packets);
-    println!("Part 1: {} {:?}", versions, t.elapsed());
-
-    let t = Instant::now();
-    let value = part_two(&packets);
-    println!("Part 2: {} {:?}", value, t.elapsed());
+    aoc::run((part_one, part_two), &load(include_str!("../input.txt")));
 }
 
 fn load(input: &str) -> Vec<u8> {
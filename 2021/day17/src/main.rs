@@ -1,19 +1,5 @@
 fn main() {
-    use std::fs;
-    use std::time::Instant;
-    
-    let input = fs::read_to_string("./input.txt").unwrap();
-    let target = load(&input);
-
-    let t1 = Instant::now();
-    let max_y = part_one(&target);
-    let t2 = Instant::now();
-    println!("Part 1: {} {:?}", max_y, t2 - t1);
-
-    let t1 = Instant::now();
-    let hits = part_two(&target);
-    let t2 = Instant::now();
-    println!("Part 2: {} {:?}", hits, t2 - t1);
+    aoc::run((part_one, part_two), &load(include_str!("../input.txt")));
 }
 
 #[derive(Debug)]
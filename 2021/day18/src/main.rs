@@ -10,21 +10,7 @@ lazy_static! {
 }
 
 fn main() {
-    use std::fs;
-    use std::time::Instant;
-    
-    let input = fs::read_to_string("./input.txt").unwrap();
-    let numbers = load(&input);
-
-    let t1 = Instant::now();
-    let magnitude = part_one(&numbers);
-    let t2 = Instant::now();
-    println!("Part 1: {} {:?}", magnitude, t2 - t1);
-
-    let t1 = Instant::now();
-    let magnitude = part_two(&numbers);
-    let t2 = Instant::now();
-    println!("Part 2: {} {:?}", magnitude, t2 - t1);
+    aoc::run((part_one, part_two), &load(include_str!("../input.txt")));
 }
 
 fn load(input: &str) -> Vec<&str> {
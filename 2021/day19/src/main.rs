@@ -32,21 +32,10 @@ lazy_static! {
 }
 
 fn main() {
-    use std::fs;
-    use std::time::Instant;
-    
-    let input = fs::read_to_string("./input.txt").unwrap();
-    let reports = load(&input);
-
-    let t1 = Instant::now();
-    let (beacons, scanners) = part_one(&reports);
-    let t2 = Instant::now();
-    println!("Part 1: {} {:?}", beacons, t2 - t1);
-
-    let t1 = Instant::now();
-    let manhattan = part_two(&scanners);
-    let t2 = Instant::now();
-    println!("Part 2: {} {:?}", manhattan, t2 - t1);
+    aoc::run((
+        |reports: &[Report]| part_one(reports).0,
+        |reports: &[Report]| part_two(&part_one(reports).1),
+    ), &load(include_str!("../input.txt"))[..]);
 }
 
 type Beacon = Point3<i32>;
@@ -1,19 +1,5 @@
 fn main() {
-    use std::fs;
-    use std::time::Instant;
-    
-    let input = fs::read_to_string("./input.txt").unwrap();
-    let positions = load(&input);
-
-    let t1 = Instant::now();
-    let score = part_one(&positions);
-    let t2 = Instant::now();
-    println!("Part 1: {} {:?}", score, t2 - t1);
-
-    let t1 = Instant::now();
-    let universes = part_two(&positions);
-    let t2 = Instant::now();
-    println!("Part 2: {} {:?}", universes, t2 - t1);
+    aoc::run((part_one, part_two), &load(include_str!("../input.txt")));
 }
 
 fn load(input: &str) -> Vec<i32> {
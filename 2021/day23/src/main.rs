@@ -1,16 +1,6 @@
 
 fn main() {
-    use std::time::Instant;
-
-    let t1 = Instant::now();
-    let energy = part_one();
-    let t2 = Instant::now();
-    println!("Part 1: {} {:?}", energy, t2 - t1);
-
-    let t1 = Instant::now();
-    let energy = part_two();
-    let t2 = Instant::now();
-    println!("Part 2: {} {:?}", energy, t2 - t1);
+    aoc::run((|_: &()| part_one(), |_: &()| part_two()), &());
 }
 
 type Shrimp = ((i32, i32), char);
@@ -1,19 +1,5 @@
 fn main() {
-    use std::fs;
-    use std::time::Instant;
-    
-    let input = fs::read_to_string("./input.txt").unwrap();
-    let cmds = load(&input);
-
-    let t1 = Instant::now();
-    let model = part_one(&cmds);
-    let t2 = Instant::now();
-    println!("Part 1: {} {:?}", model, t2 - t1);
-
-    let t1 = Instant::now();
-    let model = part_two(&cmds);
-    let t2 = Instant::now();
-    println!("Part 2: {} {:?}", model, t2 - t1);
+    aoc::run((part_one, part_two), &load(include_str!("../input.txt")));
 }
 
 fn load(input: &str) -> Vec<Cmd> {
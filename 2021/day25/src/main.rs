@@ -1,16 +1,7 @@
 use pathfinding::matrix::Matrix;
 
 fn main() {
-    use std::fs;
-    use std::time::Instant;
-    
-    let input = fs::read_to_string("./input.txt").unwrap();
-    let cucumbers = load(&input);
-
-    let t1 = Instant::now();
-    let steps = part_one(&cucumbers);
-    let t2 = Instant::now();
-    println!("Part 1: {steps} {:?}", t2 - t1);
+    aoc::run((part_one,), &load(include_str!("../input.txt")));
 }
 
 fn load(input: &str) -> Matrix<char> {
@@ -1,15 +1,5 @@
 fn main() {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    let calories = part_one(input);
-    println!("Part 1: {} ({:?})", calories, t.elapsed());
-
-    let t = Instant::now();
-    let calories = part_two(input);
-    println!("Part 2: {} ({:?})", calories, t.elapsed());
+    aoc::run((part_one, part_two), include_str!("../input.txt"));
 }
 
 fn part_one(input: &str) -> i32 {
@@ -1,14 +1,6 @@
 
 fn main() {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    println!("Part 1: {} ({:?})", part_one(input), t.elapsed());
-
-    let t = Instant::now();
-    println!("Part 2: {} ({:?})", part_two(input), t.elapsed());
+    aoc::run((part_one, part_two), include_str!("../input.txt"));
 }
 
 macro_rules! priority {
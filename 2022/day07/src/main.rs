@@ -3,15 +3,7 @@ use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 
 fn main() {
-    use std::time::Instant;
-
-    let input = include_str!("../example.txt");
-
-    let t = Instant::now();
-    println!("Part 1: {} ({:?})", part_one(input), t.elapsed());
-
-    let t = Instant::now();
-    println!("Part 2: {} ({:?})", part_two(input), t.elapsed());
+    aoc::run((part_one, part_two), include_str!("../example.txt"));
 }
 
 fn part_one(input: &str) -> usize {
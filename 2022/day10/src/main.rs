@@ -1,15 +1,8 @@
+use bitmatrix::ocr;
 use vm::Vm;
 
 fn main() {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    println!("Part 1: {} ({:?})", part_one(input), t.elapsed());
-
-    let t = Instant::now();
-    println!("Part 2: {} ({:?})", part_two(input), t.elapsed());
+    aoc::run((part_one, part_two), include_str!("../input.txt"));
 }
 
 fn part_one(input: &str) -> i32 {
@@ -34,16 +27,16 @@ fn part_one(input: &str) -> i32 {
 fn part_two(input: &str) -> String {
     let mut vm = Vm::new(input);
 
-    (0..240)
-        .for_each(|cycle| {
+    let pixels = (0..240)
+        .map(|cycle| {
             let (x, h_pos) = (vm.getx(), cycle % 40);
-            let pixel = if h_pos >= x-1 && h_pos <= x+1 { '#' } else { ' ' };
-            if h_pos == 39 { println!("{pixel}") } else { print!("{pixel}")}
-
             vm.do_tick();
-        });
 
-    "PBZGRAZA".into()
+            h_pos >= x-1 && h_pos <= x+1
+        })
+        .collect::<Vec<_>>();
+
+    ocr::read_fn(6, 40, |r, c| pixels[r * 40 + c]).unwrap()
 }
 
 
@@ -1,29 +1,16 @@
+use aoc::interval::IntervalSet;
 
-fn main() {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    println!("Part 1: {} ({:?})", part_one(input, 2000000), t.elapsed());
 
-    let t = Instant::now();
-    println!("Part 2: {} ({:?})", part_two(input, 4000000), t.elapsed());
+fn main() {
+    aoc::run((
+        |input: &str| part_one(input, 2000000),
+        |input: &str| part_two(input, 4000000),
+    ), include_str!("../input.txt"));
 }
 
 fn part_one(input: &str, y: i32) -> i32 {
-    let mut x1 = i32::MAX;
-    let mut x2 = i32::MIN;
-
-    load(input).iter()
-        .filter(|(p, md)| y <= p.1 + md && y >= p.1 - md)
-        .for_each(|(p, md)| {
-            let xd = md - p.1.abs_diff(y) as i32;
-            x1 = x1.min(p.0 - xd);
-            x2 = x2.max(p.0 + xd);
-        });
-
-    x2 - x1
+    // Less the one beacon on the row.
+    covered(&load(input), y).len() as i32 - 1
 }
 
 fn part_two(input: &str, m: i32) -> i64 {
@@ -47,29 +34,16 @@ fn part_two(input: &str, m: i32) -> i64 {
     0
 }
 
-#[allow(dead_code, clippy::needless_range_loop)]
+#[allow(dead_code)]
 fn part_two_orig(input: &str, m: i32) -> i64 {
     let sensors = load(input);
 
-    for y in 0..=m {
-        let v = covered(&sensors, y);
-
-        let (x1, mut x2) = v[0];
-        if x1 == 1 {
-            return y as i64
-        }
-        for i in 1..v.len() {
-            if v[i].0 > x2 { 
-                return (x2 as i64 + 1) * 4000000 + y as i64;
-            }
-            if x2 < v[i].1 { x2 = v[i].1 }
-        }
-        if x2 < m {
-            return (x2 as i64 + 1) * 4000000 + y as i64
-        }
-    }
-
-    0
+    (0..=m)
+        .find_map(|y| covered(&sensors, y)
+            .complement(0..=m)
+            .first()
+            .map(|x| x as i64 * 4000000 + y as i64))
+        .unwrap_or(0)
 }
 
 fn load(input: &str) -> Vec<((i32, i32), i32)> {
@@ -108,18 +82,14 @@ fn contains(s: &(i32, i32), md: &i32, p: (i32, i32)) -> bool {
     s.0.abs_diff(p.0) + s.1.abs_diff(p.1) <= (*md as u32)
 }
 
-fn covered(sensors: &[((i32, i32), i32)], y: i32) -> Vec<(i32, i32)> {
-    let mut ranges = vec![];
-
+fn covered(sensors: &[((i32, i32), i32)], y: i32) -> IntervalSet<i32> {
     sensors.iter()
         .filter(|(p, md)| y <= p.1 + md && y >= p.1 - md)
-        .for_each(|(p, md)| {
+        .map(|(p, md)| {
             let xd = md - p.1.abs_diff(y) as i32;
-            ranges.push((p.0 - xd, p.0 + xd));
-        });
-
-    ranges.sort();
-    ranges
+            p.0 - xd..=p.0 + xd
+        })
+        .collect()
 }
 
 
@@ -149,5 +119,6 @@ mod tests {
     fn example_part_two() {
         let input = include_str!("../example.txt");
         assert_eq!(part_two(input, 20), 56000011);
+        assert_eq!(part_two_orig(input, 20), 56000011);
     }
 }
@@ -4,15 +4,7 @@ use lazy_static::lazy_static;
 type Shaft = HashSet<(i64, i64)>;
 
 fn main() {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    println!("Part 1: {} ({:?})", part_one(input), t.elapsed());
-
-   let t = Instant::now();
-   println!("Part 2: {} ({:?})", part_two(input, 1_000_000_000_000), t.elapsed());
+    aoc::run((part_one, |input: &str| part_two(input, 1_000_000_000_000)), include_str!("../input.txt"));
 }
 
 fn part_one(input: &str) -> i64 {
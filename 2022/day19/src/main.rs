@@ -1,16 +1,6 @@
 
 fn main() {
-    use std::time::Instant;
-
-    let input = include_str!("../example.txt");
-
-    let t = Instant::now();
-    let quality_level = part_one(input);
-    println!("Part 1: {} ({:?})", quality_level, t.elapsed());
-
-    let t = Instant::now();
-    let geodes = part_two(input);
-    println!("Part 2: {} ({:?})", geodes, t.elapsed());
+    aoc::run((part_one, part_two), include_str!("../example.txt"));
 }
 
 #[allow(dead_code)]
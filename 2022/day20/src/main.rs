@@ -4,12 +4,7 @@ fn main() {
     use std::time::Instant;
 
     let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    println!("Part 1: {} ({:?})", part_one(input), t.elapsed());
-
-    let t = Instant::now();
-    println!("Part 2: {} ({:?})", part_two(input), t.elapsed());
+    aoc::run((part_one, part_two), input);
 
     let t = Instant::now();
     println!("Part 3: {} ({:?})", part_three(input), t.elapsed());
@@ -3,10 +3,7 @@ fn main()
     use std::time::Instant;
 
     let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    let calibration = part_one(input);
-    println!("Part 1:  {} ({:?})", calibration, t.elapsed());
+    aoc::run((part_one, part_two), input);
 
     let t = Instant::now();
     let calibration = part_one_a(input);
@@ -15,10 +12,6 @@ fn main()
     let t = Instant::now();
     let calibration = part_one_b(input);
     println!("Part 1b: {} ({:?})", calibration, t.elapsed());
-
-    let t = Instant::now();
-    let calibration = part_two(input);
-    println!("Part 2:  {} ({:?})", calibration, t.elapsed());
 }
 
 fn part_one(input: &str) -> u32
@@ -2,17 +2,7 @@ use std::ops::RangeInclusive;
 
 fn main()
 {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    let parts = part_one(input);
-    println!("Part 1: {} ({:?})", parts, t.elapsed());
-
-    let t = Instant::now();
-    let gears = part_two(input);
-    println!("Part 2: {} ({:?})", gears, t.elapsed());
+    aoc::run((part_one, part_two), include_str!("../input.txt"));
 }
 
 struct Part {
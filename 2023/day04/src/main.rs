@@ -1,16 +1,6 @@
 fn main()
 {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    let cards = part_one::<10>(input);
-    println!("Part 1: {} ({:?})", cards, t.elapsed());
-
-    let t = Instant::now();
-    let cards = part_two::<10>(input);
-    println!("Part 2: {} ({:?})", cards, t.elapsed());
+    aoc::run((part_one::<10>, part_two::<10>), include_str!("../input.txt"));
 }
 
 fn part_one<const N: usize>(input: &str) -> u32
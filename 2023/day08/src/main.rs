@@ -5,19 +5,12 @@ fn main()
     use std::time::Instant;
 
     let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    let result = part_one(input);
-    println!("Part 1:  {} ({:?})", result, t.elapsed());
+    aoc::run((part_one, part_two), input);
 
     let t = Instant::now();
     let result = part_one_alt(input);
     println!("Part 1a: {} ({:?})", result, t.elapsed());
 
-    let t = Instant::now();
-    let result = part_two(input);
-    println!("Part 2:  {} ({:?})", result, t.elapsed());
-
     let t = Instant::now();
     let result = part_two_alt(input);
     println!("Part 2a: {} ({:?})", result, t.elapsed());
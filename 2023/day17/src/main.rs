@@ -3,17 +3,7 @@ use pathfinding::matrix::Matrix;
 
 fn main()
 {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    let cards = part_one(input);
-    println!("Part 1: {} ({:?})", cards, t.elapsed());
-
-    let t = Instant::now();
-    let cards = part_two(input);
-    println!("Part 2: {} ({:?})", cards, t.elapsed());
+    aoc::run((part_one, part_two), include_str!("../input.txt"));
 }
 
 fn part_one(input: &str) -> u32
@@ -1,16 +1,8 @@
+use aoc::aabox::{AaBox, BoxSet};
+
 fn main()
 {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    let result = part_one(input);
-    println!("Part 1: {} ({:?})", result, t.elapsed());
-
-    let t = Instant::now();
-    let result = part_two(input);
-    println!("Part 2: {} ({:?})", result, t.elapsed());
+    aoc::run((part_one, part_two), include_str!("../input.txt"));
 }
 
 fn part_one(input: &str) -> i64
@@ -87,7 +79,10 @@ fn fill(path: &[(i64, i64)]) -> i64
     v_lines.sort();
     v_lines.dedup();
 
-    let rects: Vec<_> = v_lines.windows(2)
+    // The strips between neighbouring vertical lines split into
+    // rectangles between pairs of edges crossing them. They share the
+    // tiles along their edges, which the union only counts once.
+    let rects: BoxSet<2> = v_lines.windows(2)
         .map(|v| (v[0], v[1]))
         .flat_map(|(x_min, x_max)| {
             let edges: Vec<_> = h_edges.iter()
@@ -95,43 +90,12 @@ fn fill(path: &[(i64, i64)]) -> i64
                 .collect();
             edges.chunks(2)
                 .map(|e| (e[0], e[1]))
-                .map(|(e1, e2)| ((x_min, e1.0.1), (x_max, e2.0.1)))
+                .map(|(e1, e2)| AaBox::inclusive([x_min, e1.0.1], [x_max, e2.0.1]))
                 .collect::<Vec<_>>()
         })
         .collect();
 
-    let area: i64 = rects.iter()
-        .map(|(tl, br)| (br.0 - tl.0 + 1) * (br.1 - tl.1 + 1))
-        .sum();
-
-    area - (0..rects.len() - 1)
-        .map(|i| {
-            let a = rects[i];
-            rects.iter()
-                .skip(i + 1)
-                .filter_map(|b| intersection(a, *b))
-                .map(|r| (r.1.0 - r.0.0 + 1) * (r.1.1 - r.0.1 + 1))
-                .sum::<i64>()
-        })
-        .sum::<i64>()
-}
-
-type Rect = ((i64, i64), (i64, i64));
-fn intersection(
-    ((x1, y1), (x2, y2)): Rect,
-    ((x3, y3), (x4, y4)): Rect
-) -> Option<Rect>
-{
-    let x5 = x1.max(x3);
-    let x6 = x2.min(x4);
-    let y5 = y1.max(y3);
-    let y6 = y2.min(y4);
-
-    if x5 <= x6 && y5 <= y6 {
-        Some(((x5, y5), (x6, y6)))
-    } else {
-        None
-    }
+    rects.volume() as i64
 }
 
 
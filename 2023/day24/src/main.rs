@@ -1,16 +1,6 @@
 fn main()
 {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    let result = part_one(input);
-    println!("Part 1: {} ({:?})", result, t.elapsed());
-
-    let t = Instant::now();
-    let result = part_two(input);
-    println!("Part 2 (mb): {} ({:?})", result, t.elapsed());
+    aoc::run((part_one, part_two), include_str!("../input.txt"));
 }
 
 fn part_one(input: &str) -> u32
@@ -22,147 +12,51 @@ fn part_one(input: &str) -> u32
 
 fn part_two(input: &str) -> i64
 {
-    use std::collections::HashSet;
+    use aoc::linear::{solve, BigRational};
+    use num::ToPrimitive;
 
     let stones = load(input);
 
-    // Find pairs of hail stones whose velocity in a given
-    // axis is the same. These two stones will always have
-    // the same relative position to one another (ie, the 
-    // distance between them remains the same forever). In
-    // order to hit both stones, the rock must be traveling
-    // at a speed intersecting both potential axis positions.
-    // That means the difference between the rock speed and
-    // the stone speed must be a multiple of the difference
-    // between the stone positions. Find a set of values
-    // making this true across a likely range. The intersection
-    // of all potential velocities is the one (or the few but
-    // reelistically the one).
-    let mut vx = HashSet::new();
-    let mut vy = HashSet::new();
-    let mut vz = HashSet::new();
-
-    let mut pair = [stones[0], stones[1]];
-    for (a, i) in stones.iter().zip(1..) {
-        for b in stones.iter().skip(i) {
-            if vx.len() != 1 && a.v[0] == b.v[0] {
-                pair = [*a, *b];
-                let dv = (-1000..=1000)
-                    .filter(|v| *v != a.v[0])
-                    .filter(|v| (a.p[0] - b.p[0]) % (v - a.v[0]) == 0)
-                    .collect();
-                vx = if vx.is_empty() { dv } else { &dv & &vx }
+    // The rock at P moving at V hits stone i when P - p_i and V - v_i
+    // are parallel, so (P - p_i) x (V - v_i) = 0. The P x V term is the
+    // same for every stone and subtracting stone j's equation from
+    // stone i's leaves one which is linear:
+    // P x (v_i - v_j) + (p_i - p_j) x V = p_i x v_i - p_j x v_j
+    // That's three equations per pair of stones, so two pairs pin down
+    // the six unknowns. The numbers get too big for i128 while solving.
+    let big = |n: i64| BigRational::from_integer(n.into());
+    stones.windows(3)
+        .find_map(|w| {
+            let (mut a, mut b) = (vec![], vec![]);
+            for sj in &w[1..] {
+                let [w0, w1, w2] = sub(w[0].v, sj.v);
+                let [d0, d1, d2] = sub(w[0].p, sj.p);
+                a.push([0, w2, -w1, 0, -d2, d1]);
+                a.push([-w2, 0, w0, d2, 0, -d0]);
+                a.push([w1, -w0, 0, -d1, d0, 0]);
+                b.extend(sub(cross(w[0].p, w[0].v), cross(sj.p, sj.v)));
             }
-            if vy.len() != 1 && a.v[1] == b.v[1] {
-                let dv = (-1000..=1000)
-                    .filter(|v| *v != a.v[1])
-                    .filter(|v| (a.p[1] - b.p[1]) % (v - a.v[1]) == 0)
-                    .collect();
-                vy = if vy.is_empty() { dv } else { &dv & &vy }
-            }
-            if vz.len() != 1 && a.v[2] == b.v[2] {
-                let dv = (-1000..=1000)
-                    .filter(|v| *v != a.v[2])
-                    .filter(|v| (a.p[2] - b.p[2]) % (v - a.v[2]) == 0)
-                    .collect();
-                vz = if vz.is_empty() { dv } else { &dv & &vz }
-            }
-        }
-    }
-
-    let rv = [
-        *vx.iter().next().unwrap(),
-        *vy.iter().next().unwrap(),
-        *vz.iter().next().unwrap(),
-    ];
 
-    // Get the difference in x position between a pair of
-    // stones with the same x velocity.
-    let dx = pair[0].p[0] - pair[1].p[0];
-
-    // Calculate the # of jumps to cover that distance given
-    // our rocks velocity.
-    let ep = dx / (pair[0].v[0] - rv[0]);
-
-    // Get the y distance traveled in that many jumps.
-    let dy = ep * rv[1];
-
-    // The y position of stone 1 will be y1 + t * y1_v and to
-    // hit stone 2 it will be y2 + (t + ep) * y2_v. That distance
-    // needs to be dy which gives us:
-    // (y2 + (t + ep) * y2_v) - (y1 + t * y1_v) = dy
-    // We want to know t, the time it takes to get to the position.
-    let y1  = pair[0].p[1];
-    let y2  = pair[1].p[1];
-    let yv1 = pair[0].v[1];
-    let yv2 = pair[1].v[1];
-    let t = (y2 - y1 - dy + ep * yv2) / (yv1 - yv2);
-
-    // Calculate where the stone will be at time t, as our rock
-    // will be there at the same time.
-    let rp_t = [
-        pair[0].p[0] + t * pair[0].v[0],
-        pair[0].p[1] + t * pair[0].v[1],
-        pair[0].p[2] + t * pair[0].v[2],
-    ];
-
-    // The starting position for our rock is then the position at
-    // t = 0 which is the position above minus the rock's distance
-    // traveled over time t.
-    let x = rp_t[0] - t * rv[0];
-    let y = rp_t[1] - t * rv[1];
-    let z = rp_t[2] - t * rv[2];
-
-    x + y + z
+            let a = a.iter()
+                .map(|row| row.iter().map(|n| big(*n)).collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            let b = b.iter().map(|n| big(*n)).collect::<Vec<_>>();
+            solve(&a, &b).filter(|s| s.is_unique())
+        })
+        .map(|s| s.point[..3].iter().sum::<BigRational>())
+        .and_then(|n| n.to_integer().to_i64())
+        .unwrap()
 }
 
-// fn part_two_z3(input: &str) -> i64
-// {
-//     use z3::ast::{Ast, Int};
-//     use z3::{Config, Context, SatResult, Solver};
-
-//     let stones = load(input);
-
-//     let ctx = Context::new(&Config::new());
-//     let solver = Solver::new(&ctx);
-
-//     let px = Int::new_const(&ctx, "px");
-//     let py = Int::new_const(&ctx, "py");
-//     let pz = Int::new_const(&ctx, "pz");
-//     let vx = Int::new_const(&ctx, "vx");
-//     let vy = Int::new_const(&ctx, "vy");
-//     let vz = Int::new_const(&ctx, "vz");
-    
-//     let zero = Int::from_i64(&ctx, 0);
-
-//     for (n, st) in stones.iter().enumerate() {
-//         let t = Int::new_const(&ctx, format!("t{n}"));
-
-//         let x1 = &px + (&vx * &t);
-//         let y1 = &py + (&vy * &t);
-//         let z1 = &pz + (&vz * &t);
-//         let x2 = st.p[0] + (st.v[0] * &t);
-//         let y2 = st.p[1] + (st.v[1] * &t);
-//         let z2 = st.p[2] + (st.v[2] * &t);
-
-//         solver.assert(&t.ge(&zero));
-//         solver.assert(&x1._eq(&x2));
-//         solver.assert(&y1._eq(&y2));
-//         solver.assert(&z1._eq(&z2));
-//     }
-
-//     assert_eq!(solver.check(), SatResult::Sat);
-
-//     let model = solver.get_model().unwrap();
-//     let res_px = model.eval(&px, true).unwrap();
-//     let res_py = model.eval(&py, true).unwrap();
-//     let res_pz = model.eval(&pz, true).unwrap();
-//     let x = res_px.as_i64().unwrap();
-//     let y = res_py.as_i64().unwrap();
-//     let z = res_pz.as_i64().unwrap();
+fn sub(a: [i64;3], b: [i64;3]) -> [i64;3]
+{
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
 
-//     x + y + z
-// }
+fn cross(a: [i64;3], b: [i64;3]) -> [i64;3]
+{
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
 
 fn load(input: &str) -> Vec<Stone>
 {
@@ -255,10 +149,10 @@ mod tests {
         assert_eq!(crossings(&stones, 7.0, 27.0), 2);
     }
 
-    // #[test]
-    // fn example_part_two()
-    // {
-    //     let input = include_str!("../example.txt");
-    //     assert_eq!(part_two(input), 47);
-    // }
+    #[test]
+    fn example_part_two()
+    {
+        let input = include_str!("../example.txt");
+        assert_eq!(part_two(input), 47);
+    }
 }
@@ -5,13 +5,7 @@ type Graph<'a> = UnGraph::<&'a str, usize>;
 
 fn main()
 {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    let result = part_one(input);
-    println!("Part 1: {} ({:?})", result, t.elapsed());
+    aoc::run((part_one,), include_str!("../input.txt"));
 }
 
 fn part_one(input: &str) -> usize
@@ -1,16 +1,6 @@
 fn main()
 {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    let result = part_one_parsing(input);
-    println!("Part 1: {} ({:?})", result, t.elapsed());
-
-    let t = Instant::now();
-    let result = part_two_matching(input);
-    println!("Part 2: {} ({:?})", result, t.elapsed());
+    aoc::run((part_one_parsing, part_two_matching), include_str!("../input.txt"));
 }
 
 fn part_one_parsing(input: &str) -> u32
@@ -4,17 +4,7 @@ type Memos = HashMap<(u64, usize), usize>;
 
 fn main()
 {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    let result = part_one(input);
-    println!("Part 1: {} ({:?})", result, t.elapsed());
-
-    let t = Instant::now();
-    let result = part_two(input, 75);
-    println!("Part 2: {} ({:?})", result, t.elapsed());
+    aoc::run((part_one, |input: &str| part_two(input, 75)), include_str!("../input.txt"));
 }
 
 fn part_one(input: &str) -> usize
@@ -7,17 +7,7 @@ struct Machine {
 
 fn main()
 {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    let result = part_one(input);
-    println!("Part 1: {} ({:?})", result, t.elapsed());
-
-    let t = Instant::now();
-    let result = part_two(input);
-    println!("Part 2: {} ({:?})", result, t.elapsed());
+    aoc::run((part_one, part_two), include_str!("../input.txt"));
 }
 
 fn part_one(input: &str) -> i64
@@ -54,45 +44,23 @@ fn play(m: &Machine) -> Option<i64>
     None
 }
 
-#[allow(non_snake_case)]
 fn replay(m: &Machine) -> Option<i64>
 {
-    // Solve two equations with two unknowns using elimination and then
-    // check to see the solution is an integer value (it's fractional part
-    // is 0.0). Note, we're guessing there is, in fact, just one solution.
-    //
-    // We have two equations;
+    use aoc::linear::solve_integer;
+
+    // The presses solve two equations in two unknowns:
     // a * a.x + b * b.x = prize.x
     // a * a.y + b * b.y = prize.y
-    //
-    // We can scale them up to remove either b or a and get an equation with
-    // a single unknown. For instance, to remove b we can scale by multiplying
-    // each equation by the b value of the other and then subtracting the equations.
-    // We then divide by the a multplier and that'll give us the A presses.
-    // If we know A, then we can calculate B.
-    //
-    // a*26 + b*67 = 10000000012748   a*x1 + b*y1 = c     a*a.0 + b*b.0 = p.0
-    // a*66 + b*21 = 10000000012176   a*x2 + b*y2 = d     a*a.1 + b*b.1 = p.1
-    // a*(26*21) = 10000000012748*21  (x1*y2) = (c*y2)    (a.0*b.1) = (p.0*b.1)
-    // a*(66*67) = 10000000012176*67  (x2*y1) = (d*y1)    (a.1*b.0) = (p.1*b.0)
-    //
-    // (x1*y2) - (x2*y1) = (c*y2) - (d*y1) =>
-    //    (a.0*b.1) - (a.1*b.0) = (p.0*b.1) - (p.1*b.0)
-    // a = ((c*y2) - (d*y1)) / ((x1*y2) - (x2*y1))
-    // a = ((p.0*b.1) - (p.1*b.0)) / ((a.0*b.1) - (a.1*b.0))
-    //
-    // a * 546  = 210000000267708
-    // a * 4422 = 670000000815792
-    // a * 3876 = 460000000548084
-    // a = 118679050709
-
-    let A = (m.p.0 * m.b.1 - m.p.1 * m.b.0) as f64 / (m.a.0 * m.b.1 - m.a.1 * m.b.0) as f64;
-    if A.fract() == 0.0 {
-        let B = (m.p.0 as f64 - A * m.a.0 as f64) / m.b.0 as f64;
-        (B.fract() == 0.0).then_some(A as i64 * 3 + B as i64)
-    } else {
-        None
-    }
+    // and have to be whole and not negative. We're guessing there is,
+    // in fact, just the one solution.
+    let buttons = [
+        vec![m.a.0 as i128, m.b.0 as i128],
+        vec![m.a.1 as i128, m.b.1 as i128],
+    ];
+    let s = solve_integer(&buttons, &[m.p.0 as i128, m.p.1 as i128])?;
+    let (a, b) = (s.point[0], s.point[1]);
+
+    (s.is_unique() && a >= 0 && b >= 0).then_some((a * 3 + b) as i64)
 }
 
 fn load(input: &str) -> Vec<Machine>
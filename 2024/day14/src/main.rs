@@ -10,17 +10,10 @@ struct Robot {
 
 fn main()
 {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    let result = part_one(input, 103, 101);
-    println!("Part 1: {} ({:?})", result, t.elapsed());
-
-    let t = Instant::now();
-    let result = part_two(input, 103, 101);
-    println!("Part 2: {} ({:?})", result, t.elapsed());
+    aoc::run((
+        |input: &str| part_one(input, 103, 101),
+        |input: &str| part_two(input, 103, 101),
+    ), include_str!("../input.txt"));
 }
 
 fn part_one(input: &str, nrows: i32, ncols: i32) -> usize
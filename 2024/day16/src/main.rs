@@ -2,22 +2,12 @@ type Step = ((usize, char), usize);
 
 fn main()
 {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    let result = part_one(input);
-    println!("Part 1: {} ({:?})", result, t.elapsed());
-
-    let t = Instant::now();
-    let result = part_two(input);
-    println!("Part 2: {} ({:?})", result, t.elapsed());
+    aoc::run((part_one, part_two), include_str!("../input.txt"));
 }
 
 fn part_one(input: &str) -> usize
 {
-    use pathfinding::prelude::dijkstra;
+    use utils::dijkstra::dijkstra;
 
     let (start, goal, ncols, maze) = load(input);
     let (_, cost) = dijkstra(
@@ -32,28 +22,18 @@ fn part_one(input: &str) -> usize
 fn part_two(input: &str) -> usize
 {
     use std::collections::HashSet;
-    use pathfinding::prelude::yen;
-
-    // Yen finds the 'k' shortest paths. Manually move
-    // the value up from 2 until the unique set of tiles
-    // across all the paths stops progressing. For our
-    // input data, that was 9. There are mmore paths of
-    // the shortest cost through the maze but they are
-    // just variations on traversing the tiles covered
-    // by the top 9.
-    let k = 9;
+    use utils::dijkstra::dijkstra_all;
+
     let (start, goal, ncols, maze) = load(input);
-    let paths = yen(
+    let paths = dijkstra_all(
         &(start, '>'),
         |&(p, d)| do_moves(p, d, ncols, &maze),
-        |&(p, _)| p == goal,
-        k
-    );
-
-    let cost  = paths[0].1;
-    let tiles = paths.iter()
-        .filter(|(_, c)| *c == cost)
-        .flat_map(|(v, _)| v.iter().map(|p| p.0))
+        |&(p, _)| p == goal
+    ).unwrap();
+
+    let tiles = paths.nodes()
+        .iter()
+        .map(|(p, _)| *p)
         .collect::<HashSet<_>>();
 
     tiles.len()
@@ -2,17 +2,10 @@ use std::collections::HashSet;
 
 fn main()
 {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    let result = part_one(input, 70, 1024);
-    println!("Part 1: {} ({:?})", result, t.elapsed());
-
-    let t = Instant::now();
-    let result = part_two(input, 70);
-    println!("Part 2: {:?} ({:?})", result, t.elapsed());
+    aoc::run((
+        |input: &str| part_one(input, 70, 1024),
+        |input: &str| format!("{:?}", part_two(input, 70)),
+    ), include_str!("../input.txt"));
 }
 
 fn part_one(input: &str, d: u32, b: usize) -> u32
@@ -1,16 +1,9 @@
 fn main()
 {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    let result = part_one(input, 100);
-    println!("Part 1: {} ({:?})", result, t.elapsed());
-
-    let t = Instant::now();
-    let result = part_two(input, 100);
-    println!("Part 2: {} ({:?})", result, t.elapsed());
+    aoc::run((
+        |input: &str| part_one(input, 100),
+        |input: &str| part_two(input, 100),
+    ), include_str!("../input.txt"));
 }
 
 fn part_one(input: &str, limit: usize) -> usize
@@ -7,17 +7,7 @@ type Memos      = HashMap<(String, usize), usize>;
 
 fn main()
 {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    let result = part_one(input);
-    println!("Part 1: {} ({:?})", result, t.elapsed());
-
-    let t = Instant::now();
-    let result = part_two(input, 25);
-    println!("Part 2: {} ({:?})", result, t.elapsed());
+    aoc::run((part_one, |input: &str| part_two(input, 25)), include_str!("../input.txt"));
 }
 
 fn part_one(input: &str) -> usize
@@ -0,0 +1,105 @@
+use std::hash::Hash;
+use num_traits::Zero;
+use crate::{FxIndexMap, reverse_path};
+use crate::dijkstra::SmallestHolder;
+
+// Lifted from the pathfinding crate and modified to use the same
+// parent table as the others. The heuristic mustn't overestimate the
+// cost left from a node or the path found might not be the cheapest.
+pub fn astar<N, C, FN, IN, FH, FS>(
+    start: &N,
+    mut successors: FN,
+    mut heuristic: FH,
+    mut success: FS,
+) -> Option<(Vec<N>, C)>
+where
+    N: Eq + Hash + Clone,
+    C: Zero + Ord + Copy,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, C)>,
+    FH: FnMut(&N) -> C,
+    FS: FnMut(&N) -> bool,
+{
+    use std::collections::BinaryHeap;
+    use indexmap::map::Entry::{Occupied, Vacant};
+
+    // Held by estimated total cost and then by cost so far.
+    let mut to_see = BinaryHeap::new();
+    to_see.push(SmallestHolder {
+        cost: (heuristic(start), Zero::zero()),
+        index: 0,
+    });
+    let mut parents: FxIndexMap<N, (usize, C)> = FxIndexMap::default();
+    parents.insert(start.clone(), (usize::MAX, Zero::zero()));
+    while let Some(SmallestHolder { cost: (_, cost), index }) = to_see.pop() {
+        let successors = {
+            let (node, &(_, c)) = parents.get_index(index).unwrap();
+            if success(node) {
+                let path = reverse_path(&parents, |&(p, _)| p, index);
+                return Some((path, cost));
+            }
+            if cost > c {
+                continue;
+            }
+            successors(node)
+        };
+        for (successor, move_cost) in successors {
+            let new_cost = cost + move_cost;
+            let h;
+            let n;
+            match parents.entry(successor) {
+                Vacant(e) => {
+                    h = heuristic(e.key());
+                    n = e.index();
+                    e.insert((index, new_cost));
+                }
+                Occupied(mut e) => {
+                    if e.get().1 > new_cost {
+                        h = heuristic(e.key());
+                        n = e.index();
+                        e.insert((index, new_cost));
+                    } else {
+                        continue;
+                    }
+                }
+            }
+
+            to_see.push(SmallestHolder {
+                cost: (new_cost + h, new_cost),
+                index: n,
+            });
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // An open 10x10 grid with a wall across row 5 apart from the
+    // last column, costing a step more to move up or down.
+    fn moves(&(r, c): &(i32, i32)) -> Vec<((i32, i32), u32)> {
+        [((r - 1, c), 2), ((r + 1, c), 2), ((r, c - 1), 1), ((r, c + 1), 1)]
+            .into_iter()
+            .filter(|&((r, c), _)| (0..10).contains(&r) && (0..10).contains(&c))
+            .filter(|&((r, c), _)| r != 5 || c == 9)
+            .collect()
+    }
+
+    #[test]
+    fn path() {
+        let goal = (9, 0);
+        let h = |&(r, c): &(i32, i32)| (goal.0 - r).unsigned_abs() * 2 + (goal.1 - c).unsigned_abs();
+        let (path, cost) = astar(&(0, 0), moves, h, |&p| p == goal).unwrap();
+        assert_eq!(cost, 9 * 2 + 9 * 2);
+        assert_eq!((path[0], path[path.len() - 1]), ((0, 0), goal));
+        assert!(path.contains(&(5, 9)));
+
+        // Without a heuristic it's Dijkstra.
+        let (_, cost) = astar(&(0, 0), moves, |_| 0, |&p| p == goal).unwrap();
+        assert_eq!(cost, 36);
+        assert_eq!(astar(&(0, 0), moves, |_| 0, |&p| p == (5, 0)), None);
+    }
+}
@@ -1,8 +1,5 @@
-use std::hash::{BuildHasherDefault, Hash};
-use indexmap::IndexMap;
-use rustc_hash::FxHasher;
-
-type FxIndexMap<K, V> = IndexMap<K, V, BuildHasherDefault<FxHasher>>;
+use std::hash::Hash;
+use crate::{FxIndexMap, reverse_path};
 
 // Lifted from the pathfinding crate and modified to our
 // specific needs as we don't need the whole path, just the
@@ -36,6 +33,75 @@ where
     None
 }
 
+// The whole path, start and end included, to the nearest node
+// satisfying `success`.
+pub fn bfs_path<N, FN, IN, FS>(
+    start: N,
+    mut successors: FN,
+    mut success: FS) -> Option<Vec<N>>
+where
+    N: Copy + Eq + Hash,
+    FN: FnMut(N) -> IN,
+    IN: IntoIterator<Item = N>,
+    FS: FnMut(N) -> bool,
+{
+    use indexmap::map::Entry::Vacant;
+
+    if success(start) {
+        return Some(vec![start]);
+    }
+
+    let mut i = 0;
+    let mut parents: FxIndexMap<N, usize> = FxIndexMap::default();
+    parents.insert(start, usize::MAX);
+    while let Some((node, _)) = parents.get_index(i) {
+        for successor in successors(*node) {
+            if let Vacant(e) = parents.entry(successor) {
+                let n = e.index();
+                e.insert(i);
+                if success(successor) {
+                    return Some(reverse_path(&parents, |&p| p, n));
+                }
+            }
+        }
+        i += 1;
+    }
+
+    None
+}
+
+// Every node no more than `limit` steps from the start, with its
+// distance. Nodes come out in the order they were found, so nearest
+// first.
+pub fn bfs_reach<N, FN, IN>(
+    start: N,
+    limit: usize,
+    mut successors: FN) -> FxIndexMap<N, usize>
+where
+    N: Copy + Eq + Hash,
+    FN: FnMut(N) -> IN,
+    IN: IntoIterator<Item = N>,
+{
+    use indexmap::map::Entry::Vacant;
+
+    let mut i = 0;
+    let mut dists: FxIndexMap<N, usize> = FxIndexMap::default();
+    dists.insert(start, 0);
+    while let Some((node, &dist)) = dists.get_index(i) {
+        if dist == limit {
+            break;
+        }
+        for successor in successors(*node) {
+            if let Vacant(e) = dists.entry(successor) {
+                e.insert(dist + 1);
+            }
+        }
+        i += 1;
+    }
+
+    dists
+}
+
 fn bfs_length<N>(parents: &FxIndexMap<N, usize>, start: usize) -> usize
 {
     let mut count = 0;
@@ -47,4 +113,44 @@ fn bfs_length<N>(parents: &FxIndexMap<N, usize>, start: usize) -> usize
     }
 
     count
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // A 5x5 grid with a wall down column 2 apart from the bottom row.
+    fn moves((r, c): (i32, i32)) -> Vec<(i32, i32)> {
+        [(r - 1, c), (r + 1, c), (r, c - 1), (r, c + 1)]
+            .into_iter()
+            .filter(|&(r, c)| (0..5).contains(&r) && (0..5).contains(&c))
+            .filter(|&(r, c)| c != 2 || r == 4)
+            .collect()
+    }
+
+    #[test]
+    fn length() {
+        assert_eq!(bfs((0, 0), moves, (0, 4)), Some(12));
+        assert_eq!(bfs((0, 0), moves, (0, 2)), None);
+    }
+
+    #[test]
+    fn path() {
+        let path = bfs_path((0, 0), moves, |p| p == (0, 4)).unwrap();
+        assert_eq!(path.len(), 13);
+        assert_eq!((path[0], path[12]), ((0, 0), (0, 4)));
+        assert!(path.windows(2).all(|w| moves(w[0]).contains(&w[1])));
+        assert_eq!(bfs_path((0, 0), moves, |p| p == (0, 0)), Some(vec![(0, 0)]));
+        assert_eq!(bfs_path((0, 0), moves, |p| p.1 == 2 && p.0 < 4), None);
+    }
+
+    #[test]
+    fn reach() {
+        let dists = bfs_reach((0, 0), 2, moves);
+        assert_eq!(dists.len(), 5);
+        assert_eq!(dists[&(0, 0)], 0);
+        assert_eq!(dists[&(1, 1)], 2);
+        assert!(!dists.contains_key(&(3, 0)));
+        assert_eq!(bfs_reach((0, 0), usize::MAX, moves).len(), 21);
+    }
+}
@@ -0,0 +1,337 @@
+use std::hash::Hash;
+use rustc_hash::FxHashMap;
+
+// Where a sequence of states starts repeating: after the first `start`
+// steps it comes back round every `len` steps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub len: usize,
+}
+
+impl Cycle {
+    // The step in the lead in or the first time round the cycle whose
+    // state is the same as step `n`'s.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.len
+        }
+    }
+}
+
+// Floyd's tortoise and hare. Keeps no more than two states about but
+// steps through the sequence a good few more times than `brent`.
+pub fn floyd<S, FS>(start: &S, mut step: FS) -> Cycle
+where
+    S: Clone + PartialEq,
+    FS: FnMut(&mut S),
+{
+    let mut tortoise = start.clone();
+    let mut hare = start.clone();
+    loop {
+        step(&mut tortoise);
+        step(&mut hare);
+        step(&mut hare);
+        if tortoise == hare {
+            break;
+        }
+    }
+
+    // The hare's now a whole number of cycles ahead, so the two meet
+    // where the cycle starts.
+    let mut mu = 0;
+    tortoise = start.clone();
+    while tortoise != hare {
+        step(&mut tortoise);
+        step(&mut hare);
+        mu += 1;
+    }
+
+    let mut lambda = 1;
+    step(&mut hare);
+    while tortoise != hare {
+        step(&mut hare);
+        lambda += 1;
+    }
+
+    Cycle { start: mu, len: lambda }
+}
+
+// Brent's algorithm. Finds the cycle's length first by teleporting
+// the tortoise to the hare at every power of two.
+pub fn brent<S, FS>(start: &S, mut step: FS) -> Cycle
+where
+    S: Clone + PartialEq,
+    FS: FnMut(&mut S),
+{
+    let mut power = 1;
+    let mut lambda = 1;
+    let mut tortoise = start.clone();
+    let mut hare = start.clone();
+    step(&mut hare);
+    while tortoise != hare {
+        if power == lambda {
+            tortoise = hare.clone();
+            power *= 2;
+            lambda = 0;
+        }
+        step(&mut hare);
+        lambda += 1;
+    }
+
+    // Start them `lambda` apart and they meet where the cycle starts.
+    let mut mu = 0;
+    tortoise = start.clone();
+    hare = start.clone();
+    (0..lambda).for_each(|_| step(&mut hare));
+    while tortoise != hare {
+        step(&mut tortoise);
+        step(&mut hare);
+        mu += 1;
+    }
+
+    Cycle { start: mu, len: lambda }
+}
+
+// Finds the cycle by remembering a fingerprint of every state. States
+// with the same key are taken to be the same, so the key can leave out
+// anything that doesn't affect what comes next. Also returns every
+// state up to and including the first repeat.
+pub fn find_by_key<S, K, FS, FK>(start: S, step: FS, key: FK) -> (Cycle, Vec<S>)
+where
+    S: Clone,
+    K: Eq + Hash,
+    FS: FnMut(&mut S),
+    FK: FnMut(&S) -> K,
+{
+    let mut states = vec![];
+    let cycle = run(start, step, key, usize::MAX, |s| states.push(s.clone()));
+
+    (cycle.unwrap(), states)
+}
+
+// The state after `n` steps, going round the cycle rather than taking
+// all of them.
+pub fn nth_state<S, FS>(start: S, step: FS, n: usize) -> S
+where
+    S: Clone + Eq + Hash,
+    FS: FnMut(&mut S),
+{
+    let mut states = vec![];
+    let cycle = run(start, step, |s| s.clone(), n, |s| states.push(s.clone()));
+
+    match cycle {
+        Some(cycle) => states.swap_remove(cycle.reduce(n)),
+        None => states.swap_remove(n),
+    }
+}
+
+// A value derived from the state after `n` steps, for values that go
+// up by the same amount every time round the cycle, like the height
+// of a tower. States are matched by key.
+pub fn nth_value<S, K, FS, FK, FV>(start: S, step: FS, key: FK, mut value: FV, n: usize) -> i64
+where
+    K: Eq + Hash,
+    FS: FnMut(&mut S),
+    FK: FnMut(&S) -> K,
+    FV: FnMut(&S) -> i64,
+{
+    let mut values = vec![];
+    let cycle = run(start, step, key, n, |s| values.push(value(s)));
+
+    match cycle {
+        Some(Cycle { start, len }) if n > start + len => {
+            let (q, r) = ((n - start) / len, (n - start) % len);
+            values[start + r] + q as i64 * (values[start + len] - values[start])
+        }
+        _ => values[n],
+    }
+}
+
+// Steps until a key repeats or `limit` steps are taken, recording
+// every state passed through including the last.
+fn run<S, K, FS, FK, FR>(
+    start: S,
+    mut step: FS,
+    mut key: FK,
+    limit: usize,
+    mut record: FR,
+) -> Option<Cycle>
+where
+    K: Eq + Hash,
+    FS: FnMut(&mut S),
+    FK: FnMut(&S) -> K,
+    FR: FnMut(&S),
+{
+    let mut seen = FxHashMap::default();
+    let mut state = start;
+    let mut i = 0;
+    loop {
+        record(&state);
+        if let Some(prev) = seen.insert(key(&state), i) {
+            return Some(Cycle { start: prev, len: i - prev });
+        }
+        if i == limit {
+            return None;
+        }
+        step(&mut state);
+        i += 1;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // 2017 day 6's memory banks.
+    fn redistribute(banks: &mut [u32; 4]) {
+        let n = *banks.iter().max().unwrap();
+        let i = banks.iter().position(|v| *v == n).unwrap();
+        banks[i] = 0;
+        (1..=n as usize).for_each(|k| banks[(i + k) % 4] += 1);
+    }
+
+    #[test]
+    fn memory_banks() {
+        let banks = [0, 2, 7, 0];
+        let cycle = Cycle { start: 1, len: 4 };
+        assert_eq!(floyd(&banks, redistribute), cycle);
+        assert_eq!(brent(&banks, redistribute), cycle);
+
+        let (found, states) = find_by_key(banks, redistribute, |b| *b);
+        assert_eq!(found, cycle);
+        assert_eq!(states.len(), 6);
+        assert_eq!(states[5], [2, 4, 1, 2]);
+        assert_eq!(nth_state(banks, redistribute, 1_000_000), [1, 3, 4, 1]);
+        assert_eq!(cycle.reduce(1_000_000), 4);
+    }
+
+    // 2023 day 14's rocks, spun by tilting north and turning clockwise
+    // four times over.
+    fn spin(field: &mut Vec<Vec<u8>>) {
+        for _ in 0..4 {
+            for c in 0..field[0].len() {
+                let mut top = 0;
+                for r in 0..field.len() {
+                    match field[r][c] {
+                        b'#' => top = r + 1,
+                        b'O' => {
+                            field[r][c] = b'.';
+                            field[top][c] = b'O';
+                            top += 1;
+                        }
+                        _ => (),
+                    }
+                }
+            }
+            *field = (0..field[0].len())
+                .map(|c| field.iter().rev().map(|row| row[c]).collect())
+                .collect();
+        }
+    }
+
+    #[test]
+    fn dish() {
+        let field = "O....#....\nO.OO#....#\n.....##...\nOO.#O....O\n.O.....O#.\n\
+                     O.#..O.#.#\n..O..#O..O\n.......O..\n#....###..\n#OO..#...."
+            .lines()
+            .map(|l| l.bytes().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        let field = nth_state(field, spin, 1_000_000_000);
+        let load = field.iter()
+            .enumerate()
+            .map(|(r, row)| row.iter().filter(|b| **b == b'O').count() * (field.len() - r))
+            .sum::<usize>();
+        assert_eq!(load, 64);
+    }
+
+    // 2018 day 12's pots, which end up sliding along at the same speed.
+    #[test]
+    fn pots() {
+        let grow = [
+            "...##", "..#..", ".#...", ".#.#.", ".#.##", ".##..", ".####",
+            "#.#.#", "#.###", "##.#.", "##.##", "###..", "###.#", "####.",
+        ];
+        let rules = grow.iter()
+            .map(|r| r.bytes().fold(0, |n, b| n << 1 | (b == b'#') as usize))
+            .collect::<Vec<_>>();
+        let pots = "#..#.#..##......###...###"
+            .bytes()
+            .enumerate()
+            .filter(|(_, b)| *b == b'#')
+            .map(|(i, _)| i as i64)
+            .collect::<Vec<_>>();
+
+        let step = |pots: &mut Vec<i64>| {
+            let (lo, hi) = (pots[0], pots[pots.len() - 1]);
+            *pots = (lo - 2..=hi + 2)
+                .filter(|i| {
+                    let n = (i - 2..=i + 2).fold(0, |n, j| n << 1 | pots.binary_search(&j).is_ok() as usize);
+                    rules.contains(&n)
+                })
+                .collect();
+        };
+        let key = |pots: &Vec<i64>| pots.iter().map(|p| p - pots[0]).collect::<Vec<_>>();
+        let sum = |pots: &Vec<i64>| pots.iter().sum::<i64>();
+
+        assert_eq!(nth_value(pots.clone(), step, key, sum, 20), 325);
+
+        let mut brute = pots.clone();
+        (0..500).for_each(|_| step(&mut brute));
+        assert_eq!(nth_value(pots, step, key, sum, 500), sum(&brute));
+    }
+
+    // 2022 day 17's falling rocks, a row to a byte.
+    #[test]
+    fn tower() {
+        const ROCKS: [&[u8]; 5] = [
+            &[0b1111],
+            &[0b010, 0b111, 0b010],
+            &[0b111, 0b100, 0b100],
+            &[1, 1, 1, 1],
+            &[0b11, 0b11],
+        ];
+        let jets = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>".as_bytes();
+
+        let drop = |(rows, rock, jet): &mut (Vec<u8>, usize, usize)| {
+            let fits = |shape: &[u8], y: usize| shape.iter()
+                .enumerate()
+                .all(|(i, r)| rows.get(y + i).is_none_or(|row| row & r == 0));
+
+            let mut shape = ROCKS[*rock].iter().map(|r| r << 2).collect::<Vec<_>>();
+            let mut y = rows.len() + 3;
+            loop {
+                let moved = match jets[*jet] {
+                    b'>' if shape.iter().all(|r| r & 0x40 == 0) => shape.iter().map(|r| r << 1).collect(),
+                    b'<' if shape.iter().all(|r| r & 1 == 0) => shape.iter().map(|r| r >> 1).collect(),
+                    _ => shape.clone(),
+                };
+                *jet = (*jet + 1) % jets.len();
+                if fits(&moved, y) {
+                    shape = moved;
+                }
+                if y == 0 || !fits(&shape, y - 1) {
+                    break;
+                }
+                y -= 1;
+            }
+
+            for (i, r) in shape.iter().enumerate() {
+                if rows.len() <= y + i {
+                    rows.push(0);
+                }
+                rows[y + i] |= r;
+            }
+            *rock = (*rock + 1) % ROCKS.len();
+        };
+        let key = |(rows, rock, jet): &(Vec<u8>, usize, usize)|
+            (*rock, *jet, rows.iter().rev().take(32).copied().collect::<Vec<_>>());
+        let height = |(rows, _, _): &(Vec<u8>, usize, usize)| rows.len() as i64;
+
+        assert_eq!(nth_value((vec![], 0, 0), drop, key, height, 2022), 3068);
+        assert_eq!(nth_value((vec![], 0, 0), drop, key, height, 1_000_000_000_000), 1514285714288);
+    }
+}
@@ -0,0 +1,91 @@
+use std::hash::Hash;
+use num_traits::Zero;
+use crate::FxIndexMap;
+
+// The most expensive simple path, start and end included, to a node
+// satisfying `success`, along with its cost. The parent table's the
+// path being walked: entries are pushed going forward and popped
+// backing up, and a node can't be gone through twice. That tries
+// every path there is so it's for DAGs and small graphs, like the
+// junctions of a maze.
+pub fn longest_path<N, C, FN, IN, FS>(
+    start: &N,
+    mut successors: FN,
+    mut success: FS,
+) -> Option<(Vec<N>, C)>
+where
+    N: Eq + Hash + Clone,
+    C: Zero + Ord + Copy,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, C)>,
+    FS: FnMut(&N) -> bool,
+{
+    let mut path: FxIndexMap<N, C> = FxIndexMap::default();
+    let mut best = None;
+    path.insert(start.clone(), Zero::zero());
+    walk(&mut path, &mut successors, &mut success, &mut best);
+
+    best
+}
+
+fn walk<N, C, FN, IN, FS>(
+    path: &mut FxIndexMap<N, C>,
+    successors: &mut FN,
+    success: &mut FS,
+    best: &mut Option<(Vec<N>, C)>,
+)
+where
+    N: Eq + Hash + Clone,
+    C: Zero + Ord + Copy,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, C)>,
+    FS: FnMut(&N) -> bool,
+{
+    let (node, &cost) = path.last().unwrap();
+    if success(node) {
+        if best.as_ref().is_none_or(|(_, c)| cost > *c) {
+            *best = Some((path.keys().cloned().collect(), cost));
+        }
+        return;
+    }
+
+    for (successor, move_cost) in successors(node) {
+        if !path.contains_key(&successor) {
+            path.insert(successor, cost + move_cost);
+            walk(path, successors, success, best);
+            path.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn dag() {
+        let edges = |n: &char| match n {
+            'a' => vec![('b', 1), ('c', 5)],
+            'b' => vec![('c', 1), ('d', 7)],
+            'c' => vec![('d', 2)],
+            _   => vec![],
+        };
+        assert_eq!(longest_path(&'a', edges, |&n| n == 'd'), Some((vec!['a', 'b', 'd'], 8)));
+        assert_eq!(longest_path(&'a', edges, |&n| n == 'c'), Some((vec!['a', 'c'], 5)));
+        assert_eq!(longest_path(&'d', edges, |&n| n == 'a'), None);
+    }
+
+    #[test]
+    fn cycles() {
+        // A square with a diagonal from 1 to 3, every edge both ways.
+        let edges = |&n: &u8| match n {
+            0 => vec![(1, 1), (3, 1)],
+            1 => vec![(0, 1), (2, 1), (3, 1)],
+            2 => vec![(1, 1), (3, 1)],
+            _ => vec![(0, 1), (1, 1), (2, 1)],
+        };
+        let (path, cost) = longest_path(&0, edges, |&n| n == 2).unwrap();
+        assert_eq!(cost, 3);
+        assert!(path == [0, 1, 3, 2] || path == [0, 3, 1, 2]);
+    }
+}
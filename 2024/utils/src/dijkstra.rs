@@ -1,10 +1,30 @@
 use std::cmp::Ordering;
-use std::hash::{BuildHasherDefault, Hash};
-use indexmap::IndexMap;
+use std::hash::Hash;
 use num_traits::Zero;
-use rustc_hash::FxHasher;
+use crate::{FxIndexMap, reverse_path};
 
-type FxIndexMap<K, V> = IndexMap<K, V, BuildHasherDefault<FxHasher>>;
+// The cheapest path, start and end included, to a node satisfying
+// `success`, along with its cost.
+pub fn dijkstra<N, C, FN, IN, FS>(
+    start: &N,
+    mut successors: FN,
+    mut success: FS,
+) -> Option<(Vec<N>, C)>
+where
+    N: Eq + Hash + Clone,
+    C: Zero + Ord + Copy,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, C)>,
+    FS: FnMut(&N) -> bool,
+{
+    let (parents, reached) = run_dijkstra(start, None, &mut successors, &mut success);
+    reached.map(|target| {
+        (
+            reverse_path(&parents, |&(p, _)| p, target),
+            parents.get_index(target).unwrap().1 .1,
+        )
+    })
+}
 
 // Lifted from the pathfinding crate and modified to our
 // specific needs.
@@ -21,7 +41,7 @@ where
     IN: IntoIterator<Item = (N, C)>,
     FS: FnMut(&N) -> bool,
 {
-    let (parents, reached) = run_dijkstra(start, limit, &mut successors, &mut success);
+    let (parents, reached) = run_dijkstra(start, Some(limit), &mut successors, &mut success);
     reached.map(|target| {
         (
             reverse_path(&parents, |&(p, _)| p, target),
@@ -30,9 +50,164 @@ where
     })
 }
 
-fn run_dijkstra<N, C, FN, IN, FS>(
+// Every node costing no more than `limit` to get to, with its cost.
+// Nodes come out in the order they were first found, which isn't
+// necessarily cheapest first.
+pub fn dijkstra_reach<N, C, FN, IN>(
     start: &N,
     limit: C,
+    mut successors: FN,
+) -> FxIndexMap<N, C>
+where
+    N: Eq + Hash + Clone,
+    C: Zero + Ord + Copy,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, C)>,
+{
+    let (parents, _) = run_dijkstra(start, Some(limit), &mut successors, &mut |_| false);
+    parents.into_iter().map(|(node, (_, cost))| (node, cost)).collect()
+}
+
+// Every cheapest path to the nodes satisfying `success`. Rather than a
+// single parent each node keeps all the ones it can be reached from
+// for the same cost, so a node can appear on many paths. Move costs
+// need to be more than zero.
+pub fn dijkstra_all<N, C, FN, IN, FS>(
+    start: &N,
+    mut successors: FN,
+    mut success: FS,
+) -> Option<AllPaths<N, C>>
+where
+    N: Eq + Hash + Clone,
+    C: Zero + Ord + Copy,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, C)>,
+    FS: FnMut(&N) -> bool,
+{
+    use std::collections::BinaryHeap;
+    use indexmap::map::Entry::{Occupied, Vacant};
+
+    let mut to_see = BinaryHeap::new();
+    to_see.push(SmallestHolder {
+        cost: Zero::zero(),
+        index: 0,
+    });
+    let mut parents: FxIndexMap<N, (Vec<usize>, C)> = FxIndexMap::default();
+    parents.insert(start.clone(), (vec![], Zero::zero()));
+    let mut targets = vec![];
+    let mut best = None;
+    while let Some(SmallestHolder { cost, index }) = to_see.pop() {
+        if best.is_some_and(|best| cost > best) {
+            break;
+        }
+        let successors = {
+            let (node, (_, c)) = parents.get_index(index).unwrap();
+            if cost > *c {
+                continue;
+            }
+            if success(node) {
+                best = Some(cost);
+                targets.push(index);
+                continue;
+            }
+            successors(node)
+        };
+        for (successor, move_cost) in successors {
+            let new_cost = cost + move_cost;
+            let n;
+            match parents.entry(successor) {
+                Vacant(e) => {
+                    n = e.index();
+                    e.insert((vec![index], new_cost));
+                }
+                Occupied(mut e) => {
+                    if e.get().1 > new_cost {
+                        n = e.index();
+                        e.insert((vec![index], new_cost));
+                    } else {
+                        if e.get().1 == new_cost {
+                            e.get_mut().0.push(index);
+                        }
+                        continue;
+                    }
+                }
+            }
+
+            to_see.push(SmallestHolder {
+                cost: new_cost,
+                index: n,
+            });
+        }
+    }
+
+    best.map(|_| AllPaths { parents, targets })
+}
+
+// The cheapest paths found by `dijkstra_all`.
+pub struct AllPaths<N, C> {
+    parents: FxIndexMap<N, (Vec<usize>, C)>,
+    targets: Vec<usize>,
+}
+
+impl<N, C> AllPaths<N, C>
+where
+    N: Eq + Hash + Clone,
+    C: Copy,
+{
+    pub fn cost(&self) -> C {
+        self.parents[self.targets[0]].1
+    }
+
+    // The nodes satisfying `success` reached for the cheapest cost.
+    pub fn targets(&self) -> impl Iterator<Item = &N> {
+        self.targets.iter().map(|&i| self.parents.get_index(i).unwrap().0)
+    }
+
+    // Every node on at least one of the paths, each once.
+    pub fn nodes(&self) -> Vec<N> {
+        let mut seen = vec![false; self.parents.len()];
+        let mut stack = self.targets.clone();
+        let mut nodes = vec![];
+        while let Some(i) = stack.pop() {
+            if !seen[i] {
+                seen[i] = true;
+                let (node, (from, _)) = self.parents.get_index(i).unwrap();
+                nodes.push(node.clone());
+                stack.extend(from);
+            }
+        }
+
+        nodes
+    }
+
+    // Every one of the paths, start and end included. There can be
+    // a great many of them.
+    pub fn paths(&self) -> Vec<Vec<N>> {
+        let mut paths = vec![];
+        let mut stack = self.targets.iter().map(|&i| vec![i]).collect::<Vec<_>>();
+        while let Some(path) = stack.pop() {
+            let (_, (from, _)) = self.parents.get_index(path[path.len() - 1]).unwrap();
+            if from.is_empty() {
+                paths.push(path.iter()
+                    .rev()
+                    .map(|&i| self.parents.get_index(i).unwrap().0.clone())
+                    .collect());
+            } else {
+                for &i in from {
+                    let mut path = path.clone();
+                    path.push(i);
+                    stack.push(path);
+                }
+            }
+        }
+
+        paths
+    }
+}
+
+fn run_dijkstra<N, C, FN, IN, FS>(
+    start: &N,
+    limit: Option<C>,
     successors: &mut FN,
     stop: &mut FS,
 ) -> (FxIndexMap<N, (usize, C)>, Option<usize>)
@@ -56,7 +231,11 @@ where
     let mut target_reached = None;
     while let Some(SmallestHolder { cost, index }) = to_see.pop() {
         let successors = {
-            let (node, _) = parents.get_index(index).unwrap();
+            let (node, &(_, c)) = parents.get_index(index).unwrap();
+            if cost > c {
+                // Already seen for less.
+                continue;
+            }
             if stop(node) {
                 target_reached = Some(index);
                 break;
@@ -65,7 +244,7 @@ where
         };
         for (successor, move_cost) in successors {
             let new_cost = cost + move_cost;
-            if new_cost <= limit {
+            if limit.is_none_or(|limit| new_cost <= limit) {
                 let n;
                 match parents.entry(successor) {
                     Vacant(e) => {
@@ -92,27 +271,9 @@ where
     (parents, target_reached)
 }
 
-fn reverse_path<N, V, F>(parents: &FxIndexMap<N, V>, mut parent: F, start: usize) -> Vec<N>
-where
-    N: Eq + Hash + Clone,
-    F: FnMut(&V) -> usize,
-{
-    let mut i = start;
-    let path = std::iter::from_fn(|| {
-        parents.get_index(i).map(|(node, value)| {
-            i = parent(value);
-            node
-        })
-    })
-    .collect::<Vec<&N>>();
-    // Collecting the going through the vector is needed to revert the path because the
-    // unfold iterator is not double-ended due to its iterative nature.
-    path.into_iter().rev().cloned().collect()
-}
-
-struct SmallestHolder<K> {
-    cost: K,
-    index: usize,
+pub(crate) struct SmallestHolder<K> {
+    pub(crate) cost: K,
+    pub(crate) index: usize,
 }
 
 impl<K: PartialEq> PartialEq for SmallestHolder<K> {
@@ -133,4 +294,68 @@ impl<K: Ord> Ord for SmallestHolder<K> {
     fn cmp(&self, other: &Self) -> Ordering {
         other.cost.cmp(&self.cost)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Two ways round from 'a' to 'e' costing 6, one costing 7 and a
+    // dead end.
+    fn edges(n: &char) -> Vec<(char, u32)> {
+        match n {
+            'a' => vec![('b', 1), ('c', 2), ('f', 1)],
+            'b' => vec![('d', 3), ('e', 6)],
+            'c' => vec![('d', 2)],
+            'd' => vec![('e', 2)],
+            'f' => vec![('g', 1)],
+            _   => vec![],
+        }
+    }
+
+    #[test]
+    fn path() {
+        let (path, cost) = dijkstra(&'a', edges, |&n| n == 'e').unwrap();
+        assert_eq!(cost, 6);
+        assert!(path == ['a', 'b', 'd', 'e'] || path == ['a', 'c', 'd', 'e']);
+        assert_eq!(dijkstra(&'a', edges, |&n| n == 'a'), Some((vec!['a'], 0)));
+        assert_eq!(dijkstra(&'c', edges, |&n| n == 'b'), None);
+    }
+
+    #[test]
+    fn limited() {
+        assert_eq!(dijkstra_limited(&'a', 5, edges, |&n| n == 'e'), None);
+        assert_eq!(dijkstra_limited(&'a', 6, edges, |&n| n == 'e').unwrap().1, 6);
+    }
+
+    #[test]
+    fn reach() {
+        let costs = dijkstra_reach(&'a', 2, edges);
+        let mut nodes = costs.keys().copied().collect::<Vec<_>>();
+        nodes.sort();
+        assert_eq!(nodes, ['a', 'b', 'c', 'f', 'g']);
+        assert_eq!((costs[&'c'], costs[&'g']), (2, 2));
+        assert_eq!(dijkstra_reach(&'a', 100, edges)[&'e'], 6);
+    }
+
+    #[test]
+    fn all_paths() {
+        let all = dijkstra_all(&'a', edges, |&n| n == 'e').unwrap();
+        assert_eq!(all.cost(), 6);
+        assert_eq!(all.targets().collect::<Vec<_>>(), [&'e']);
+
+        let mut nodes = all.nodes();
+        nodes.sort();
+        assert_eq!(nodes, ['a', 'b', 'c', 'd', 'e']);
+
+        let mut paths = all.paths();
+        paths.sort();
+        assert_eq!(paths, [['a', 'b', 'd', 'e'], ['a', 'c', 'd', 'e']]);
+
+        // Both ends of the cheapest paths to either.
+        let all = dijkstra_all(&'a', edges, |&n| n == 'd' || n == 'g').unwrap();
+        assert_eq!(all.cost(), 2);
+        assert_eq!(all.paths(), [['a', 'f', 'g']]);
+        assert!(dijkstra_all(&'c', edges, |&n| n == 'b').is_none());
+    }
+}
@@ -1,4 +1,35 @@
+pub mod astar;
 pub mod bfs;
+pub mod cycle;
+pub mod dfs;
 pub mod dijkstra;
 pub mod map;
-pub mod ix;
\ No newline at end of file
+pub mod ix;
+
+use std::hash::{BuildHasherDefault, Hash};
+use indexmap::IndexMap;
+use rustc_hash::FxHasher;
+
+/// The parent table every search keeps: nodes in the order they're
+/// found, each with the index of the node it was reached from.
+pub type FxIndexMap<K, V> = IndexMap<K, V, BuildHasherDefault<FxHasher>>;
+
+// Follows the parent indices back from `start` and returns the path
+// from the search's start to it.
+pub(crate) fn reverse_path<N, V, F>(parents: &FxIndexMap<N, V>, mut parent: F, start: usize) -> Vec<N>
+where
+    N: Eq + Hash + Clone,
+    F: FnMut(&V) -> usize,
+{
+    let mut i = start;
+    let path = std::iter::from_fn(|| {
+        parents.get_index(i).map(|(node, value)| {
+            i = parent(value);
+            node
+        })
+    })
+    .collect::<Vec<&N>>();
+    // Collecting the going through the vector is needed to revert the path because the
+    // unfold iterator is not double-ended due to its iterative nature.
+    path.into_iter().rev().cloned().collect()
+}
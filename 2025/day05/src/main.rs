@@ -1,68 +1,39 @@
+use aoc::interval::IntervalSet;
+
 fn main()
 {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    let result = part_one(input);
-    println!("Part 1: {} ({:?})", result, t.elapsed());
-
-    let t = Instant::now();
-    let result = part_two(input);
-    println!("Part 2: {} ({:?})", result, t.elapsed());
+    aoc::run((part_one, part_two), include_str!("../input.txt"));
 }
 
 fn part_one(input: &str) -> usize
 {
     let (s, ids) = input.split_once("\n\n").unwrap();
-    let v = s.lines()
-        .map(|l| {
-            let (s1, s2) = l.split_once('-').unwrap();
-            let l = s1.parse::<u64>().unwrap();
-            let h = s2.parse::<u64>().unwrap();
-
-            (l, h)
-        })
-        .collect::<Vec<_>>();
+    let fresh = load(s);
 
     ids.lines()
         .flat_map(|l| l.parse::<u64>())
-        .filter(|n| v.iter().any(|(l, h)| n >= l && n <= h))
+        .filter(|n| fresh.contains(*n))
         .count()
 }
 
 fn part_two(input: &str) -> usize
 {
     let (s, _) = input.split_once("\n\n").unwrap();
-    let mut v = s.lines()
+
+    load(s).len() as usize
+}
+
+fn load(s: &str) -> IntervalSet<u64>
+{
+    s.lines()
         .map(|l| {
             let (s1, s2) = l.split_once('-').unwrap();
             let l = s1.parse::<u64>().unwrap();
             let h = s2.parse::<u64>().unwrap();
 
-            (l, h)
+            l..=h
         })
-        .collect::<Vec<_>>();
-    v.sort();
-
-    let mut ranges = vec![];
-    let mut curr = v[0];
-    v.iter()
-        .skip(1)
-        .for_each(|&(l, h)| {
-            if l <= curr.1 {
-                curr.1 = curr.1.max(h);
-            } else {
-                ranges.push(curr);
-                curr = (l, h)
-            }
-        });
-    ranges.push(curr);
-
-    ranges.iter()
-        .map(|&(l, h)| (l..=h).count())
-        .sum()
+        .collect()
 }
 
 
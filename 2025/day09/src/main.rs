@@ -1,16 +1,10 @@
-fn main()
-{
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
+use std::ops::RangeInclusive;
 
-    let t = Instant::now();
-    let result = part_one(input);
-    println!("Part 1: {} ({:?})", result, t.elapsed());
+use aoc::interval::IntervalSet;
 
-    let t = Instant::now();
-    let result = part_two(input);
-    println!("Part 2: {} ({:?})", result, t.elapsed());
+fn main()
+{
+    aoc::run((part_one, part_two), include_str!("../input.txt"));
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -96,58 +90,23 @@ fn part_two(input: &str) -> u64
 
 fn is_inside(r: &[Tile;4], v_edges: &[Edge], h_edges: &[Edge]) -> bool
 {
-    let (a, b) = (r[0].x, r[1].x);
-    let v1 = interior_intervals(r[0].y, v_edges);
-    let mut v2 = boundary_intervals(r[0].y, h_edges);
-    if !merge_intervals(&v1, &mut v2).into_iter()
-        .any(|(x1, x2)| x1 <= a && b <= x2) { return false }
-
-    let (a, b) = (r[2].x, r[3].x);
-    let v1 = interior_intervals(r[2].y, v_edges);
-    let mut v2 = boundary_intervals(r[2].y, h_edges);
-    if !merge_intervals(&v1, &mut v2).into_iter()
-        .any(|(x1, x2)| x1 <= a && b <= x2) { return false }
-
-    let (a, b) = (r[0].y, r[2].y);
-    let v1 = interior_intervals(r[0].x, h_edges);
-    let mut v2 = boundary_intervals(r[0].x, v_edges);
-    if !merge_intervals(&v1, &mut v2).into_iter()
-        .any(|(x1, x2)| x1 <= a && b <= x2) { return false }
-
-    let (a, b) = (r[1].y, r[3].y);
-    let v1 = interior_intervals(r[1].x, h_edges);
-    let mut v2 = boundary_intervals(r[1].x, v_edges);
-    if !merge_intervals(&v1, &mut v2).into_iter()
-        .any(|(x1, x2)| x1 <= a && b <= x2) { return false }
-
-    true
+    spans(r[0].y, r[0].x..=r[1].x, v_edges, h_edges) &&
+    spans(r[2].y, r[2].x..=r[3].x, v_edges, h_edges) &&
+    spans(r[0].x, r[0].y..=r[2].y, h_edges, v_edges) &&
+    spans(r[1].x, r[1].y..=r[3].y, h_edges, v_edges)
 }
 
-fn merge_intervals(v1: &[u64], v2: &mut Vec<(u64, u64)>) -> Vec<(u64, u64)>
+// Whether the tiles along the line at n are all inside the shape over
+// the range: between pairs of edges crossing the line or on an edge
+// running along it.
+fn spans(n: u64, range: RangeInclusive<u64>, across: &[Edge], along: &[Edge]) -> bool
 {
-    let mut v = v1.chunks(2)
-        .map(|ch| (ch[0], ch[1]))
-        .collect::<Vec<_>>();
-    v2.append(&mut v);
-    v2.sort();
-    v2.dedup();
-
-    // Merge the intervals
-    let mut v3 = vec![];
-    let mut curr = v2[0];
-    v2.iter()
-        .skip(1)
-        .for_each(|&(l, r)| {
-            if l <= curr.1 {
-                curr.1 = curr.1.max(r);
-            } else {
-                v3.push(curr);
-                curr = (l, r)
-            }
-        });
-    v3.push(curr);
-
-    v3
+    let inside = interior_intervals(n, across);
+    inside.chunks(2)
+        .map(|ch| ch[0]..=ch[1])
+        .chain(boundary_intervals(n, along).into_iter().map(|(p1, p2)| p1..=p2))
+        .collect::<IntervalSet<_>>()
+        .covers(range)
 }
 
 fn interior_intervals(n: u64, edges: &[Edge]) -> Vec<u64>
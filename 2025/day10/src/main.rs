@@ -1,20 +1,6 @@
 fn main()
 {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    let result = part_one(input);
-    println!("Part 1: {} ({:?})", result, t.elapsed());
-
-    let t = Instant::now();
-    let result = part_two(input);
-    println!("Part 2: {} ({:?})", result, t.elapsed());
-
-    // let t = Instant::now();
-    // let result = part_two_dfs(input);
-    // println!("Part 2: {} ({:?})", result, t.elapsed());
+    aoc::run((part_one, part_two), include_str!("../input.txt"));
 }
 
 fn part_one(input: &str) -> usize
@@ -78,36 +64,20 @@ fn part_two(input: &str) -> u32
 
 fn solve(joltage: &[u32], buttons: &[Vec<usize>]) -> u32
 {
-    use good_lp::{default_solver, variable, variables, SolverModel, Solution, constraint, Expression};
-
-    let btns = (0..joltage.len())
-        .map(|i| buttons.iter()
-            .enumerate()
-            .filter(|(_, v)| v.contains(&i))
-            .map(|(i, _)| i)
-            .collect::<Vec<_>>()
-        )
-        .collect::<Vec<_>>();
-
-    let mut vars = variables!();
-    let xs = (0..buttons.len())
-        .map(|_| vars.add(variable().integer().min(0)))
-        .collect::<Vec<_>>();
-
-    let mut model = vars
-        .minimise(xs.iter().copied().sum::<Expression>())
-        .using(default_solver);
-    model.set_parameter("log", "0");
+    use aoc::linear::{Ilp, Relation};
 
+    // The fewest presses with every counter hit exactly: one equation
+    // per counter over the buttons which bump it.
+    let mut ilp = Ilp::minimise(vec![1; buttons.len()]);
     for (i, n) in joltage.iter().enumerate() {
-        let expr: Expression = btns[i].iter().map(|&p| xs[p]).sum();
-        model = model.with(constraint!(expr == *n));
+        let coefs = buttons.iter()
+            .map(|b| b.contains(&i) as i128)
+            .collect();
+        ilp.add(coefs, Relation::Eq, *n as i128);
     }
 
-    let solution = model.solve().unwrap();
-    xs.iter()
-        .map(|xi| solution.value(*xi) as u32)
-        .sum()
+    let (presses, _) = ilp.solve().expect("no press count hits the joltages");
+    presses as u32
 }
 
 #[allow(dead_code)]
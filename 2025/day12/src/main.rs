@@ -2,13 +2,7 @@ use bitmatrix::BitMatrix;
 
 fn main()
 {
-    use std::time::Instant;
-
-    let input = include_str!("../input.txt");
-
-    let t = Instant::now();
-    let result = part_one(input);
-    println!("Part 1: {} ({:?})", result, t.elapsed());
+    aoc::run((part_one,), include_str!("../input.txt"));
 }
 
 fn part_one(input: &str) -> usize
@@ -68,8 +62,8 @@ fn load(input: &str) -> (Vec<BitMatrix>, Vec<Tree>)
     // The first 6 are the presents
     let iter = input.split("\n\n");
     let presents = iter.take(6)
-        .map(|s| s.lines().skip(1))
-        .map(|l| BitMatrix::from_rows(l.map(|s| s.bytes()), |c| *c == b'#'))
+        .map(|s| s.split_once('\n').unwrap().1)
+        .map(|s| s.parse::<BitMatrix>().unwrap())
         .collect();
 
     // The rest are regions an amounts
@@ -0,0 +1,362 @@
+//! Axis aligned boxes in any number of dimensions: the cuboids of
+//! reactor reboots, falling bricks and dug out lagoons. Boxes are half
+//! open, taking in `lo` but stopping short of `hi`, so they split and
+//! tile without off by ones; `inclusive` builds one from the corners
+//! the puzzles give.
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AaBox<const N: usize> {
+    pub lo: [i64; N],
+    pub hi: [i64; N],
+}
+
+impl<const N: usize> AaBox<N> {
+    pub fn new(lo: [i64; N], hi: [i64; N]) -> Self
+    {
+        AaBox { lo, hi }
+    }
+
+    /// The smallest box with the corners `a` and `b` inside it, in
+    /// whichever order they're given.
+    pub fn inclusive(a: [i64; N], b: [i64; N]) -> Self
+    {
+        AaBox {
+            lo: std::array::from_fn(|d| a[d].min(b[d])),
+            hi: std::array::from_fn(|d| a[d].max(b[d]) + 1),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        (0..N).any(|d| self.lo[d] >= self.hi[d])
+    }
+
+    pub fn volume(&self) -> u128
+    {
+        if self.is_empty() {
+            0
+        } else {
+            (0..N).map(|d| (self.hi[d] - self.lo[d]) as u128).product()
+        }
+    }
+
+    pub fn contains(&self, p: [i64; N]) -> bool
+    {
+        (0..N).all(|d| self.lo[d] <= p[d] && p[d] < self.hi[d])
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool
+    {
+        self.intersection(other).is_some()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self>
+    {
+        let b = AaBox {
+            lo: std::array::from_fn(|d| self.lo[d].max(other.lo[d])),
+            hi: std::array::from_fn(|d| self.hi[d].min(other.hi[d])),
+        };
+
+        (!b.is_empty()).then_some(b)
+    }
+
+    /// What's left of this box with `other` taken out, as at most 2N
+    /// disjoint boxes. Slabs are sliced off one axis at a time until
+    /// only the overlap's left.
+    pub fn subtract(&self, other: &Self) -> Vec<Self>
+    {
+        let Some(cut) = self.intersection(other) else { return vec![*self] };
+
+        let mut rest = *self;
+        let mut pieces = vec![];
+        for d in 0..N {
+            if rest.lo[d] < cut.lo[d] {
+                pieces.push(AaBox { hi: with(rest.hi, d, cut.lo[d]), ..rest });
+                rest.lo[d] = cut.lo[d];
+            }
+            if cut.hi[d] < rest.hi[d] {
+                pieces.push(AaBox { lo: with(rest.lo, d, cut.hi[d]), ..rest });
+                rest.hi[d] = cut.hi[d];
+            }
+        }
+
+        pieces
+    }
+
+    pub fn translate(&self, by: [i64; N]) -> Self
+    {
+        AaBox {
+            lo: std::array::from_fn(|d| self.lo[d] + by[d]),
+            hi: std::array::from_fn(|d| self.hi[d] + by[d]),
+        }
+    }
+
+    // Whether the boxes overlap on every axis but the last: one's
+    // directly above the other.
+    fn overlaps_across(&self, other: &Self) -> bool
+    {
+        (0..N - 1).all(|d| self.lo[d] < other.hi[d] && other.lo[d] < self.hi[d])
+    }
+}
+
+fn with<const N: usize>(mut a: [i64; N], d: usize, v: i64) -> [i64; N]
+{
+    a[d] = v;
+    a
+}
+
+/// The union of any number of boxes, kept as disjoint boxes so the
+/// volume's just a sum.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BoxSet<const N: usize> {
+    boxes: Vec<AaBox<N>>,
+}
+
+impl<const N: usize> BoxSet<N> {
+    pub fn new() -> Self
+    {
+        BoxSet { boxes: vec![] }
+    }
+
+    pub fn insert(&mut self, b: AaBox<N>)
+    {
+        if !b.is_empty() {
+            self.remove(&b);
+            self.boxes.push(b);
+        }
+    }
+
+    pub fn remove(&mut self, b: &AaBox<N>)
+    {
+        self.boxes = self.boxes.iter()
+            .flat_map(|c| c.subtract(b))
+            .collect();
+    }
+
+    pub fn contains(&self, p: [i64; N]) -> bool
+    {
+        self.boxes.iter().any(|b| b.contains(p))
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        self.boxes.is_empty()
+    }
+
+    pub fn volume(&self) -> u128
+    {
+        self.boxes.iter().map(|b| b.volume()).sum()
+    }
+
+    /// The disjoint boxes making up the set, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &AaBox<N>>
+    {
+        self.boxes.iter()
+    }
+}
+
+impl<const N: usize> FromIterator<AaBox<N>> for BoxSet<N> {
+    fn from_iter<I: IntoIterator<Item = AaBox<N>>>(iter: I) -> Self
+    {
+        let mut set = BoxSet::new();
+        iter.into_iter().for_each(|b| set.insert(b));
+        set
+    }
+}
+
+/// Boxes stacked along the last axis, bucketed by where they sit over
+/// the others on a grid of `cell` sized squares, for asking what's
+/// underneath a box without looking at all of them.
+#[derive(Clone, Debug)]
+pub struct BoxIndex<const N: usize> {
+    cell: i64,
+    boxes: Vec<AaBox<N>>,
+    buckets: HashMap<[i64; N], Vec<usize>>,
+}
+
+impl<const N: usize> BoxIndex<N> {
+    pub fn new(cell: i64) -> Self
+    {
+        BoxIndex { cell, boxes: vec![], buckets: HashMap::new() }
+    }
+
+    /// Adds the box, returning its id: the number of boxes added before
+    /// it.
+    pub fn insert(&mut self, b: AaBox<N>) -> usize
+    {
+        let id = self.boxes.len();
+        self.boxes.push(b);
+        for key in self.cells(&b) {
+            self.buckets.entry(key).or_default().push(id);
+        }
+
+        id
+    }
+
+    pub fn get(&self, id: usize) -> &AaBox<N>
+    {
+        &self.boxes[id]
+    }
+
+    pub fn len(&self) -> usize
+    {
+        self.boxes.len()
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        self.boxes.is_empty()
+    }
+
+    /// Ids of the boxes above or below `b`, in id order.
+    pub fn column(&self, b: &AaBox<N>) -> Vec<usize>
+    {
+        let mut ids = self.cells(b)
+            .iter()
+            .filter_map(|key| self.buckets.get(key))
+            .flatten()
+            .copied()
+            .filter(|&id| self.boxes[id].overlaps_across(b))
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+        ids.dedup();
+
+        ids
+    }
+
+    /// Ids of the highest boxes wholly beneath `b`: the ones it's
+    /// resting on, or would land on if it fell.
+    pub fn below(&self, b: &AaBox<N>) -> Vec<usize>
+    {
+        let under = self.column(b)
+            .into_iter()
+            .filter(|&id| self.boxes[id].hi[N - 1] <= b.lo[N - 1])
+            .collect::<Vec<_>>();
+        let top = under.iter().map(|&id| self.boxes[id].hi[N - 1]).max();
+
+        under.into_iter()
+            .filter(|&id| Some(self.boxes[id].hi[N - 1]) == top)
+            .collect()
+    }
+
+    /// `b` moved down the last axis until it lands on a box or the
+    /// floor.
+    pub fn drop(&self, b: &AaBox<N>, floor: i64) -> AaBox<N>
+    {
+        let top = self.below(b)
+            .first()
+            .map_or(floor, |&id| self.boxes[id].hi[N - 1]);
+
+        b.translate(with([0; N], N - 1, top - b.lo[N - 1]))
+    }
+
+    // The grid squares under the box, with the last axis zeroed.
+    fn cells(&self, b: &AaBox<N>) -> Vec<[i64; N]>
+    {
+        let lo: [i64; N] = std::array::from_fn(|d| if d + 1 < N { b.lo[d].div_euclid(self.cell) } else { 0 });
+        let hi: [i64; N] = std::array::from_fn(|d| if d + 1 < N { (b.hi[d] - 1).div_euclid(self.cell) } else { 0 });
+
+        // Counts through every square like an odometer.
+        let mut cells = vec![];
+        let mut key = lo;
+        loop {
+            cells.push(key);
+            let Some(d) = (0..N - 1).find(|&d| key[d] < hi[d]) else { break };
+            key[d] += 1;
+            key[..d].copy_from_slice(&lo[..d]);
+        }
+
+        cells
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boxes()
+    {
+        let a = AaBox::inclusive([10, 10, 10], [12, 12, 12]);
+        let b = AaBox::inclusive([13, 13, 11], [11, 11, 13]);
+        assert_eq!((a.volume(), b.volume()), (27, 27));
+        assert_eq!(a.intersection(&b), Some(AaBox::new([11, 11, 11], [13, 13, 13])));
+        assert!(a.contains([12, 10, 11]) && !a.contains([13, 10, 11]));
+        assert_eq!(a.intersection(&a.translate([3, 0, 0])), None);
+        assert_eq!(AaBox::new([0, 0], [0, 5]).volume(), 0);
+    }
+
+    #[test]
+    fn subtracting()
+    {
+        let a = AaBox::new([0, 0, 0], [10, 10, 10]);
+        let b = AaBox::new([2, 3, 4], [5, 6, 7]);
+        let pieces = a.subtract(&b);
+        assert_eq!(pieces.len(), 6);
+        assert_eq!(pieces.iter().map(|p| p.volume()).sum::<u128>(), 1000 - 27);
+        assert!(pieces.iter().all(|p| !p.intersects(&b)));
+        assert!((0..6).all(|i| (i + 1..6).all(|j| !pieces[i].intersects(&pieces[j]))));
+
+        assert!(a.subtract(&a).is_empty());
+        assert_eq!(a.subtract(&b.translate([20, 0, 0])), [a]);
+        assert_eq!(b.subtract(&AaBox::new([3, 0, 0], [20, 20, 20])).len(), 1);
+    }
+
+    #[test]
+    fn reactor()
+    {
+        // 2021 day 22's first example.
+        let mut on = BoxSet::new();
+        on.insert(AaBox::inclusive([10, 10, 10], [12, 12, 12]));
+        assert_eq!(on.volume(), 27);
+        on.insert(AaBox::inclusive([11, 11, 11], [13, 13, 13]));
+        assert_eq!(on.volume(), 46);
+        on.remove(&AaBox::inclusive([9, 9, 9], [11, 11, 11]));
+        assert_eq!(on.volume(), 38);
+        on.insert(AaBox::inclusive([10, 10, 10], [10, 10, 10]));
+        assert_eq!(on.volume(), 39);
+        assert!(on.contains([10, 10, 10]) && !on.contains([11, 10, 10]));
+    }
+
+    #[test]
+    fn rectangles()
+    {
+        let set = [
+            AaBox::inclusive([0, 0], [4, 4]),
+            AaBox::inclusive([4, 2], [8, 6]),
+            AaBox::inclusive([2, 2], [6, 4]),
+        ].into_iter().collect::<BoxSet<2>>();
+        assert_eq!(set.volume(), 25 + 25 - 3);
+    }
+
+    #[test]
+    fn falling()
+    {
+        // 2023 day 22's example bricks, dropped from the bottom up. Five
+        // of them aren't the only brick holding another one up.
+        let bricks = [
+            ([1, 0, 1], [1, 2, 1]), ([0, 0, 2], [2, 0, 2]), ([0, 2, 3], [2, 2, 3]),
+            ([0, 0, 4], [0, 2, 4]), ([2, 0, 5], [2, 2, 5]), ([0, 1, 6], [2, 1, 6]),
+            ([1, 1, 8], [1, 1, 9]),
+        ];
+
+        let mut index = BoxIndex::new(2);
+        let below = bricks.iter()
+            .map(|&(a, b)| {
+                let brick = index.drop(&AaBox::inclusive(a, b), 1);
+                let below = index.below(&brick);
+                index.insert(brick);
+                below
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(below, [vec![], vec![0], vec![0], vec![1, 2], vec![1, 2], vec![3, 4], vec![5]]);
+        assert_eq!(index.get(6).lo[2], 5);
+        assert_eq!(index.column(&AaBox::inclusive([0, 0, 0], [0, 0, 0])), [1, 3]);
+
+        let sole = below.iter().filter(|v| v.len() == 1).map(|v| v[0]).collect::<Vec<_>>();
+        assert_eq!((0..index.len()).filter(|i| !sole.contains(i)).count(), 5);
+    }
+}
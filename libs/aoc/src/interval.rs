@@ -0,0 +1,354 @@
+//! Sets of integers kept as sorted runs, for the puzzles about ranges
+//! of IDs, addresses or coordinates far too big to hold one at a time,
+//! and maps shifting whole ranges at once like 2023 day 5's almanac.
+//!
+//! Ranges can be given any way Rust writes them (`a..b`, `a..=b`,
+//! `a..` and so on) and come back out inclusive, so a set can run all
+//! the way up to `T::max_value()`.
+use std::ops::{Bound, RangeBounds, RangeInclusive};
+use num::PrimInt;
+
+/// A set of integers stored as disjoint runs in order, with runs which
+/// touch merged into one.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    runs: Vec<(T, T)>,
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self
+    {
+        IntervalSet { runs: vec![] }
+    }
+
+    pub fn insert<R: RangeBounds<T>>(&mut self, range: R)
+    {
+        let Some((lo, hi)) = bounds(&range) else { return };
+
+        // Runs which overlap or touch the new one get merged into it.
+        let i = self.runs.partition_point(|&(_, h)| h.saturating_add(T::one()) < lo);
+        let j = self.runs.partition_point(|&(l, _)| l <= hi.saturating_add(T::one()));
+        let lo = if i < j { lo.min(self.runs[i].0) } else { lo };
+        let hi = if i < j { hi.max(self.runs[j - 1].1) } else { hi };
+        self.runs.splice(i..j, [(lo, hi)]);
+    }
+
+    pub fn remove<R: RangeBounds<T>>(&mut self, range: R)
+    {
+        let Some((lo, hi)) = bounds(&range) else { return };
+
+        let i = self.runs.partition_point(|&(_, h)| h < lo);
+        let j = self.runs.partition_point(|&(l, _)| l <= hi);
+        if i == j {
+            return
+        }
+
+        // What's left of the runs at either end.
+        let (first, last) = (self.runs[i], self.runs[j - 1]);
+        let left = (first.0 < lo).then(|| (first.0, lo - T::one()));
+        let right = (last.1 > hi).then(|| (hi + T::one(), last.1));
+        self.runs.splice(i..j, left.into_iter().chain(right));
+    }
+
+    pub fn contains(&self, n: T) -> bool
+    {
+        let i = self.runs.partition_point(|&(_, h)| h < n);
+        i < self.runs.len() && self.runs[i].0 <= n
+    }
+
+    /// Whether every number in the range is in the set.
+    pub fn covers<R: RangeBounds<T>>(&self, range: R) -> bool
+    {
+        let Some((lo, hi)) = bounds(&range) else { return true };
+
+        let i = self.runs.partition_point(|&(_, h)| h < lo);
+        i < self.runs.len() && self.runs[i].0 <= lo && hi <= self.runs[i].1
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        self.runs.is_empty()
+    }
+
+    /// How many numbers are in the set. Wide enough for a set of every
+    /// `u64` or `i64`, but not for 128 bit sets that big.
+    pub fn len(&self) -> u128
+    {
+        self.runs.iter()
+            .map(|&(lo, hi)| (hi.to_i128().unwrap() - lo.to_i128().unwrap()) as u128 + 1)
+            .sum()
+    }
+
+    pub fn first(&self) -> Option<T>
+    {
+        self.runs.first().map(|r| r.0)
+    }
+
+    pub fn last(&self) -> Option<T>
+    {
+        self.runs.last().map(|r| r.1)
+    }
+
+    /// The runs in order.
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_
+    {
+        self.runs.iter().map(|&(lo, hi)| lo..=hi)
+    }
+
+    pub fn union(&self, other: &Self) -> Self
+    {
+        let mut set = self.clone();
+        other.runs.iter().for_each(|&(lo, hi)| set.insert(lo..=hi));
+        set
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self
+    {
+        let mut runs = vec![];
+        let (mut i, mut j) = (0, 0);
+        while i < self.runs.len() && j < other.runs.len() {
+            let (a, b) = (self.runs[i], other.runs[j]);
+            let (lo, hi) = (a.0.max(b.0), a.1.min(b.1));
+            if lo <= hi {
+                runs.push((lo, hi));
+            }
+            if a.1 < b.1 { i += 1 } else { j += 1 }
+        }
+
+        IntervalSet { runs }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self
+    {
+        let mut set = self.clone();
+        other.runs.iter().for_each(|&(lo, hi)| set.remove(lo..=hi));
+        set
+    }
+
+    /// Everything within the bounds which isn't in the set.
+    pub fn complement<R: RangeBounds<T>>(&self, within: R) -> Self
+    {
+        let mut set = IntervalSet::new();
+        set.insert(within);
+        set.difference(self)
+    }
+}
+
+impl<T: PrimInt, R: RangeBounds<T>> FromIterator<R> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = R>>(iter: I) -> Self
+    {
+        let mut set = IntervalSet::new();
+        iter.into_iter().for_each(|r| set.insert(r));
+        set
+    }
+}
+
+/// A piecewise map which shifts each of its source ranges to start at
+/// a new place and leaves every other number where it is.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RangeMap<T> {
+    // Source runs in order with where each one's start goes.
+    pieces: Vec<(T, T, T)>,
+}
+
+impl<T: PrimInt> RangeMap<T> {
+    pub fn new() -> Self
+    {
+        RangeMap { pieces: vec![] }
+    }
+
+    /// Maps `src` onto the range of the same length starting at `dst`.
+    /// Panics if `src` overlaps a range already in the map.
+    pub fn insert<R: RangeBounds<T>>(&mut self, src: R, dst: T)
+    {
+        let Some((lo, hi)) = bounds(&src) else { return };
+
+        let i = self.pieces.partition_point(|&(_, h, _)| h < lo);
+        assert!(i == self.pieces.len() || self.pieces[i].0 > hi, "overlapping source ranges");
+        self.pieces.insert(i, (lo, hi, dst));
+    }
+
+    pub fn get(&self, n: T) -> T
+    {
+        let i = self.pieces.partition_point(|&(_, h, _)| h < n);
+        match self.pieces.get(i) {
+            Some(&(lo, _, dst)) if lo <= n => dst + (n - lo),
+            _ => n,
+        }
+    }
+
+    /// Where the whole set ends up, with its runs split wherever they
+    /// straddle the edge of a source range.
+    pub fn map(&self, set: &IntervalSet<T>) -> IntervalSet<T>
+    {
+        let mut mapped = IntervalSet::new();
+        for &(lo, hi) in &set.runs {
+            let mut from = Some(lo);
+            let i = self.pieces.partition_point(|&(_, h, _)| h < lo);
+            for &(l, h, dst) in self.pieces[i..].iter().take_while(|p| p.0 <= hi) {
+                let Some(start) = from else { break };
+
+                // The stretch before the piece stays put.
+                if start < l {
+                    mapped.insert(start..l);
+                }
+                let (a, b) = (start.max(l), hi.min(h));
+                mapped.insert(dst + (a - l)..=dst + (b - l));
+                from = (h < hi).then(|| h + T::one());
+            }
+
+            if let Some(start) = from {
+                mapped.insert(start..=hi);
+            }
+        }
+
+        mapped
+    }
+}
+
+// A range as inclusive bounds, `None` if it's empty.
+fn bounds<T: PrimInt, R: RangeBounds<T>>(range: &R) -> Option<(T, T)>
+{
+    let lo = match range.start_bound() {
+        Bound::Included(&n) => n,
+        Bound::Excluded(&n) => n.checked_add(&T::one())?,
+        Bound::Unbounded    => T::min_value(),
+    };
+    let hi = match range.end_bound() {
+        Bound::Included(&n) => n,
+        Bound::Excluded(&n) => n.checked_sub(&T::one())?,
+        Bound::Unbounded    => T::max_value(),
+    };
+
+    (lo <= hi).then_some((lo, hi))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runs<T: PrimInt>(set: &IntervalSet<T>) -> Vec<(T, T)>
+    {
+        set.iter().map(|r| (*r.start(), *r.end())).collect()
+    }
+
+    #[test]
+    fn inserting()
+    {
+        let mut set = IntervalSet::new();
+        set.insert(10..=14);
+        set.insert(3..5);
+        set.insert(16..20);
+        set.insert(7..7);
+        assert_eq!(runs(&set), [(3, 4), (10, 14), (16, 19)]);
+
+        // Touching runs merge, as do ones bridged by a new run.
+        set.insert(5..=5);
+        set.insert(15..=15);
+        assert_eq!(runs(&set), [(3, 5), (10, 19)]);
+        set.insert(0..=12);
+        assert_eq!(runs(&set), [(0, 19)]);
+        assert_eq!(set.len(), 20);
+    }
+
+    #[test]
+    fn removing()
+    {
+        let mut set = [0..=9, 20..=29, 40..=49].into_iter().collect::<IntervalSet<i32>>();
+        set.remove(5..25);
+        assert_eq!(runs(&set), [(0, 4), (25, 29), (40, 49)]);
+        set.remove(42..=43);
+        assert_eq!(runs(&set), [(0, 4), (25, 29), (40, 41), (44, 49)]);
+        set.remove(..);
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn membership()
+    {
+        let set = [-5..=-1, 3..=8].into_iter().collect::<IntervalSet<i64>>();
+        assert!(set.contains(-5) && set.contains(-1) && set.contains(8));
+        assert!(!set.contains(0) && !set.contains(9) && !set.contains(-6));
+        assert!(set.covers(4..8) && set.covers(-5..0) && set.covers(1..1));
+        assert!(!set.covers(-1..=3));
+        assert_eq!((set.first(), set.last()), (Some(-5), Some(8)));
+    }
+
+    #[test]
+    fn algebra()
+    {
+        let a = [0..10, 20..30].into_iter().collect::<IntervalSet<u8>>();
+        let b = [5..25, 28..40].into_iter().collect::<IntervalSet<u8>>();
+        assert_eq!(runs(&a.union(&b)), [(0, 39)]);
+        assert_eq!(runs(&a.intersection(&b)), [(5, 9), (20, 24), (28, 29)]);
+        assert_eq!(runs(&a.difference(&b)), [(0, 4), (25, 27)]);
+        assert_eq!(runs(&b.difference(&a)), [(10, 19), (30, 39)]);
+        assert_eq!(runs(&a.complement(..)), [(10, 19), (30, 255)]);
+        assert_eq!(runs(&a.complement(5..=25)), [(10, 19)]);
+    }
+
+    #[test]
+    fn extremes()
+    {
+        // 2016 day 20's example: the IPs 0 to 9 bar three blocked runs.
+        let blocked = [5..=8, 0..=2, 4..=7].into_iter().collect::<IntervalSet<u32>>();
+        let allowed = blocked.complement(0..=9);
+        assert_eq!((allowed.first(), allowed.len()), (Some(3), 2));
+
+        let all = IntervalSet::from_iter([..]);
+        assert_eq!(all.len(), 1 << 32);
+        assert!(all.contains(u32::MAX) && all.covers(..));
+        assert!(blocked.complement(..).contains(u32::MAX));
+        assert_eq!(IntervalSet::<i64>::from_iter([..]).len(), 1 << 64);
+    }
+
+    #[test]
+    fn mapping()
+    {
+        let mut map = RangeMap::new();
+        map.insert(98..100, 50);
+        map.insert(50..98, 52);
+        assert_eq!([79, 14, 55, 13, 98, 99, 100].map(|n| map.get(n)), [81, 14, 57, 13, 50, 51, 100]);
+
+        // A run over the whole map comes apart at each piece.
+        let set = IntervalSet::from_iter([90..=105]);
+        assert_eq!(runs(&map.map(&set)), [(50, 51), (92, 105)]);
+        let set = IntervalSet::from_iter([95..=99]);
+        assert_eq!(runs(&map.map(&set)), [(50, 51), (97, 99)]);
+    }
+
+    #[test]
+    fn almanac()
+    {
+        // 2023 day 5's example, seed ranges pushed through every stage.
+        let stages: [&[(u64, u64, u64)]; 7] = [
+            &[(50, 98, 2), (52, 50, 48)],
+            &[(0, 15, 37), (37, 52, 2), (39, 0, 15)],
+            &[(49, 53, 8), (0, 11, 42), (42, 0, 7), (57, 7, 4)],
+            &[(88, 18, 7), (18, 25, 70)],
+            &[(45, 77, 23), (81, 45, 19), (68, 64, 13)],
+            &[(0, 69, 1), (1, 0, 69)],
+            &[(60, 56, 37), (56, 93, 4)],
+        ];
+        let maps = stages.map(|s| {
+            let mut map = RangeMap::new();
+            s.iter().for_each(|&(dst, src, len)| map.insert(src..src + len, dst));
+            map
+        });
+
+        assert_eq!([79, 14, 55, 13].map(|n| maps.iter().fold(n, |n, m| m.get(n))), [82, 43, 86, 35]);
+        let seeds = IntervalSet::from_iter([79..79 + 14, 55..55 + 13]);
+        let locations = maps.iter().fold(seeds, |set, m| m.map(&set));
+        assert_eq!(locations.first(), Some(46));
+    }
+
+    #[test]
+    #[should_panic(expected = "overlapping")]
+    fn overlaps()
+    {
+        let mut map = RangeMap::new();
+        map.insert(0..10, 100);
+        map.insert(9..12, 200);
+    }
+}
@@ -0,0 +1,248 @@
+//! What every day has in common: the `Solution` trait days expose
+//! their parts through and the `run` their mains hand them to, which
+//! times the parts and prints the answers.
+use std::fmt::Display;
+use std::time::{Duration, Instant};
+
+pub mod aabox;
+pub mod interval;
+pub mod linear;
+pub mod manhattan;
+pub mod number;
+pub mod report;
+
+/// Set by the runner to have `run` print answers in a form it can
+/// read back rather than for people.
+pub const RUNNER_ENV: &str = "AOC_RUNNER";
+
+/// A day's two parts, given the input or whatever the day loads it
+/// into. Functions taking it implement it, as a pair or on their own
+/// for the last day of a year.
+pub trait Solution<I: ?Sized = str> {
+    fn part_one(&self, input: &I) -> String;
+
+    /// `None` when there's only the one part.
+    fn part_two(&self, input: &I) -> Option<String>;
+}
+
+impl<I: ?Sized, F1, A> Solution<I> for (F1,)
+    where F1: Fn(&I) -> A, A: Display,
+{
+    fn part_one(&self, input: &I) -> String
+    {
+        (self.0)(input).to_string()
+    }
+
+    fn part_two(&self, _: &I) -> Option<String>
+    {
+        None
+    }
+}
+
+impl<I: ?Sized, F1, F2, A, B> Solution<I> for (F1, F2)
+    where F1: Fn(&I) -> A, F2: Fn(&I) -> B, A: Display, B: Display,
+{
+    fn part_one(&self, input: &I) -> String
+    {
+        (self.0)(input).to_string()
+    }
+
+    fn part_two(&self, input: &I) -> Option<String>
+    {
+        Some((self.1)(input).to_string())
+    }
+}
+
+/// Runs both parts and prints each answer with how long it took.
+/// Loading the input first keeps the parsing out of the times.
+pub fn run<I: ?Sized, S: Solution<I>>(solution: S, input: &I)
+{
+    let runner = std::env::var_os(RUNNER_ENV).is_some();
+    let print = |answer: Answer| if runner {
+        println!("{}", answer.encode())
+    } else {
+        println!("{answer}")
+    };
+
+    let t = Instant::now();
+    let answer = solution.part_one(input);
+    print(Answer { part: 1, answer, elapsed: Some(t.elapsed()) });
+
+    let t = Instant::now();
+    if let Some(answer) = solution.part_two(input) {
+        print(Answer { part: 2, answer, elapsed: Some(t.elapsed()) });
+    }
+}
+
+/// One part's answer and, if it's known, how long it took.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Answer {
+    pub part: u8,
+    pub answer: String,
+    pub elapsed: Option<Duration>,
+}
+
+impl Answer {
+    const TAG: &str = "aoc";
+
+    /// A single line for the runner: tag, part, nanoseconds and the
+    /// answer, tab separated, with the answer escaped.
+    pub fn encode(&self) -> String
+    {
+        let nanos = self.elapsed.map_or(String::new(), |d| d.as_nanos().to_string());
+        format!("{}\t{}\t{}\t{}", Answer::TAG, self.part, nanos, escape(&self.answer))
+    }
+
+    /// Reads a line a day printed, either encoded or in the `Part 1:
+    /// 42 (1.2ms)` shape mains have always printed by hand.
+    pub fn parse(line: &str) -> Option<Answer>
+    {
+        Answer::decode(line).or_else(|| Answer::parse_printed(line))
+    }
+
+    fn decode(line: &str) -> Option<Answer>
+    {
+        let mut fields = line.splitn(4, '\t');
+        if fields.next()? != Answer::TAG {
+            return None
+        }
+        let part = fields.next()?.parse().ok()?;
+        let elapsed = match fields.next()? {
+            "" => None,
+            n  => Some(Duration::from_nanos(n.parse().ok()?)),
+        };
+        let answer = unescape(fields.next()?);
+
+        Some(Answer { part, answer, elapsed })
+    }
+
+    fn parse_printed(line: &str) -> Option<Answer>
+    {
+        let (label, rest) = line.trim().strip_prefix("Part ")?.split_once(':')?;
+        let part = match label.to_lowercase().as_str() {
+            "1" | "one" => 1,
+            "2" | "two" => 2,
+            _ => return None,
+        };
+
+        // The time's last, in brackets or not.
+        let rest = rest.trim();
+        let timed = rest.strip_suffix(')')
+            .and_then(|s| s.rsplit_once(" ("))
+            .or_else(|| rest.rsplit_once(' '))
+            .and_then(|(answer, time)| parse_duration(time).map(|d| (answer.trim(), d)));
+
+        Some(match timed {
+            Some((answer, d)) => Answer { part, answer: answer.into(), elapsed: Some(d) },
+            None => Answer { part, answer: rest.into(), elapsed: None },
+        })
+    }
+}
+
+/// Printed the way mains always have.
+impl std::fmt::Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result
+    {
+        match self.elapsed {
+            Some(d) => write!(f, "Part {}: {} ({:?})", self.part, self.answer, d),
+            None => write!(f, "Part {}: {}", self.part, self.answer),
+        }
+    }
+}
+
+/// Reads a duration written with `{:?}`, like `1.5ms` or `12µs`.
+pub fn parse_duration(s: &str) -> Option<Duration>
+{
+    let units = [("ns", 1.0), ("µs", 1e3), ("us", 1e3), ("ms", 1e6), ("s", 1e9)];
+    let (n, scale) = units.iter()
+        .find_map(|(unit, scale)| s.strip_suffix(unit).map(|n| (n, scale)))?;
+    let n = n.parse::<f64>().ok().filter(|n| *n >= 0.0)?;
+
+    Some(Duration::from_nanos((n * scale).round() as u64))
+}
+
+fn escape(s: &str) -> String
+{
+    s.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n")
+}
+
+fn unescape(s: &str) -> String
+{
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some(c)   => out.push(c),
+            None      => out.push('\\'),
+        }
+    }
+
+    out
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solutions()
+    {
+        let day = (|s: &str| s.len(), |s: &str| s.to_uppercase());
+        assert_eq!(day.part_one("abc"), "3");
+        assert_eq!(day.part_two("abc"), Some("ABC".into()));
+
+        let last = (|s: &str| s.lines().count(),);
+        assert_eq!(last.part_one("a\nb"), "2");
+        assert_eq!(last.part_two("a\nb"), None);
+
+        // Loaded into something else first.
+        let loaded = (|v: &[i32]| v.iter().sum::<i32>(), |v: &[i32]| v.len());
+        assert_eq!(loaded.part_one(&[1, 2, 3][..]), "6");
+        assert_eq!(loaded.part_two(&[1, 2, 3][..]), Some("3".into()));
+    }
+
+    #[test]
+    fn encoded()
+    {
+        let answer = Answer { part: 2, answer: "#.\t#\n\\.#".into(), elapsed: Some(Duration::from_nanos(1234)) };
+        assert_eq!(answer.encode().lines().count(), 1);
+        assert_eq!(Answer::parse(&answer.encode()), Some(answer));
+
+        let untimed = Answer { part: 1, answer: "42".into(), elapsed: None };
+        assert_eq!(Answer::parse(&untimed.encode()), Some(untimed));
+    }
+
+    #[test]
+    fn printed()
+    {
+        let answer = |part, answer: &str, nanos: Option<u64>| Some(Answer { part, answer: answer.into(), elapsed: nanos.map(Duration::from_nanos) });
+
+        assert_eq!(Answer::parse("Part 1: 42 (1.5ms)"), answer(1, "42", Some(1_500_000)));
+        assert_eq!(Answer::parse("Part 2: 42  (12µs)"), answer(2, "42", Some(12_000)));
+        assert_eq!(Answer::parse("Part 1: 7 3 250ns"), answer(1, "7 3", Some(250)));
+        assert_eq!(Answer::parse("Part two: 3,4"), answer(2, "3,4", None));
+        assert_eq!(Answer::parse("Part 1: (1, 2)"), answer(1, "(1, 2)", None));
+        assert_eq!(Answer::parse("Part 3: 1"), None);
+        assert_eq!(Answer::parse("FFT: 1234"), None);
+
+        let a = Answer { part: 1, answer: "ABC".into(), elapsed: Some(Duration::from_micros(2500)) };
+        assert_eq!(Answer::parse(&a.to_string()), Some(a));
+    }
+
+    #[test]
+    fn durations()
+    {
+        assert_eq!(parse_duration("1.2s"), Some(Duration::from_millis(1200)));
+        assert_eq!(parse_duration("3ns"), Some(Duration::from_nanos(3)));
+        assert_eq!(parse_duration("12.5µs"), Some(Duration::from_nanos(12_500)));
+        assert_eq!(parse_duration("abc"), None);
+        assert_eq!(parse_duration("ms"), None);
+    }
+}
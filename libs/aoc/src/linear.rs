@@ -0,0 +1,477 @@
+//! Exact linear algebra for the puzzles which come down to systems of
+//! equations: elimination over the rationals, integer solutions via
+//! the Hermite normal form and a branch and bound integer program
+//! solver for small problems. Nothing's floating point, so answers
+//! never need rounding or checking for a fractional part.
+use num::{Num, Signed, Zero, One};
+use num::rational::Ratio;
+
+pub use num::BigRational;
+
+/// Rationals good for small systems; use `BigRational` when the
+/// coefficients are big enough for elimination to overflow.
+pub type Rational = Ratio<i128>;
+
+/// Every solution to a linear system: `point` plus any combination of
+/// the `kernel` vectors.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SolutionSet<T> {
+    pub point: Vec<T>,
+    pub kernel: Vec<Vec<T>>,
+}
+
+impl<T> SolutionSet<T> {
+    pub fn is_unique(&self) -> bool
+    {
+        self.kernel.is_empty()
+    }
+}
+
+/// Solves `a x = b` by Gauss-Jordan elimination, `None` if there's no
+/// solution. Works for any field, `Rational` and `BigRational` being
+/// the useful ones.
+pub fn solve<T>(a: &[Vec<T>], b: &[T]) -> Option<SolutionSet<T>>
+    where T: Clone + Num,
+{
+    let n = a.first().map_or(0, |r| r.len());
+    let mut m = a.iter()
+        .zip(b)
+        .map(|(row, b)| row.iter().cloned().chain([b.clone()]).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    // Reduced row echelon form, noting the pivot column of each row.
+    let mut pivots = vec![];
+    for c in 0..n {
+        let r = pivots.len();
+        let Some(i) = (r..m.len()).find(|&i| !m[i][c].is_zero()) else { continue };
+        m.swap(r, i);
+
+        let p = m[r][c].clone();
+        m[r].iter_mut().for_each(|v| *v = v.clone() / p.clone());
+        let pivot = m[r].clone();
+        for (i, row) in m.iter_mut().enumerate() {
+            let f = row[c].clone();
+            if i != r && !f.is_zero() {
+                row.iter_mut()
+                    .zip(&pivot)
+                    .for_each(|(v, p)| *v = v.clone() - f.clone() * p.clone());
+            }
+        }
+        pivots.push(c);
+    }
+
+    // A row of zeros equal to something that isn't.
+    if m[pivots.len()..].iter().any(|row| !row[n].is_zero()) {
+        return None
+    }
+
+    let mut point = vec![T::zero(); n];
+    pivots.iter().zip(&m).for_each(|(&c, row)| point[c] = row[n].clone());
+
+    // A kernel vector for each free column, with the pivots moving to
+    // cancel it out.
+    let kernel = (0..n)
+        .filter(|c| !pivots.contains(c))
+        .map(|f| {
+            let mut v = vec![T::zero(); n];
+            v[f] = T::one();
+            pivots.iter().zip(&m).for_each(|(&c, row)| v[c] = T::zero() - row[f].clone());
+            v
+        })
+        .collect();
+
+    Some(SolutionSet { point, kernel })
+}
+
+/// The column Hermite normal form of `a`: returns `(h, u)` with
+/// `h = a u` and `u` unimodular. `h` is lower triangular, its pivots
+/// positive, entries left of a pivot reduced modulo it and the zero
+/// columns last.
+pub fn hermite(a: &[Vec<i128>]) -> (Vec<Vec<i128>>, Vec<Vec<i128>>)
+{
+    use crate::number::extended_gcd;
+
+    let n = a.first().map_or(0, |r| r.len());
+    let mut h = a.to_vec();
+    let mut u = (0..n)
+        .map(|i| (0..n).map(|j| (i == j) as i128).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    let mut p = 0;
+    for i in 0..a.len() {
+        if p == n { break }
+
+        // Gathers the gcd of the row from p on into column p.
+        for j in p + 1..n {
+            let (x, y) = (h[i][p], h[i][j]);
+            if y == 0 { continue }
+            let (g, s, t) = extended_gcd(x, y);
+            combine(&mut h, &mut u, p, j, [s, t, -y / g, x / g]);
+        }
+
+        let pivot = h[i][p];
+        if pivot == 0 { continue }
+        if pivot < 0 {
+            combine(&mut h, &mut u, p, p, [-1, 0, -1, 0]);
+        }
+
+        for k in 0..p {
+            let q = h[i][k].div_euclid(h[i][p]);
+            combine(&mut h, &mut u, p, k, [1, 0, -q, 1]);
+        }
+        p += 1;
+    }
+
+    (h, u)
+}
+
+// Replaces columns p and j of both matrices with x*p + y*j and
+// z*p + w*j, where p and j may be the same column.
+fn combine(h: &mut [Vec<i128>], u: &mut [Vec<i128>], p: usize, j: usize, [x, y, z, w]: [i128; 4])
+{
+    for row in h.iter_mut().chain(u.iter_mut()) {
+        let (a, b) = (row[p], row[j]);
+        row[j] = z * a + w * b;
+        row[p] = x * a + y * b;
+    }
+}
+
+/// Solves `a x = b` over the integers, `None` if there's no integer
+/// solution. The kernel vectors generate every integer solution,
+/// not just some of them.
+pub fn solve_integer(a: &[Vec<i128>], b: &[i128]) -> Option<SolutionSet<i128>>
+{
+    let (h, u) = hermite(a);
+    let n = u.len();
+    let rank = (0..n).take_while(|&k| h.iter().any(|row| row[k] != 0)).count();
+
+    // Forward substitution down the pivots of h y = b.
+    let mut y = vec![0; n];
+    for k in 0..rank {
+        let r = (0..h.len()).find(|&r| h[r][k] != 0).unwrap();
+        let rest = b[r] - (0..k).map(|l| h[r][l] * y[l]).sum::<i128>();
+        if rest % h[r][k] != 0 {
+            return None
+        }
+        y[k] = rest / h[r][k];
+    }
+
+    // The rows without a pivot have to come out right too.
+    if h.iter().zip(b).any(|(row, b)| row.iter().zip(&y).map(|(h, y)| h * y).sum::<i128>() != *b) {
+        return None
+    }
+
+    let point = u.iter().map(|row| row.iter().zip(&y).map(|(u, y)| u * y).sum()).collect();
+    let kernel = (rank..n).map(|k| u.iter().map(|row| row[k]).collect()).collect();
+
+    Some(SolutionSet { point, kernel })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Relation {
+    Le,
+    Eq,
+    Ge,
+}
+
+/// An integer program: minimise the cost of a vector of non-negative
+/// integers subject to linear constraints. Solved by branch and bound
+/// over an exact simplex, which is fine for the dozen or so variables
+/// a puzzle has but no more.
+#[derive(Clone, Debug)]
+pub struct Ilp {
+    costs: Vec<i128>,
+    rows: Vec<(Vec<i128>, Relation, i128)>,
+}
+
+impl Ilp {
+    pub fn minimise(costs: Vec<i128>) -> Self
+    {
+        Ilp { costs, rows: vec![] }
+    }
+
+    /// Adds the constraint `coefs . x rel rhs`.
+    pub fn add(&mut self, coefs: Vec<i128>, rel: Relation, rhs: i128)
+    {
+        assert_eq!(coefs.len(), self.costs.len(), "one coefficient per variable");
+        self.rows.push((coefs, rel, rhs));
+    }
+
+    /// The least cost and a vector with it, `None` if there's no
+    /// solution or no least cost.
+    pub fn solve(&self) -> Option<(i128, Vec<i128>)>
+    {
+        let rational = |v: &[i128]| v.iter().copied().map(Rational::from_integer).collect::<Vec<_>>();
+        let costs = rational(&self.costs);
+        let base = self.rows.iter()
+            .map(|(coefs, rel, rhs)| (rational(coefs), *rel, Rational::from_integer(*rhs)))
+            .collect::<Vec<_>>();
+
+        // Each node is the extra bounds put on variables to get there.
+        let mut best: Option<(i128, Vec<i128>)> = None;
+        let mut stack = vec![vec![]];
+        while let Some(bounds) = stack.pop() {
+            let mut rows = base.clone();
+            for &(j, rel, k) in &bounds {
+                let mut coefs = vec![Rational::zero(); costs.len()];
+                coefs[j] = Rational::one();
+                rows.push((coefs, rel, Rational::from_integer(k)));
+            }
+
+            let (value, x) = match simplex(&costs, &rows) {
+                Lp::Optimal(value, x) => (value, x),
+                Lp::Infeasible => continue,
+                Lp::Unbounded  => return None,
+            };
+
+            // Integer costs make every integer solution's cost whole.
+            if best.as_ref().is_some_and(|(b, _)| value.ceil().to_integer() >= *b) {
+                continue
+            }
+
+            match x.iter().position(|v| !v.is_integer()) {
+                None => best = Some((value.to_integer(), x.iter().map(|v| v.to_integer()).collect())),
+                Some(j) => {
+                    let mut up = bounds.clone();
+                    up.push((j, Relation::Ge, x[j].ceil().to_integer()));
+                    let mut down = bounds;
+                    down.push((j, Relation::Le, x[j].floor().to_integer()));
+                    stack.push(up);
+                    stack.push(down);
+                },
+            }
+        }
+
+        best
+    }
+}
+
+enum Lp {
+    Optimal(Rational, Vec<Rational>),
+    Infeasible,
+    Unbounded,
+}
+
+// Minimises costs . x over non-negative rationals with the two phase
+// simplex method, using Bland's rule so degenerate pivots can't cycle.
+fn simplex(costs: &[Rational], rows: &[(Vec<Rational>, Relation, Rational)]) -> Lp
+{
+    let n = costs.len();
+    let slacks = rows.iter().filter(|(_, rel, _)| *rel != Relation::Eq).count();
+    let cols = n + slacks;
+    let width = cols + rows.len();
+
+    // Every row gets an artificial variable to start the basis off,
+    // after being flipped to have a non-negative right hand side.
+    let mut t = vec![vec![Rational::zero(); width + 1]; rows.len()];
+    let mut basis = vec![0; rows.len()];
+    let mut s = n;
+    for (i, (coefs, rel, rhs)) in rows.iter().enumerate() {
+        let sign = Rational::from_integer(if rhs.is_negative() { -1 } else { 1 });
+        t[i][..n].iter_mut().zip(coefs).for_each(|(t, c)| *t = c * sign);
+        match rel {
+            Relation::Le => { t[i][s] = sign; s += 1 },
+            Relation::Ge => { t[i][s] = -sign; s += 1 },
+            Relation::Eq => (),
+        }
+        t[i][cols + i] = Rational::one();
+        t[i][width] = rhs * sign;
+        basis[i] = cols + i;
+    }
+
+    // Phase one drives the artificials to zero if anything can.
+    let mut phase = vec![Rational::zero(); width];
+    phase[cols..].fill(Rational::one());
+    pivots(&mut t, &mut basis, &phase, cols);
+    if basis.iter().zip(&t).any(|(&b, row)| b >= cols && !row[width].is_zero()) {
+        return Lp::Infeasible
+    }
+
+    // Artificials left in the basis are zero; swap them out for real
+    // columns where there's one to swap in. Rows with none are
+    // redundant and stay as they are.
+    for i in 0..t.len() {
+        if basis[i] >= cols && let Some(j) = (0..cols).find(|&j| !t[i][j].is_zero()) {
+            pivot(&mut t, &mut basis, i, j);
+        }
+    }
+
+    phase.fill(Rational::zero());
+    phase[..n].copy_from_slice(costs);
+    if !pivots(&mut t, &mut basis, &phase, cols) {
+        return Lp::Unbounded
+    }
+
+    let mut x = vec![Rational::zero(); n];
+    basis.iter().zip(&t).filter(|(b, _)| **b < n).for_each(|(&b, row)| x[b] = row[width]);
+    let value = costs.iter().zip(&x).map(|(c, x)| c * x).sum();
+
+    Lp::Optimal(value, x)
+}
+
+// Pivots until none of the first `cols` columns has a negative reduced
+// cost. False if the objective's unbounded.
+fn pivots(t: &mut [Vec<Rational>], basis: &mut [usize], costs: &[Rational], cols: usize) -> bool
+{
+    let rhs = costs.len();
+    loop {
+        let entering = (0..cols).find(|&j| {
+            let reduced = costs[j] - basis.iter().zip(t.iter()).map(|(&b, row)| costs[b] * row[j]).sum::<Rational>();
+            reduced.is_negative()
+        });
+        let Some(j) = entering else { return true };
+
+        let leaving = (0..t.len())
+            .filter(|&i| t[i][j].is_positive())
+            .min_by(|&a, &b| (t[a][rhs] / t[a][j]).cmp(&(t[b][rhs] / t[b][j])).then(basis[a].cmp(&basis[b])));
+        let Some(i) = leaving else { return false };
+
+        pivot(t, basis, i, j);
+    }
+}
+
+fn pivot(t: &mut [Vec<Rational>], basis: &mut [usize], i: usize, j: usize)
+{
+    let p = t[i][j];
+    t[i].iter_mut().for_each(|v| *v /= p);
+    let row = t[i].clone();
+    for (k, other) in t.iter_mut().enumerate() {
+        let f = other[j];
+        if k != i && !f.is_zero() {
+            other.iter_mut().zip(&row).for_each(|(v, r)| *v -= f * r);
+        }
+    }
+    basis[i] = j;
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rationals(rows: &[&[i128]]) -> Vec<Vec<Rational>>
+    {
+        rows.iter().map(|r| r.iter().copied().map(Rational::from_integer).collect()).collect()
+    }
+
+    #[test]
+    fn elimination()
+    {
+        let a = rationals(&[&[2, 1, -1], &[-3, -1, 2], &[-2, 1, 2]]);
+        let b = rationals(&[&[8, -11, -3]]).remove(0);
+        let s = solve(&a, &b).unwrap();
+        assert!(s.is_unique());
+        assert_eq!(s.point, rationals(&[&[2, 3, -1]])[0]);
+
+        // Fractions come out exactly.
+        let s = solve(&rationals(&[&[3, 0], &[0, 7]]), &rationals(&[&[1, 2]])[0]).unwrap();
+        assert_eq!(s.point, [Ratio::new(1, 3), Ratio::new(2, 7)]);
+    }
+
+    #[test]
+    fn underdetermined()
+    {
+        // x + y + z = 6, y - z = 1, and the sum of those twice over.
+        let a = rationals(&[&[1, 1, 1], &[0, 1, -1], &[2, 3, 1]]);
+        let b = rationals(&[&[6, 1, 13]]).remove(0);
+        let s = solve(&a, &b).unwrap();
+        assert_eq!(s.kernel.len(), 1);
+
+        let check = |x: &[Rational]| a.iter()
+            .map(|row| row.iter().zip(x).map(|(a, x)| a * x).sum::<Rational>())
+            .collect::<Vec<_>>();
+        assert_eq!(check(&s.point), b);
+        let shifted = s.point.iter().zip(&s.kernel[0]).map(|(p, k)| p + k * Rational::from(5)).collect::<Vec<_>>();
+        assert_eq!(check(&shifted), b);
+
+        let mut b = b;
+        b[2] = Rational::from(14);
+        assert_eq!(solve(&a, &b), None);
+    }
+
+    #[test]
+    fn big_rationals()
+    {
+        // Coefficients whose products overflow i128 during elimination.
+        let big = |n: i128| BigRational::from_integer(n.into());
+        let e = 10i128.pow(15);
+        let a = [
+            vec![big(e + 1), big(e - 3), big(7)],
+            vec![big(e - 5), big(3 * e), big(e + 11)],
+            vec![big(13), big(e + 17), big(2 * e - 1)],
+        ];
+        let x = [big(3), big(-4), big(5)];
+        let b = a.iter()
+            .map(|row| row.iter().zip(&x).map(|(a, x)| a * x).sum::<BigRational>())
+            .collect::<Vec<_>>();
+        assert_eq!(solve(&a, &b).unwrap().point, x);
+    }
+
+    #[test]
+    fn hermite_form()
+    {
+        let a = vec![vec![2, 4, 4], vec![-6, 6, 12], vec![10, -4, -16]];
+        let (h, u) = hermite(&a);
+        let au = a.iter()
+            .map(|row| (0..3).map(|j| (0..3).map(|k| row[k] * u[k][j]).sum::<i128>()).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(au, h);
+        assert!(h[0][1] == 0 && h[0][2] == 0 && h[1][2] == 0);
+        assert!((0..3).all(|i| h[i][i] > 0 && (0..i).all(|k| (0..h[i][i]).contains(&h[i][k]))));
+    }
+
+    #[test]
+    fn integers()
+    {
+        // 2024 day 13's first example machine.
+        let s = solve_integer(&[vec![94, 22], vec![34, 67]], &[8400, 5400]).unwrap();
+        assert!(s.is_unique());
+        assert_eq!(s.point, [80, 40]);
+        assert_eq!(solve_integer(&[vec![26, 67], vec![66, 21]], &[12748, 12176]), None);
+
+        // 6x + 10y + 15z = 1 has solutions but 6x + 10y = 1 doesn't.
+        let s = solve_integer(&[vec![6, 10, 15]], &[1]).unwrap();
+        assert_eq!(s.kernel.len(), 2);
+        assert_eq!(6 * s.point[0] + 10 * s.point[1] + 15 * s.point[2], 1);
+        assert!(s.kernel.iter().all(|k| 6 * k[0] + 10 * k[1] + 15 * k[2] == 0));
+        assert_eq!(solve_integer(&[vec![6, 10]], &[1]), None);
+    }
+
+    #[test]
+    fn programs()
+    {
+        // 2025 day 10's first example: the fewest presses hitting every
+        // counter's joltage exactly.
+        let buttons: [&[usize]; 6] = [&[3], &[1, 3], &[2], &[2, 3], &[0, 2], &[0, 1]];
+        let mut ilp = Ilp::minimise(vec![1; 6]);
+        for (i, n) in [3, 5, 4, 7].into_iter().enumerate() {
+            ilp.add(buttons.iter().map(|b| b.contains(&i) as i128).collect(), Relation::Eq, n);
+        }
+        assert_eq!(ilp.solve().map(|(c, _)| c), Some(10));
+
+        // The LP optimum is at (1.8, 2.8) but no integer point gets y
+        // above 2.
+        let mut ilp = Ilp::minimise(vec![0, -1]);
+        ilp.add(vec![-1, 1], Relation::Le, 1);
+        ilp.add(vec![3, 2], Relation::Le, 12);
+        ilp.add(vec![2, 3], Relation::Le, 12);
+        assert_eq!(ilp.solve().map(|(c, _)| c), Some(-2));
+    }
+
+    #[test]
+    fn no_program()
+    {
+        let mut ilp = Ilp::minimise(vec![1, 1]);
+        ilp.add(vec![2, 2], Relation::Eq, 3);
+        assert_eq!(ilp.solve(), None);
+
+        let mut ilp = Ilp::minimise(vec![-1, 0]);
+        ilp.add(vec![1, -1], Relation::Le, 2);
+        assert_eq!(ilp.solve(), None);
+
+        let mut ilp = Ilp::minimise(vec![1, 1]);
+        ilp.add(vec![1, 1], Relation::Ge, 3);
+        ilp.add(vec![1, 0], Relation::Le, 1);
+        assert_eq!(ilp.solve().map(|(c, x)| (c, x[1] >= 2)), Some((3, true)));
+    }
+}
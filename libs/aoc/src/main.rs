@@ -0,0 +1,218 @@
+//! Runs days and prints a summary of their answers and times.
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode, ExitStatus};
+use std::time::Duration;
+
+use aoc::{Answer, RUNNER_ENV};
+use aoc::report::{Format, Report};
+
+const USAGE: &str = "usage: aoc [YEAR [DAY]] [--format text|json|csv] [--budget MS] [--debug] [--root DIR]";
+
+#[derive(Debug, PartialEq)]
+struct Args {
+    year: Option<u16>,
+    day: Option<u8>,
+    format: Format,
+    budget: Duration,
+    release: bool,
+    root: PathBuf,
+}
+
+struct Day {
+    year: u16,
+    day: u8,
+    package: String,
+    dir: PathBuf,
+}
+
+fn main() -> ExitCode
+{
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{e}\n{USAGE}");
+            return ExitCode::from(2)
+        }
+    };
+
+    let days = find_days(&args.root, args.year, args.day);
+    if days.is_empty() {
+        eprintln!("no days found under {}", args.root.display());
+        return ExitCode::from(2)
+    }
+
+    let mut report = Report::new(args.budget);
+    for day in &days {
+        eprintln!("{} day {}", day.year, day.day);
+        let (answers, error) = run_day(day, args.release);
+        if answers.is_empty() && error.is_none() {
+            eprintln!("{} day {} printed no answers", day.year, day.day);
+        }
+        report.add(day.year, day.day, answers, error);
+    }
+    println!("{}", report.render(args.format));
+
+    if report.failed() { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
+
+fn parse_args<I: Iterator<Item = String>>(mut it: I) -> Result<Args, String>
+{
+    let mut args = Args {
+        year: None,
+        day: None,
+        format: Format::Text,
+        budget: Duration::from_secs(1),
+        release: true,
+        root: Path::new(env!("CARGO_MANIFEST_DIR")).join("../.."),
+    };
+
+    while let Some(arg) = it.next() {
+        let mut value = || it.next().ok_or(format!("{arg} needs a value"));
+        match arg.as_str() {
+            "--format" => args.format = value()?.parse()?,
+            "--budget" => {
+                let ms = value()?.parse().map_err(|_| "--budget is in milliseconds")?;
+                args.budget = Duration::from_millis(ms)
+            }
+            "--root"  => args.root = value()?.into(),
+            "--debug" => args.release = false,
+            _ if arg.starts_with('-') => return Err(format!("unknown option {arg}")),
+            _ if args.year.is_none() => args.year = Some(arg.parse().map_err(|_| format!("bad year {arg}"))?),
+            _ if args.day.is_none() => args.day = Some(arg.parse().map_err(|_| format!("bad day {arg}"))?),
+            _ => return Err(format!("unexpected argument {arg}")),
+        }
+    }
+
+    Ok(args)
+}
+
+// Every day crate in the year workspaces, in order.
+fn find_days(root: &Path, year: Option<u16>, day: Option<u8>) -> Vec<Day>
+{
+    let numbered = |dir: &Path, prefix: &str| {
+        let mut found = std::fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|e| {
+                let name = e.file_name().into_string().ok()?;
+                let n = name.strip_prefix(prefix)?.parse::<u16>().ok()?;
+                Some((n, e.path()))
+            })
+            .collect::<Vec<_>>();
+        found.sort();
+        found
+    };
+
+    numbered(root, "")
+        .into_iter()
+        .filter(|(y, _)| *y > 2000 && year.is_none_or(|year| year == *y))
+        .flat_map(|(y, path)| numbered(&path, "day")
+            .into_iter()
+            .filter(|(d, _)| day.is_none_or(|day| day as u16 == *d))
+            .filter_map(move |(d, dir)| {
+                let package = package_name(&dir)?;
+                Some(Day { year: y, day: d as u8, package, dir })
+            }))
+        .collect()
+}
+
+fn package_name(dir: &Path) -> Option<String>
+{
+    let manifest = std::fs::read_to_string(dir.join("Cargo.toml")).ok()?;
+    manifest.lines()
+        .find_map(|l| l.trim().strip_prefix("name")?.trim().strip_prefix('=')?.trim().strip_prefix('"')?.strip_suffix('"'))
+        .map(String::from)
+}
+
+// Builds and runs the day from its own directory, so days reading
+// `./input.txt` find it, and collects what it printed.
+fn run_day(day: &Day, release: bool) -> (Vec<Answer>, Option<String>)
+{
+    let cargo = std::env::var_os("CARGO").unwrap_or("cargo".into());
+    let mut cmd = Command::new(cargo);
+    cmd.args(["run", "-q", "-p", &day.package, "--manifest-path"])
+        .arg(day.dir.join("../Cargo.toml"))
+        .current_dir(&day.dir)
+        .env(RUNNER_ENV, "1");
+    if release {
+        cmd.arg("--release");
+    }
+
+    match cmd.output() {
+        Err(e) => (vec![], Some(format!("couldn't run cargo: {e}"))),
+        Ok(out) => {
+            let answers = String::from_utf8_lossy(&out.stdout)
+                .lines()
+                .filter_map(Answer::parse)
+                .collect();
+            let error = (!out.status.success())
+                .then(|| failure(&String::from_utf8_lossy(&out.stderr), out.status));
+            (answers, error)
+        }
+    }
+}
+
+// The panic message if there is one, otherwise the last thing said.
+fn failure(stderr: &str, status: ExitStatus) -> String
+{
+    let lines = stderr.lines().collect::<Vec<_>>();
+    if let Some(i) = lines.iter().position(|l| l.contains(" panicked at ")) {
+        let message = lines.get(i + 1).map_or("", |l| l.trim());
+        return format!("panicked: {message}")
+    }
+
+    lines.iter()
+        .rfind(|l| !l.trim().is_empty())
+        .map_or(status.to_string(), |l| l.trim().to_string())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Result<Args, String>
+    {
+        parse_args(s.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn arguments()
+    {
+        let a = args("2019 13 --format csv --budget 250").unwrap();
+        assert_eq!((a.year, a.day, a.format, a.budget, a.release), (Some(2019), Some(13), Format::Csv, Duration::from_millis(250), true));
+
+        let a = args("--debug").unwrap();
+        assert_eq!((a.year, a.day, a.format, a.release), (None, None, Format::Text, false));
+
+        assert!(args("2019 13 1").is_err());
+        assert!(args("twenty").is_err());
+        assert!(args("--format").is_err());
+        assert!(args("--format xml").is_err());
+        assert!(args("--fast").is_err());
+    }
+
+    #[test]
+    fn days()
+    {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+        let days = find_days(&root, Some(2019), None);
+        assert_eq!(days.len(), 25);
+        assert_eq!((days[7].day, days[7].package.as_str()), (8, "day8"));
+
+        let days = find_days(&root, Some(2024), Some(12));
+        assert_eq!((days.len(), days[0].package.as_str()), (1, "day012"));
+        assert!(find_days(&root, Some(1999), None).is_empty());
+    }
+
+    #[test]
+    fn failures()
+    {
+        let status = Command::new("false").status().unwrap();
+        let stderr = "thread 'main' (42) panicked at src/main.rs:5:9:\nattempt to subtract with overflow\nnote: run with `RUST_BACKTRACE=1`";
+        assert_eq!(failure(stderr, status), "panicked: attempt to subtract with overflow");
+        assert_eq!(failure("error: could not compile `day01`\n\n", status), "error: could not compile `day01`");
+        assert_eq!(failure("", status), status.to_string());
+    }
+}
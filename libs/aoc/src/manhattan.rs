@@ -0,0 +1,297 @@
+//! Ranges under the Manhattan metric, which are octahedra in three
+//! dimensions, and finding the point most of them cover: 2018 day 23's
+//! nanobots. The search splits boxes rather than handing the problem
+//! to a solver, and is exact.
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::aabox::AaBox;
+
+pub fn distance<const N: usize>(a: [i64; N], b: [i64; N]) -> i64
+{
+    (0..N).map(|d| (a[d] - b[d]).abs()).sum()
+}
+
+/// Everything within `r` of `pos`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Bot<const N: usize = 3> {
+    pub pos: [i64; N],
+    pub r: i64,
+}
+
+impl<const N: usize> Bot<N> {
+    pub fn in_range(&self, p: [i64; N]) -> bool
+    {
+        distance(self.pos, p) <= self.r
+    }
+
+    /// Whether the two ranges have a point in common.
+    pub fn overlaps(&self, other: &Self) -> bool
+    {
+        distance(self.pos, other.pos) <= self.r + other.r
+    }
+
+    /// Whether any point of the box is in range.
+    pub fn reaches(&self, b: &AaBox<N>) -> bool
+    {
+        !b.is_empty() && distance_to(self.pos, b) <= self.r
+    }
+}
+
+// How far the nearest point of the (non-empty) box is from p.
+fn distance_to<const N: usize>(p: [i64; N], b: &AaBox<N>) -> i64
+{
+    (0..N).map(|d| (b.lo[d] - p[d]).max(0) + (p[d] - (b.hi[d] - 1)).max(0)).sum()
+}
+
+/// A point in range of as many bots as any point is, nearest the
+/// origin of all such points, and how many bots it's in range of.
+///
+/// The search runs in the rotated coordinates `x+y-z`, `x-y+z` and
+/// `-x+y+z`, where every range is a box, cut by a slab on their sum
+/// (`x+y+z`), and distance from the origin is the largest of the four
+/// in size. Boxes are searched best first, most bots reaching them and
+/// then nearest the origin, and split in half along every axis. A box
+/// is ranked by its nearest point in range of every bot reaching it,
+/// which is exactly the best any point in it can do, so the search
+/// heads straight down to the answer rather than across the faces of
+/// the ranges.
+pub fn most_in_range(bots: &[Bot]) -> ([i64; 3], usize)
+{
+    // The rotated coordinates of a point are all odd or all even, so
+    // each parity is a lattice of its own: u is (x+y-z - parity) / 2,
+    // and so on.
+    let ranges = [0, 1].map(|p| bots.iter().map(|b| Region::range(b, p)).collect::<Vec<_>>());
+
+    let entry = |p: i64, lo: [i64; 3], hi: [i64; 3]| {
+        let b = Region::cells(lo, hi);
+        let reaching = ranges[p as usize].iter()
+            .filter(|r| !b.intersect(r).is_empty())
+            .collect::<Vec<_>>();
+        let count = reaching.len();
+
+        // With no point in range of them all, every point in the box
+        // is out of range of at least one.
+        let common = reaching.iter().fold(b, |c, r| c.intersect(r));
+        let (count, d) = match common.nearest(p) {
+            Some(d) => (count, d),
+            None    => (count - 1, b.nearest(p).unwrap()),
+        };
+        let volume = (0..3).map(|k| (hi[k] - lo[k] + 1) as u128).product::<u128>();
+
+        // Ties on bots and distance go to the smaller box, so the search
+        // heads down to points rather than across.
+        (count, Reverse(d), Reverse(volume), p, lo, hi)
+    };
+
+    let mut heap = BinaryHeap::new();
+    for p in [0, 1] {
+        let lo = std::array::from_fn(|k| ranges[p as usize].iter().map(|r| r.lo[k]).fold(0, i64::min));
+        let hi = std::array::from_fn(|k| ranges[p as usize].iter().map(|r| r.hi[k]).fold(0, i64::max));
+        heap.push(entry(p, lo, hi));
+    }
+
+    while let Some((count, _, Reverse(volume), p, lo, hi)) = heap.pop() {
+        if volume == 1 {
+            let [b, c, d] = lo.map(|u| 2 * u + p);
+            return ([(b + c) / 2, (b + d) / 2, (c + d) / 2], count)
+        }
+
+        let mid: [i64; 3] = std::array::from_fn(|k| lo[k] + (hi[k] - lo[k]) / 2);
+        for corner in 0..8 {
+            let lo = std::array::from_fn(|k| if corner >> k & 1 == 0 { lo[k] } else { mid[k] + 1 });
+            let hi = std::array::from_fn(|k| if corner >> k & 1 == 0 { mid[k] } else { hi[k] });
+            if (0..3).all(|k| lo[k] <= hi[k]) {
+                heap.push(entry(p, lo, hi));
+            }
+        }
+    }
+
+    unreachable!("the search always ends at a point")
+}
+
+// Cells (u, v, w) of one parity's lattice, inclusive, with their sum
+// s = u + v + w limited too.
+#[derive(Clone, Copy, Debug)]
+struct Region {
+    lo: [i64; 3],
+    hi: [i64; 3],
+    sum: (i64, i64),
+}
+
+impl Region {
+    fn cells(lo: [i64; 3], hi: [i64; 3]) -> Self
+    {
+        Region { lo, hi, sum: (lo.iter().sum(), hi.iter().sum()) }
+    }
+
+    // The cells of parity p in range of the bot.
+    fn range(bot: &Bot, p: i64) -> Self
+    {
+        let [x, y, z] = bot.pos;
+        let q = [x + y - z, x - y + z, -x + y + z];
+        Region {
+            lo: q.map(|q| ceil_half(q - bot.r - p)),
+            hi: q.map(|q| floor_half(q + bot.r - p)),
+            sum: (ceil_half(x + y + z - bot.r - 3 * p), floor_half(x + y + z + bot.r - 3 * p)),
+        }
+    }
+
+    // The cells of parity p within d of the origin.
+    fn ball(d: i64, p: i64) -> Self
+    {
+        Region {
+            lo: [ceil_half(-d - p); 3],
+            hi: [floor_half(d - p); 3],
+            sum: (ceil_half(-d - 3 * p), floor_half(d - 3 * p)),
+        }
+    }
+
+    fn intersect(&self, other: &Self) -> Self
+    {
+        Region {
+            lo: std::array::from_fn(|k| self.lo[k].max(other.lo[k])),
+            hi: std::array::from_fn(|k| self.hi[k].min(other.hi[k])),
+            sum: (self.sum.0.max(other.sum.0), self.sum.1.min(other.sum.1)),
+        }
+    }
+
+    // The sums of the cells in the box make up every integer between
+    // the sums of its corners, so it's enough to check those overlap.
+    fn is_empty(&self) -> bool
+    {
+        (0..3).any(|k| self.lo[k] > self.hi[k])
+            || self.sum.0.max(self.lo.iter().sum()) > self.sum.1.min(self.hi.iter().sum())
+    }
+
+    // How far the nearest cell is from the origin, if there is one.
+    // Being within a distance only gets easier as it grows, so it's
+    // found by bisection.
+    fn nearest(&self, p: i64) -> Option<i64>
+    {
+        if self.is_empty() {
+            return None
+        }
+
+        let far = self.lo.iter().chain(&self.hi).map(|v| v.abs()).max().unwrap();
+        let (mut lo, mut hi) = (0, 6 * far + 3);
+        while lo < hi {
+            let d = lo + (hi - lo) / 2;
+            if self.intersect(&Region::ball(d, p)).is_empty() { lo = d + 1 } else { hi = d }
+        }
+
+        Some(lo)
+    }
+}
+
+fn floor_half(v: i64) -> i64
+{
+    v.div_euclid(2)
+}
+
+fn ceil_half(v: i64) -> i64
+{
+    -(-v).div_euclid(2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tries every point in a box which takes in all the ranges and the
+    // origin.
+    fn brute_force<const N: usize>(bots: &[Bot<N>], size: i64) -> (i64, usize)
+    {
+        let mut p = [-size; N];
+        let mut best = (0, Reverse(0));
+        loop {
+            let count = bots.iter().filter(|b| b.in_range(p)).count();
+            best = best.max((count, Reverse(distance(p, [0; N]))));
+
+            let Some(d) = (0..N).find(|&d| p[d] < size) else { break };
+            p[d] += 1;
+            p[..d].fill(-size);
+        }
+
+        (best.1.0, best.0)
+    }
+
+    // Small random bots from a xorshift generator, so failures repeat.
+    fn random_bots(seed: u64, n: usize, size: i64) -> Vec<Bot>
+    {
+        let mut s = seed;
+        let mut next = |m: i64| {
+            s ^= s << 13;
+            s ^= s >> 7;
+            s ^= s << 17;
+            (s % m as u64) as i64
+        };
+
+        (0..n)
+            .map(|_| Bot { pos: std::array::from_fn(|_| next(2 * size + 1) - size), r: next(size / 2) + 1 })
+            .collect()
+    }
+
+    #[test]
+    fn ranges()
+    {
+        let bot = Bot { pos: [1, 2, 3], r: 4 };
+        assert!(bot.in_range([3, 3, 4]) && !bot.in_range([3, 3, 5]));
+        assert!(bot.overlaps(&Bot { pos: [5, 5, 5], r: 5 }));
+        assert!(!bot.overlaps(&Bot { pos: [5, 5, 5], r: 4 }));
+        assert!(bot.reaches(&AaBox::new([4, 3, 3], [9, 9, 9])));
+        assert!(!bot.reaches(&AaBox::new([5, 3, 3], [9, 9, 9])));
+    }
+
+    #[test]
+    fn nanobots()
+    {
+        // 2018 day 23's example.
+        let bots = [
+            ([10, 12, 12], 2), ([12, 14, 12], 2), ([16, 12, 12], 4),
+            ([14, 14, 14], 6), ([50, 50, 50], 200), ([10, 10, 10], 5),
+        ].map(|(pos, r)| Bot { pos, r });
+        assert_eq!(most_in_range(&bots), ([12, 12, 12], 5));
+        assert_eq!(most_in_range(&[]), ([0, 0, 0], 0));
+    }
+
+    #[test]
+    fn random()
+    {
+        for seed in 1..=40 {
+            let bots = random_bots(seed * 7919, 12, 12);
+            let (p, count) = most_in_range(&bots);
+            assert_eq!(bots.iter().filter(|b| b.in_range(p)).count(), count);
+            assert_eq!((distance(p, [0; 3]), count), brute_force(&bots, 18), "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn puzzle_scale()
+    {
+        // The nearest face of a lone range lies flat to the origin, and
+        // every point of it is nearest.
+        let r = 100_000_000;
+        let (p, count) = most_in_range(&[Bot { pos: [3 * r, 3 * r, 3 * r], r }]);
+        assert_eq!((distance(p, [0; 3]), count), (8 * r, 1));
+
+        // A thousand bots all reaching one point, and one more which is
+        // nearest the origin there, alongside the real input's sort of
+        // spread.
+        let target = [21_345_678, 43_210_987, 12_345_679];
+        let mut bots = random_bots(17, 1000, 150_000_000)
+            .into_iter()
+            .map(|b| Bot { r: distance(b.pos, target) + b.r % 1000, ..b })
+            .collect::<Vec<_>>();
+        bots.push(Bot { pos: target.map(|v| 2 * v), r: distance(target, [0; 3]) });
+        let (p, count) = most_in_range(&bots);
+        assert_eq!((distance(p, [0; 3]), count), (distance(target, [0; 3]), 1001));
+        assert_eq!(bots.iter().filter(|b| b.in_range(p)).count(), 1001);
+
+        // Bots spread every which way, which can't be checked against
+        // anything slower but has to finish and agree with itself.
+        let bots = random_bots(29, 1000, 200_000_000);
+        let (p, count) = most_in_range(&bots);
+        assert_eq!(bots.iter().filter(|b| b.in_range(p)).count(), count);
+    }
+}
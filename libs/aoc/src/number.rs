@@ -0,0 +1,309 @@
+//! Modular arithmetic the puzzles keep coming back to: the Chinese
+//! remainder theorem, powers, inverses and logs modulo a number, and
+//! affine maps like the card shuffles of 2019 day 22.
+//!
+//! Everything works in `i128` and multiplies without overflowing for
+//! any modulus that fits, so moduli needn't be kept below 2^63.
+use std::collections::HashMap;
+use std::fmt;
+
+pub fn gcd(mut a: i128, mut b: i128) -> i128
+{
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    a.abs()
+}
+
+pub fn lcm(a: i128, b: i128) -> i128
+{
+    if a == 0 || b == 0 { 0 } else { (a / gcd(a, b) * b).abs() }
+}
+
+/// Returns `(g, x, y)` with `a * x + b * y = g`, the gcd of `a` and
+/// `b`.
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128)
+{
+    let (mut r0, mut r1) = (a, b);
+    let (mut x0, mut x1) = (1, 0);
+    let (mut y0, mut y1) = (0, 1);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (x0, x1) = (x1, x0 - q * x1);
+        (y0, y1) = (y1, y0 - q * y1);
+    }
+
+    if r0 < 0 { (-r0, -x0, -y0) } else { (r0, x0, y0) }
+}
+
+/// `a` reduced into `0..m`.
+pub fn modulo(a: i128, m: i128) -> i128
+{
+    a.rem_euclid(m)
+}
+
+/// `a * b` modulo `m`, falling back to doubling and adding when the
+/// product doesn't fit.
+pub fn mulmod(a: i128, b: i128, m: i128) -> i128
+{
+    let (mut a, mut b) = (modulo(a, m), modulo(b, m));
+    if let Some(p) = a.checked_mul(b) {
+        return p % m
+    }
+
+    // Both are below m, so their sum fits unsigned.
+    let m = m as u128;
+    let mut p = 0u128;
+    while b > 0 {
+        if b & 1 == 1 {
+            p = (p + a as u128) % m;
+        }
+        a = ((a as u128 * 2) % m) as i128;
+        b >>= 1;
+    }
+
+    p as i128
+}
+
+/// `base` to the power `exp` modulo `m`.
+pub fn modpow(base: i128, mut exp: u128, m: i128) -> i128
+{
+    let mut base = modulo(base, m);
+    let mut v = modulo(1, m);
+    while exp > 0 {
+        if exp & 1 == 1 {
+            v = mulmod(v, base, m);
+        }
+        base = mulmod(base, base, m);
+        exp >>= 1;
+    }
+
+    v
+}
+
+/// The `x` in `0..m` with `a * x = 1` modulo `m`, if `a` and `m` are
+/// coprime.
+pub fn modinv(a: i128, m: i128) -> Option<i128>
+{
+    let (g, x, _) = extended_gcd(modulo(a, m), m);
+    (g == 1).then(|| modulo(x, m))
+}
+
+/// Solves `x = r` modulo `m` for every `(r, m)` at once, returning the
+/// smallest non-negative `x` with the lcm of the moduli. The moduli
+/// needn't be coprime; `None` if the congruences contradict each
+/// other. Panics if the lcm doesn't fit in an `i128`.
+pub fn crt(congruences: &[(i128, i128)]) -> Option<(i128, i128)>
+{
+    congruences.iter().try_fold((0, 1), |(r1, m1), &(r2, m2)| {
+        let (g, p, _) = extended_gcd(m1, m2);
+        let diff = r2 - r1;
+        if diff % g != 0 {
+            return None
+        }
+
+        // x = r1 + m1 * k, with k solving m1/g * k = diff/g mod m2/g.
+        let m = m2 / g;
+        let k = mulmod(diff / g, p, m);
+        let lcm = m1.checked_mul(m).expect("crt modulus overflows i128");
+        Some((modulo(r1 + mulmod(m1, k, lcm), lcm), lcm))
+    })
+}
+
+/// The smallest `x >= 0` with `base^x = target` modulo `m`, found by
+/// baby steps and giant steps in about `sqrt(m)` time and space.
+/// `base` has to be coprime to `m`.
+pub fn discrete_log(base: i128, target: i128, m: i128) -> Option<i128>
+{
+    let n = (m as f64).sqrt().ceil() as i128;
+    let target = modulo(target, m);
+
+    // Baby steps: base^j for j in 0..n, keeping the smallest j.
+    let mut steps = HashMap::new();
+    let mut v = modulo(1, m);
+    for j in 0..n {
+        steps.entry(v).or_insert(j);
+        v = mulmod(v, base, m);
+    }
+
+    // Giant steps: target * base^-n*i for i in 0..=n.
+    let giant = modpow(modinv(base, m)?, n as u128, m);
+    let mut v = target;
+    for i in 0..=n {
+        if let Some(j) = steps.get(&v) {
+            return Some(i * n + j)
+        }
+        v = mulmod(v, giant, m);
+    }
+
+    None
+}
+
+/// The map `x -> a * x + b` modulo `M`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Affine<const M: i128> {
+    pub a: i128,
+    pub b: i128,
+}
+
+impl<const M: i128> Affine<M> {
+    pub const IDENTITY: Self = Affine { a: 1, b: 0 };
+
+    pub fn new(a: i128, b: i128) -> Self
+    {
+        Affine { a: modulo(a, M), b: modulo(b, M) }
+    }
+
+    pub fn apply(&self, x: i128) -> i128
+    {
+        modulo(mulmod(self.a, x, M) + self.b, M)
+    }
+
+    /// This map followed by `other`.
+    pub fn then(&self, other: &Self) -> Self
+    {
+        Affine::new(
+            mulmod(other.a, self.a, M),
+            mulmod(other.a, self.b, M) + other.b,
+        )
+    }
+
+    /// The map undoing this one, if `a` is invertible modulo `M`.
+    pub fn inverse(&self) -> Option<Self>
+    {
+        let inv = modinv(self.a, M)?;
+        Some(Affine::new(inv, -mulmod(inv, self.b, M)))
+    }
+
+    /// This map applied `n` times over, by repeated squaring.
+    pub fn pow(&self, mut n: u128) -> Self
+    {
+        let mut base = *self;
+        let mut f = Self::IDENTITY;
+        while n > 0 {
+            if n & 1 == 1 {
+                f = f.then(&base);
+            }
+            base = base.then(&base);
+            n >>= 1;
+        }
+
+        f
+    }
+}
+
+impl<const M: i128> Default for Affine<M> {
+    fn default() -> Self
+    {
+        Self::IDENTITY
+    }
+}
+
+impl<const M: i128> fmt::Display for Affine<M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        write!(f, "{}x + {} (mod {M})", self.a, self.b)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcds()
+    {
+        assert_eq!((gcd(12, 18), gcd(-12, 18), gcd(0, 5)), (6, 6, 5));
+        assert_eq!((lcm(4, 6), lcm(0, 6)), (12, 0));
+        let (g, x, y) = extended_gcd(240, 46);
+        assert_eq!((g, 240 * x + 46 * y), (2, 2));
+    }
+
+    #[test]
+    fn powers()
+    {
+        assert_eq!(modpow(2, 10, 1000), 24);
+        assert_eq!(modpow(-2, 3, 7), 6);
+        assert_eq!(modpow(5, 0, 1), 0);
+        assert_eq!(modinv(3, 11), Some(4));
+        assert_eq!(modinv(-3, 11), Some(7));
+        assert_eq!(modinv(6, 9), None);
+
+        // A prime too big to multiply under directly: 2^127 - 1.
+        let m = i128::MAX;
+        assert_eq!(mulmod(m - 1, m - 1, m), 1);
+        assert_eq!(modpow(3, (m - 1) as u128, m), 1);
+        let inv = modinv(123_456_789, m).unwrap();
+        assert_eq!(mulmod(inv, 123_456_789, m), 1);
+    }
+
+    #[test]
+    fn remainders()
+    {
+        // 2020 day 13's example: buses 7,13,x,x,59,x,31,19.
+        let buses = [(0, 7), (-1, 13), (-4, 59), (-6, 31), (-7, 19)];
+        assert_eq!(crt(&buses), Some((1068781, 7 * 13 * 59 * 31 * 19)));
+
+        assert_eq!(crt(&[(2, 6), (8, 10)]), Some((8, 30)));
+        assert_eq!(crt(&[(1, 6), (2, 10)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+
+        // An answer past i64.
+        let big = [(1, 1_000_000_007), (2, 998_244_353), (3, 1_000_000_009)];
+        let (x, m) = crt(&big).unwrap();
+        assert!(x > i64::MAX as i128);
+        assert!(big.iter().all(|&(r, n)| x % n == r));
+        assert_eq!(m, 1_000_000_007 * 998_244_353 * 1_000_000_009);
+    }
+
+    #[test]
+    fn logs()
+    {
+        // 2020 day 25's example.
+        const M: i128 = 20201227;
+        assert_eq!(discrete_log(7, 5764801, M), Some(8));
+        assert_eq!(discrete_log(7, 17807724, M), Some(11));
+        assert_eq!(modpow(17807724, 8, M), 14897079);
+
+        assert_eq!(discrete_log(2, 1, 11), Some(0));
+        assert_eq!(discrete_log(4, 2, 7), Some(2));
+        assert_eq!(discrete_log(2, 3, 7), None);
+    }
+
+    #[test]
+    fn affine()
+    {
+        let f = Affine::<11>::new(3, 4);
+        let g = Affine::<11>::new(-2, 5);
+        assert_eq!(f.then(&g).apply(7), g.apply(f.apply(7)));
+        assert_eq!(f.inverse().unwrap().apply(f.apply(9)), 9);
+        assert_eq!(f.pow(5).apply(2), (0..5).fold(2, |x, _| f.apply(x)));
+        assert_eq!(f.pow(0), Affine::IDENTITY);
+        assert_eq!(Affine::<10>::new(4, 1).inverse(), None);
+    }
+
+    #[test]
+    fn shuffles()
+    {
+        // 2019 day 22's examples: a deck of ten, where each technique
+        // moves the card at x to f(x).
+        let new = Affine::<10>::new(-1, -1);
+        let cut = |n: i128| Affine::<10>::new(1, -n);
+        let inc = |n: i128| Affine::<10>::new(n, 0);
+        let deck = |f: Affine<10>| {
+            let moves = f.inverse().unwrap();
+            (0..10).map(|x| moves.apply(x)).collect::<Vec<_>>()
+        };
+
+        assert_eq!(deck(inc(7).then(&new).then(&new)), [0, 3, 6, 9, 2, 5, 8, 1, 4, 7]);
+        assert_eq!(deck(cut(6).then(&inc(7)).then(&new)), [3, 0, 7, 4, 1, 8, 5, 2, 9, 6]);
+        assert_eq!(deck(inc(7).then(&inc(9)).then(&cut(-2))), [6, 3, 0, 7, 4, 1, 8, 5, 2, 9]);
+
+        // Shuffling ten times over is shuffling once, ten times.
+        let f = cut(3).then(&inc(3)).then(&new);
+        assert_eq!(f.pow(10), (0..10).fold(Affine::IDENTITY, |g, _| g.then(&f)));
+    }
+}
@@ -0,0 +1,256 @@
+//! The runner's summary of what it ran, as a table, JSON or CSV.
+use std::fmt::Write;
+use std::time::Duration;
+
+use crate::Answer;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    /// Took longer than the budget.
+    Slow,
+    /// Panicked, didn't build or otherwise exited with an error.
+    Failed,
+}
+
+impl Status {
+    fn as_str(&self) -> &'static str
+    {
+        match self {
+            Status::Ok     => "ok",
+            Status::Slow   => "slow",
+            Status::Failed => "failed",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Row {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    pub elapsed: Option<Duration>,
+    pub status: Status,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err>
+    {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv"  => Ok(Format::Csv),
+            _ => Err(format!("unknown format {s}, expected text, json or csv")),
+        }
+    }
+}
+
+pub struct Report {
+    budget: Duration,
+    rows: Vec<Row>,
+}
+
+impl Report {
+    /// An empty report flagging parts which take longer than `budget`.
+    pub fn new(budget: Duration) -> Self
+    {
+        Report { budget, rows: Vec::new() }
+    }
+
+    pub fn rows(&self) -> &[Row]
+    {
+        &self.rows
+    }
+
+    /// Adds the answers a day printed. When the day fell over `error`
+    /// says why and goes in as a failed row after the parts it did.
+    pub fn add(&mut self, year: u16, day: u8, answers: Vec<Answer>, error: Option<String>)
+    {
+        let next = answers.last().map_or(1, |a| a.part + 1);
+        for a in answers {
+            let status = match a.elapsed {
+                Some(d) if d > self.budget => Status::Slow,
+                _ => Status::Ok,
+            };
+            self.rows.push(Row { year, day, part: a.part, answer: a.answer, elapsed: a.elapsed, status });
+        }
+        if let Some(error) = error {
+            self.rows.push(Row { year, day, part: next, answer: error, elapsed: None, status: Status::Failed });
+        }
+    }
+
+    pub fn failed(&self) -> bool
+    {
+        self.rows.iter().any(|r| r.status == Status::Failed)
+    }
+
+    pub fn render(&self, format: Format) -> String
+    {
+        match format {
+            Format::Text => self.text(),
+            Format::Json => self.json(),
+            Format::Csv  => self.csv(),
+        }
+    }
+
+    fn text(&self) -> String
+    {
+        let cells = self.rows.iter()
+            .map(|r| [
+                r.year.to_string(),
+                r.day.to_string(),
+                r.part.to_string(),
+                // Pictures don't fit in a table.
+                r.answer.lines().next().unwrap_or("").to_string(),
+                r.elapsed.map_or(String::new(), |d| format!("{d:.2?}")),
+                if r.status == Status::Ok { String::new() } else { r.status.as_str().to_uppercase() },
+            ])
+            .collect::<Vec<_>>();
+
+        let header = ["Year", "Day", "Part", "Answer", "Time", ""];
+        let widths = (0..header.len())
+            .map(|i| cells.iter().map(|c| c[i].chars().count()).chain([header[i].len()]).max().unwrap())
+            .collect::<Vec<_>>();
+
+        let mut s = String::new();
+        for row in [header.map(String::from)].iter().chain(&cells) {
+            let line = row.iter()
+                .zip(&widths)
+                .enumerate()
+                .map(|(i, (cell, w))| match i {
+                    3 | 5 => format!("{cell:<w$}"),
+                    _ => format!("{cell:>w$}"),
+                })
+                .collect::<Vec<_>>()
+                .join("  ");
+            writeln!(s, "{}", line.trim_end()).unwrap();
+        }
+
+        let total = self.rows.iter().filter_map(|r| r.elapsed).sum::<Duration>();
+        let count = |status| self.rows.iter().filter(|r| r.status == status).count();
+        write!(s, "{} parts in {:.2?}, {} slow, {} failed", self.rows.len(), total, count(Status::Slow), count(Status::Failed)).unwrap();
+
+        s
+    }
+
+    fn json(&self) -> String
+    {
+        let rows = self.rows.iter()
+            .map(|r| format!(
+                r#"{{"year":{},"day":{},"part":{},"answer":{},"nanos":{},"status":"{}"}}"#,
+                r.year, r.day, r.part,
+                json_string(&r.answer),
+                r.elapsed.map_or("null".into(), |d| d.as_nanos().to_string()),
+                r.status.as_str(),
+            ))
+            .collect::<Vec<_>>();
+
+        format!("[{}]", rows.join(","))
+    }
+
+    fn csv(&self) -> String
+    {
+        let mut s = String::from("year,day,part,answer,nanos,status");
+        for r in &self.rows {
+            let nanos = r.elapsed.map_or(String::new(), |d| d.as_nanos().to_string());
+            write!(s, "\n{},{},{},{},{},{}", r.year, r.day, r.part, csv_field(&r.answer), nanos, r.status.as_str()).unwrap();
+        }
+
+        s
+    }
+}
+
+fn json_string(s: &str) -> String
+{
+    let mut out = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"'  => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+
+    out
+}
+
+fn csv_field(s: &str) -> String
+{
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> Report
+    {
+        let answer = |part, answer: &str, ms| Answer { part, answer: answer.into(), elapsed: Some(Duration::from_millis(ms)) };
+
+        let mut report = Report::new(Duration::from_millis(100));
+        report.add(2019, 8, vec![answer(1, "1742", 2), answer(2, "#..#\n####", 150)], None);
+        report.add(2022, 17, vec![answer(1, "3,068", 40)], Some("thread 'main' panicked".into()));
+        report
+    }
+
+    #[test]
+    fn statuses()
+    {
+        let report = report();
+        let statuses = report.rows().iter().map(|r| (r.day, r.part, r.status)).collect::<Vec<_>>();
+        assert_eq!(statuses, [(8, 1, Status::Ok), (8, 2, Status::Slow), (17, 1, Status::Ok), (17, 2, Status::Failed)]);
+        assert!(report.failed());
+        assert!(!Report::new(Duration::ZERO).failed());
+    }
+
+    #[test]
+    fn text()
+    {
+        let text = report().render(Format::Text);
+        let lines = text.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "Year  Day  Part  Answer                      Time");
+        assert_eq!(lines[2], "2019    8     2  #..#                    150.00ms  SLOW");
+        assert_eq!(lines[4], "2022   17     2  thread 'main' panicked            FAILED");
+        assert_eq!(lines[5], "4 parts in 192.00ms, 1 slow, 1 failed");
+    }
+
+    #[test]
+    fn json()
+    {
+        let json = report().render(Format::Json);
+        assert!(json.starts_with(r#"[{"year":2019,"day":8,"part":1,"answer":"1742","nanos":2000000,"status":"ok"},"#));
+        assert!(json.contains(r##""answer":"#..#\n####""##));
+        assert!(json.ends_with(r#""answer":"thread 'main' panicked","nanos":null,"status":"failed"}]"#));
+    }
+
+    #[test]
+    fn csv()
+    {
+        let csv = report().render(Format::Csv);
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "year,day,part,answer,nanos,status");
+        assert_eq!(lines[1], "2019,8,1,1742,2000000,ok");
+        assert_eq!(lines[2], "2019,8,2,\"#..#");
+        assert_eq!(lines[4], "2022,17,1,\"3,068\",40000000,ok");
+        assert_eq!(lines[5], "2022,17,2,thread 'main' panicked,,failed");
+    }
+}
@@ -1,6 +1,12 @@
 //! Matrix of bits and utilities to rotate, transpose, etc.
 #![allow(dead_code)]
 
+pub mod life;
+pub mod ocr;
+mod region;
+pub mod sparse;
+pub mod text;
+
 use std::ops::{
     BitAnd,
     BitAndAssign,
@@ -11,9 +17,57 @@ use std::ops::{
     Range,
 };
 
-#[derive(Debug)]
-pub struct BadIndex;
+use text::ParseError;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BadIndex {
+    /// A cell outside a rows x cols matrix.
+    Cell { row: usize, col: usize, rows: usize, cols: usize },
+    /// Rows or columns running off the edge of a rows x cols matrix.
+    Range { rows: Range<usize>, cols: Range<usize>, shape: (usize, usize) },
+    /// Matrices which needed to be the same shape but weren't.
+    Shape { lhs: (usize, usize), rhs: (usize, usize) },
+}
+
+/// One of the eight ways a matrix can be turned over and around:
+/// flipped left to right or not, then turned clockwise 0 to 3 times.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Orientation {
+    pub flipped: bool,
+    pub turns: usize,
+}
+
+impl Orientation {
+    pub const ALL: [Orientation; 8] = {
+        const fn o(flipped: bool, turns: usize) -> Orientation
+        {
+            Orientation { flipped, turns }
+        }
+        [
+            o(false, 0), o(false, 1), o(false, 2), o(false, 3),
+            o(true, 0), o(true, 1), o(true, 2), o(true, 3),
+        ]
+    };
+
+    /// Returns a copy of the matrix turned this way.
+    pub fn apply(&self, m: &BitMatrix) -> BitMatrix
+    {
+        let m = if self.flipped { m.flipped_vert() } else { m.clone() };
+        m.rotated_cw(self.turns)
+    }
+}
+
+/// What gets shifted in at the edge when the matrix is shifted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fill {
+    Off,
+    On,
+    /// Whatever was shifted out at the opposite edge.
+    Wrap,
+}
 
+// Each row starts on a word boundary, column c of a row being bit
+// c % 64 of word c / 64. Bits past the last column are always zero.
 #[derive(Clone, Debug)]
 pub struct BitMatrix {
     pub rows: usize,
@@ -24,11 +78,7 @@ pub struct BitMatrix {
 impl BitMatrix {
     pub fn new(rows: usize, cols: usize) -> Self
     {
-        // Allocate enough space to hold all the entries in a set
-        // of 64bit values. This could be bigger than needed so
-        // we have to pass the width (cols) to the bit_pos function.
-        let words = (rows * cols).div_ceil(64);
-        let data = vec![0u64; words];
+        let data = vec![0u64; rows * cols.div_ceil(64)];
 
         Self { rows, cols, data }
     }
@@ -36,32 +86,34 @@ impl BitMatrix {
     /// Creates a BitGrid from an iterator of rows and a predicate
     /// `rows_iter` can be anything iterable over row items
     /// `is_on` should return true for a cell that should be set
+    ///
+    /// Panics if the rows aren't all the same length.
     pub fn from_rows<I, R, C, F>(rows_iter: I, is_on: F) -> Self
         where
             I: IntoIterator<Item = R>,
-            R: IntoIterator<Item = C> + Clone,
-            F: Fn(&C) -> bool,
-    {
-        let v: Vec<R> = rows_iter.into_iter().collect();
-        let rows = v.len();
-        let cols = v
-            .first()
-            .map(|r| r.clone().into_iter().count())
-            .unwrap_or(0);
-
-        let words = (rows * cols).div_ceil(64);
-        let mut data = vec![0u64; words];
-
-        for (r, row) in v.into_iter().enumerate() {
-            for (c, cell) in row.into_iter().enumerate() {
-                if is_on(&cell) {
-                    let (w, m) = bit_pos(r, c, cols);
-                    data[w] |= m;
-                }
-            }
+            R: IntoIterator<Item = C>,
+            F: FnMut(&C) -> bool,
+    {
+        Self::try_from_rows(rows_iter, is_on).expect("rows of different lengths")
+    }
+
+    /// Like `from_rows` but returns an error for rows which aren't
+    /// the same length as the first.
+    pub fn try_from_rows<I, R, C, F>(rows_iter: I, mut is_on: F) -> Result<Self, ParseError>
+        where
+            I: IntoIterator<Item = R>,
+            R: IntoIterator<Item = C>,
+            F: FnMut(&C) -> bool,
+    {
+        let v = rows_iter.into_iter()
+            .map(|row| row.into_iter().map(|cell| is_on(&cell)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let cols = v.first().map_or(0, |row| row.len());
+        if let Some(r) = v.iter().position(|row| row.len() != cols) {
+            return Err(ParseError::Ragged { row: r, len: v[r].len(), expected: cols })
         }
 
-        BitMatrix { rows, cols, data }
+        Ok(BitMatrix::from_fn(v.len(), cols, |r, c| v[r][c]))
     }
 
     /// Creates a new matrix with on bits set from a vector of
@@ -82,40 +134,110 @@ impl BitMatrix {
     {
         let mut m = BitMatrix::new(rows, cols);
 
+        // Build each word up before storing it.
         for r in 0..rows {
-            for c in 0..cols {
-                if f(r, c) { m.set(r, c); }
+            for (i, word) in m.row_mut(r).iter_mut().enumerate() {
+                let c0 = i * 64;
+                *word = (c0..cols.min(c0 + 64))
+                    .filter(|c| f(r, *c))
+                    .fold(0, |w, c| w | 1 << (c - c0));
             }
         }
 
         m
     }
-    
-    /// Gets the bit at (row, col)
+
+    /// Number of 64 bit words used for each row.
+    pub fn stride(&self) -> usize
+    {
+        self.cols.div_ceil(64)
+    }
+
+    /// The words making up a row. Column c is bit c % 64 of word c / 64
+    /// and any bits past the last column are zero.
+    pub fn row(&self, r: usize) -> &[u64]
+    {
+        let stride = self.stride();
+        &self.data[r * stride..(r + 1) * stride]
+    }
+
+    /// Mutable access to the words making up a row. Bits past the last
+    /// column must be left zero.
+    pub fn row_mut(&mut self, r: usize) -> &mut [u64]
+    {
+        let stride = self.stride();
+        &mut self.data[r * stride..(r + 1) * stride]
+    }
+
+    /// All the rows' words, one row after another.
+    pub fn words(&self) -> &[u64]
+    {
+        &self.data
+    }
+
+    // Panics rather than quietly using the padding or the next row.
+    fn bit_pos(&self, r: usize, c: usize) -> (usize, u64)
+    {
+        self.checked_pos(r, c).unwrap_or_else(|e| panic!("bad index: {e:?}"))
+    }
+
+    fn checked_pos(&self, row: usize, col: usize) -> Result<(usize, u64), BadIndex>
+    {
+        if row < self.rows && col < self.cols {
+            Ok(bit_pos(row, col, self.stride()))
+        } else {
+            Err(BadIndex::Cell { row, col, rows: self.rows, cols: self.cols })
+        }
+    }
+
+    /// Gets the bit at (row, col) or None if it's outside the matrix.
+    pub fn try_get(&self, row: usize, col: usize) -> Option<bool>
+    {
+        let (w, m) = self.checked_pos(row, col).ok()?;
+        Some(self.data[w] & m != 0)
+    }
+
+    /// Sets the bit at (row, col) unless it's outside the matrix.
+    pub fn try_set(&mut self, row: usize, col: usize) -> Result<(), BadIndex>
+    {
+        self.try_update(row, col, true)
+    }
+
+    /// Sets or clears the bit at (row, col) unless it's outside the
+    /// matrix.
+    pub fn try_update(&mut self, row: usize, col: usize, val: bool) -> Result<(), BadIndex>
+    {
+        let (w, m) = self.checked_pos(row, col)?;
+        if val { self.data[w] |= m } else { self.data[w] &= !m }
+
+        Ok(())
+    }
+
+    /// Gets the bit at (row, col), panicking if it's outside the matrix.
     pub fn get(&self, row: usize, col: usize) -> bool
     {
-        let (w, m) = bit_pos(row, col, self.cols);
+        let (w, m) = self.bit_pos(row, col);
         self.data[w] & m != 0
     }
 
     /// Sets the bit at (row, col) to 1
     pub fn set(&mut self, row: usize, col: usize)
     {
-        let (w, m) = bit_pos(row, col, self.cols);
+        let (w, m) = self.bit_pos(row, col);
         self.data[w] |= m
     }
 
     /// Sets or clears the entry based on if the value is zero or not.
     pub fn update(&mut self, row: usize, col: usize, val: bool)
     {
-        let (w, m) = bit_pos(row, col, self.cols);
+        let (w, m) = self.bit_pos(row, col);
         if val { self.data[w] |= m } else { self.data[w] &= !m }
     }
 
     /// Clears the value at (row, col) - set bit to 0
     pub fn clear(&mut self, row: usize, col: usize)
     {
-        let (w, m) = bit_pos(row, col, self.cols);
+        let (w, m) = self.bit_pos(row, col);
         self.data[w] &= !m
     }
 
@@ -132,7 +254,7 @@ impl BitMatrix {
     {
         (0..self.rows).flat_map(move |r| {
             (0..self.cols).map(move |c| {
-                let (w, m) = bit_pos(r, c, self.cols);
+                let (w, m) = self.bit_pos(r, c);
                 ((r, c), (self.data[w] & m) != 0)
             })
         })
@@ -147,9 +269,10 @@ impl BitMatrix {
     pub fn items_mut<F>(&mut self, mut f: F)
         where F: FnMut((usize, usize), &mut BitRef),
     {
+        let stride = self.stride();
         for r in 0..self.rows {
             for c in 0..self.cols {
-                let (w, mask) = bit_pos(r, c, self.cols);
+                let (w, mask) = bit_pos(r, c, stride);
                 let mut bit_ref = BitRef { word: &mut self.data[w], mask };
                 f((r, c), &mut bit_ref);
             }
@@ -164,7 +287,7 @@ impl BitMatrix {
         let data = tile.iter()
             .fold(vec![0u64; self.data.len()], |mut mask, (r, c)| {
                 let (wr, wc) = (row + *r, col + *c);
-                let (w, m) = bit_pos(wr, wc, self.cols);
+                let (w, m) = self.bit_pos(wr, wc);
                 mask[w] |= m;
                 mask
             });
@@ -199,35 +322,83 @@ impl BitMatrix {
     pub fn slice(&self, rows: Range<usize>, cols: Range<usize>) -> Result<Self, BadIndex>
     {
         if rows.end > self.rows || cols.end > self.cols {
-            return Err(BadIndex);
+            return Err(BadIndex::Range { rows, cols, shape: (self.rows, self.cols) });
         }
 
         let mut m = BitMatrix::new(rows.len(), cols.len());
-        rows
-            .enumerate()
-            .flat_map(move |r| cols.clone().enumerate().map(move |c| (r, c)))
-            .filter(|((_, r), (_, c))| self.get(*r, *c))
-            .for_each(|((a, _), (b, _))| m.set(a, b));
+        let width = cols.len();
+        for (r, src) in rows.enumerate() {
+            let src = self.row(src);
+            for (i, word) in m.row_mut(r).iter_mut().enumerate() {
+                let n = (width - i * 64).min(64);
+                *word = window(src, cols.start + i * 64) & low_bits(n);
+            }
+        }
 
         Ok(m)
     }
 
+    /// Returns the (row, column) of the top left corner of everywhere
+    /// the on bits of the pattern are all on in the matrix. Off bits
+    /// in the pattern don't matter and matches can overlap.
+    pub fn find_pattern(&self, pattern: &BitMatrix) -> Vec<(usize, usize)>
+    {
+        if pattern.rows > self.rows || pattern.cols > self.cols {
+            return vec![]
+        }
+
+        // Lay each pattern row over a word's worth of the matrix row
+        // at a time and check nothing's missing.
+        let fits = |r: usize, c: usize| (0..pattern.rows).all(|pr| {
+            let src = self.row(r + pr);
+            pattern.row(pr).iter()
+                .enumerate()
+                .all(|(i, p)| window(src, c + i * 64) & p == *p)
+        });
+
+        (0..=self.rows - pattern.rows)
+            .flat_map(|r| (0..=self.cols - pattern.cols).map(move |c| (r, c)))
+            .filter(|(r, c)| fits(*r, *c))
+            .collect()
+    }
+
+    /// Copies of the matrix in each of the eight orientations.
+    pub fn orientations(&self) -> impl Iterator<Item = (Orientation, BitMatrix)> + '_
+    {
+        Orientation::ALL.into_iter().map(|o| (o, o.apply(self)))
+    }
+
+    /// Looks for the pattern turned each of the eight ways, returning
+    /// the first orientation of the pattern found in the matrix along
+    /// with where it was found.
+    pub fn find_pattern_any_orientation(&self, pattern: &BitMatrix) -> Option<(Orientation, Vec<(usize, usize)>)>
+    {
+        pattern.orientations()
+            .map(|(o, p)| (o, self.find_pattern(&p)))
+            .find(|(_, found)| !found.is_empty())
+    }
+
     /// Returns a copy of the matrix after transposition.
     pub fn transposed(&self) -> Self
     {
-        assert!(
-            self.rows != 0 || self.cols == 0,
-            "this operation would create a matrix with empty rows"
-        );
-        let mut m = BitMatrix {
-            rows: self.cols,
-            cols: self.rows,
-            data: vec![0u64; self.data.len()],
-        };
-
-        for r in 0..self.rows {
-            for c in 0..self.cols {
-                m.update(c, r, self.get(r, c));
+        // Work through 64x64 blocks, transposing each into the block
+        // mirrored across the diagonal.
+        let mut m = BitMatrix::new(self.cols, self.rows);
+        let (src_stride, dst_stride) = (self.stride(), m.stride());
+        let mut block = [0u64; 64];
+        for br in 0..self.rows.div_ceil(64) {
+            for bc in 0..src_stride {
+                for (i, word) in block.iter_mut().enumerate() {
+                    let r = br * 64 + i;
+                    *word = if r < self.rows { self.data[r * src_stride + bc] } else { 0 };
+                }
+                transpose64(&mut block);
+                for (i, word) in block.iter().enumerate() {
+                    let r = bc * 64 + i;
+                    if r < m.rows {
+                        m.data[r * dst_stride + br] = *word;
+                    }
+                }
             }
         }
 
@@ -237,45 +408,32 @@ impl BitMatrix {
     /// Flips the matrix around the vertical axis (left/right).
     pub fn flip_vert(&mut self)
     {
+        // Reversing the words and the bits in them puts the row
+        // backwards at the top of the last word, so shift it down.
+        let shift = self.stride() * 64 - self.cols;
         for r in 0..self.rows {
-            for c in 0..self.cols / 2 {
-                let opposite = self.cols - 1 - c;
-
-                // swap the bits (left, right)
-                let lt = self.get(r, c);
-                let rt = self.get(r, opposite);
-
-                self.update(r, c, rt);
-                self.update(r, opposite, lt);
-            }
+            let row = self.row_mut(r);
+            row.reverse();
+            row.iter_mut().for_each(|w| *w = w.reverse_bits());
+            shr_row(row, shift);
         }
     }
 
     /// Flips the matrix around the horizontal axis (up/down).
     pub fn flip_horz(&mut self)
     {
+        let stride = self.stride();
         for r in 0..self.rows / 2 {
             let opposite = self.rows - 1 - r;
-
-            for c in 0..self.cols {
-                // swap the bits (up, down)
-                let up = self.get(r, c);
-                let dn = self.get(opposite, c);
-
-                self.update(r, c, dn);
-                self.update(opposite, c, up);
-            }
+            let (top, bottom) = self.data.split_at_mut(opposite * stride);
+            top[r * stride..(r + 1) * stride].swap_with_slice(&mut bottom[..stride]);
         }
     }
 
-    /// Rotates a square matrix in place 90 degrees clockwise
-    /// x number of `times` modulo 4.
+    /// Rotates the matrix in place 90 degrees clockwise x number
+    /// of `times` modulo 4. Rows and columns swap over for an odd
+    /// number of turns.
     pub fn rotate_cw(&mut self, times: usize) {
-        assert!(
-            self.rows == self.cols,
-            "attempt to rotate a non-square matrix"
-        );
-
         match times % 4 {
             0 => (),
             1 => {  // 90° clockwise
@@ -296,7 +454,7 @@ impl BitMatrix {
         }
     }
 
-    /// Rotates a square matrix counter-clock-wise x number of times.
+    /// Rotates the matrix counter-clock-wise x number of times.
     pub fn rotate_ccw(&mut self, times: usize) {
         self.rotate_cw(4 - (times % 4));
     }
@@ -333,14 +491,183 @@ impl BitMatrix {
         m
     }
 
-    /// Draw the matrix to stdout using # for on and . for off.
-    pub fn draw(&self) {
+    /// Returns a copy with every row moved up one, the new bottom
+    /// row filled in by `fill`.
+    pub fn shifted_up(&self, fill: Fill) -> Self
+    {
+        if self.rows == 0 { return self.clone() }
+
+        let stride = self.stride();
+        let mut m = self.clone();
+        m.data.copy_within(stride.., 0);
+        let last = (self.rows - 1) * stride;
+        match fill {
+            Fill::Off  => m.data[last..].fill(0),
+            Fill::On   => m.fill_row(self.rows - 1),
+            Fill::Wrap => m.data[last..].copy_from_slice(self.row(0)),
+        }
+
+        m
+    }
+
+    /// Returns a copy with every row moved down one, the new top row
+    /// filled in by `fill`.
+    pub fn shifted_down(&self, fill: Fill) -> Self
+    {
+        if self.rows == 0 { return self.clone() }
+
+        let stride = self.stride();
+        let mut m = self.clone();
+        m.data.copy_within(..(self.rows - 1) * stride, stride);
+        match fill {
+            Fill::Off  => m.data[..stride].fill(0),
+            Fill::On   => m.fill_row(0),
+            Fill::Wrap => m.data[..stride].copy_from_slice(self.row(self.rows - 1)),
+        }
+
+        m
+    }
+
+    /// Returns a copy with every column moved left one, the new last
+    /// column filled in by `fill`.
+    pub fn shifted_left(&self, fill: Fill) -> Self
+    {
+        if self.cols == 0 { return self.clone() }
+
+        let (w, bit) = bit_pos(0, self.cols - 1, self.stride());
+        let mut m = self.clone();
+        for row in m.data.chunks_mut(self.stride()) {
+            let on = match fill {
+                Fill::Off  => 0,
+                Fill::On   => bit,
+                Fill::Wrap => if row[0] & 1 != 0 { bit } else { 0 },
+            };
+            shr_row(row, 1);
+            row[w] |= on;
+        }
+
+        m
+    }
+
+    /// Returns a copy with every column moved right one, the new first
+    /// column filled in by `fill`.
+    pub fn shifted_right(&self, fill: Fill) -> Self
+    {
+        if self.cols == 0 { return self.clone() }
+
+        let (w, bit) = bit_pos(0, self.cols - 1, self.stride());
+        let mut m = self.clone();
+        for row in m.data.chunks_mut(self.stride()) {
+            let on = match fill {
+                Fill::Off  => false,
+                Fill::On   => true,
+                Fill::Wrap => row[w] & bit != 0,
+            };
+            shl_row(row, 1);
+            row[0] |= on as u64;
+        }
+        m.clear_padding();
+
+        m
+    }
+
+    /// Returns a copy with a border `n` cells wide all the way round,
+    /// on or off according to `fill`.
+    pub fn padded(&self, n: usize, fill: bool) -> Self
+    {
+        let mut m = BitMatrix::new(self.rows + 2 * n, self.cols + 2 * n);
+        if fill {
+            (0..m.rows).for_each(|r| m.fill_row(r));
+            for r in n..n + self.rows {
+                (n..n + self.cols).for_each(|c| m.clear(r, c));
+            }
+        }
+
+        // Each source word lands n bits along, straddling two words
+        // unless n is a multiple of 64.
+        let (skip, off) = (n / 64, n % 64);
         for r in 0..self.rows {
-            for c in 0..self.cols {
-                print!("{}", if self.get(r, c) { '#' } else { '.' });
+            let dst = m.row_mut(r + n);
+            for (i, w) in self.row(r).iter().enumerate() {
+                dst[i + skip] |= w << off;
+                if off > 0 && i + skip + 1 < dst.len() {
+                    dst[i + skip + 1] |= w >> (64 - off);
+                }
             }
-            println!();
         }
+
+        m
+    }
+
+    /// Number of bits set.
+    pub fn count_ones(&self) -> usize
+    {
+        self.data.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    // Turns on every column of a row.
+    fn fill_row(&mut self, r: usize)
+    {
+        let n = self.cols % 64;
+        let row = self.row_mut(r);
+        row.fill(u64::MAX);
+        if let (Some(w), true) = (row.last_mut(), n > 0) {
+            *w = low_bits(n);
+        }
+    }
+
+    // Zeroes the bits past the last column.
+    fn clear_padding(&mut self)
+    {
+        let n = self.cols % 64;
+        if n == 0 { return }
+
+        let stride = self.stride();
+        self.data.chunks_mut(stride).for_each(|row| row[stride - 1] &= low_bits(n));
+    }
+
+    /// `self | rhs`, or an error if they're not the same shape.
+    pub fn try_bitor(&self, rhs: &BitMatrix) -> Result<BitMatrix, BadIndex>
+    {
+        self.zip_words(rhs, |a, b| a | b)
+    }
+
+    /// `self & rhs`, or an error if they're not the same shape.
+    pub fn try_bitand(&self, rhs: &BitMatrix) -> Result<BitMatrix, BadIndex>
+    {
+        self.zip_words(rhs, |a, b| a & b)
+    }
+
+    /// `self ^ rhs`, or an error if they're not the same shape.
+    pub fn try_bitxor(&self, rhs: &BitMatrix) -> Result<BitMatrix, BadIndex>
+    {
+        self.zip_words(rhs, |a, b| a ^ b)
+    }
+
+    fn zip_words<F>(&self, rhs: &BitMatrix, f: F) -> Result<BitMatrix, BadIndex>
+        where F: Fn(u64, u64) -> u64,
+    {
+        self.check_shape(rhs)?;
+        let data = self.data.iter()
+            .zip(&rhs.data)
+            .map(|(a, b)| f(*a, *b))
+            .collect();
+
+        Ok(BitMatrix { data, ..*self })
+    }
+
+    fn check_shape(&self, rhs: &BitMatrix) -> Result<(), BadIndex>
+    {
+        if (self.rows, self.cols) == (rhs.rows, rhs.cols) {
+            Ok(())
+        } else {
+            Err(BadIndex::Shape { lhs: (self.rows, self.cols), rhs: (rhs.rows, rhs.cols) })
+        }
+    }
+
+    /// Draw the matrix to stdout using # for on and . for off.
+    pub fn draw(&self) {
+        println!("{self}");
     }
 }
 
@@ -375,54 +702,97 @@ impl<'a> BitRef<'a> {
     }
 }
 
-// let (w, m) = bit_pos(r, c, width)
+// let (w, m) = bit_pos(r, c, stride)
 // bitset[w] |= m to set that cell in the board
 // bitset[w] & m != 0 to see if that cell is occupied
-fn bit_pos(r: usize, c: usize, w: usize) -> (usize, u64)
+fn bit_pos(r: usize, c: usize, stride: usize) -> (usize, u64)
 {
-    let idx = r * w + c;
-    (idx / 64, 1u64 << (idx % 64))
+    (r * stride + c / 64, 1u64 << (c % 64))
 }
 
-impl<'a, 'b> BitOr<&'b BitMatrix> for &'a BitMatrix {
-    type Output = BitMatrix;
+// Mask of the lowest n bits, n <= 64.
+fn low_bits(n: usize) -> u64
+{
+    if n == 64 { u64::MAX } else { (1u64 << n) - 1 }
+}
 
-    fn bitor(self, rhs: &'b BitMatrix) -> BitMatrix
-    {
-        assert_eq!(self.cols, rhs.cols);
-        assert_eq!(self.data.len(), rhs.data.len());
+// The 64 bits of a row starting at a column, with zeros past the end.
+fn window(row: &[u64], start: usize) -> u64
+{
+    let (w, off) = (start / 64, start % 64);
+    let mut v = row.get(w).map_or(0, |x| x >> off);
+    if off > 0 && w + 1 < row.len() {
+        v |= row[w + 1] << (64 - off);
+    }
 
-        let data = self.data.iter()
-            .zip(&rhs.data)
-            .map(|(a, b)| a | b)
-            .collect();
+    v
+}
+
+// Shifts a multi-word row down (towards column 0) by n < 64 bits.
+fn shr_row(row: &mut [u64], n: usize)
+{
+    if n == 0 { return }
 
-        BitMatrix { data, ..*self }
+    for i in 0..row.len() {
+        let hi = row.get(i + 1).map_or(0, |w| w << (64 - n));
+        row[i] = row[i] >> n | hi;
     }
 }
 
-impl<'a, 'b> BitAnd<&'b BitMatrix> for &'a BitMatrix {
+// Shifts a multi-word row up (away from column 0) by n < 64 bits,
+// leaving whatever goes past the last column in the padding.
+fn shl_row(row: &mut [u64], n: usize)
+{
+    if n == 0 { return }
+
+    for i in (0..row.len()).rev() {
+        let lo = if i > 0 { row[i - 1] >> (64 - n) } else { 0 };
+        row[i] = row[i] << n | lo;
+    }
+}
+
+// Transposes a 64x64 block in place, bit c of word r swapping with
+// bit r of word c, by swapping ever smaller off diagonal sub-blocks
+// (Hacker's Delight 7-3).
+fn transpose64(a: &mut [u64; 64])
+{
+    let mut j = 32;
+    let mut m = 0x0000_0000_ffff_ffffu64;
+    while j != 0 {
+        let mut k = 0;
+        while k < 64 {
+            let t = ((a[k] >> j) ^ a[k + j]) & m;
+            a[k] ^= t << j;
+            a[k + j] ^= t;
+            k = (k + j + 1) & !j;
+        }
+        j >>= 1;
+        m ^= m << j;
+    }
+}
+
+impl BitOr<&BitMatrix> for &BitMatrix {
     type Output = BitMatrix;
 
-    fn bitand(self, rhs: &'b BitMatrix) -> BitMatrix
+    fn bitor(self, rhs: &BitMatrix) -> BitMatrix
     {
-        assert_eq!(self.cols, rhs.cols);
-        assert_eq!(self.data.len(), rhs.data.len());
+        self.try_bitor(rhs).unwrap_or_else(|e| panic!("{e:?}"))
+    }
+}
 
-        let data = self.data.iter()
-            .zip(&rhs.data)
-            .map(|(a, b)| a & b)
-            .collect();
+impl BitAnd<&BitMatrix> for &BitMatrix {
+    type Output = BitMatrix;
 
-        BitMatrix { data, ..*self }
+    fn bitand(self, rhs: &BitMatrix) -> BitMatrix
+    {
+        self.try_bitand(rhs).unwrap_or_else(|e| panic!("{e:?}"))
     }
 }
 
 impl BitOrAssign<&BitMatrix> for BitMatrix {
     fn bitor_assign(&mut self, rhs: &BitMatrix)
     {
-        assert_eq!(self.cols, rhs.cols);
-        assert_eq!(self.data.len(), rhs.data.len());
+        self.check_shape(rhs).unwrap_or_else(|e| panic!("{e:?}"));
 
         self.data.iter_mut()
             .zip(&rhs.data)
@@ -433,8 +803,7 @@ impl BitOrAssign<&BitMatrix> for BitMatrix {
 impl BitAndAssign<&BitMatrix> for BitMatrix {
     fn bitand_assign(&mut self, rhs: &BitMatrix)
     {
-        assert_eq!(self.cols, rhs.cols);
-        assert_eq!(self.data.len(), rhs.data.len());
+        self.check_shape(rhs).unwrap_or_else(|e| panic!("{e:?}"));
 
         self.data.iter_mut()
             .zip(&rhs.data)
@@ -442,31 +811,222 @@ impl BitAndAssign<&BitMatrix> for BitMatrix {
     }
 }
 
-impl<'a, 'b> BitXor<&'b BitMatrix> for &'a BitMatrix {
+impl BitXor<&BitMatrix> for &BitMatrix {
     type Output = BitMatrix;
 
-    fn bitxor(self, rhs: &'b BitMatrix) -> BitMatrix
+    fn bitxor(self, rhs: &BitMatrix) -> BitMatrix
     {
-        assert_eq!(self.cols, rhs.cols);
-        assert_eq!(self.data.len(), rhs.data.len());
-
-        let data = self.data.iter()
-            .zip(rhs.data.iter())
-            .map(|(a, b)| a ^ b)
-            .collect();
-
-        BitMatrix { data, ..*self }
+        self.try_bitxor(rhs).unwrap_or_else(|e| panic!("{e:?}"))
     }
 }
 
 impl BitXorAssign<&BitMatrix> for BitMatrix {
     fn bitxor_assign(&mut self, rhs: &BitMatrix)
     {
-        assert_eq!(self.cols, rhs.cols);
-        assert_eq!(self.data.len(), rhs.data.len());
+        self.check_shape(rhs).unwrap_or_else(|e| panic!("{e:?}"));
 
         self.data.iter_mut()
             .zip(&rhs.data)
             .for_each(|(a, b)| *a ^= *b);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Odd sizes so rows straddle words and blocks are partly filled.
+    const SIZES: [(usize, usize); 6] = [(1, 1), (3, 70), (10, 10), (64, 64), (65, 130), (130, 7)];
+
+    fn sample(rows: usize, cols: usize) -> BitMatrix
+    {
+        let mut seed = (rows * 31 + cols) as u64;
+        BitMatrix::from_fn(rows, cols, |_, _| {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            seed >> 63 == 1
+        })
+    }
+
+    fn cells(m: &BitMatrix) -> Vec<Vec<bool>>
+    {
+        m.iter().map(|row| row.collect()).collect()
+    }
+
+    fn padding_clear(m: &BitMatrix) -> bool
+    {
+        let n = m.cols % 64;
+        n == 0 || (0..m.rows).all(|r| m.row(r).last().unwrap() & !low_bits(n) == 0)
+    }
+
+    #[test]
+    fn transpose()
+    {
+        for (rows, cols) in SIZES {
+            let m = sample(rows, cols);
+            let t = m.transposed();
+            assert_eq!((t.rows, t.cols), (cols, rows));
+            assert!((0..rows).all(|r| (0..cols).all(|c| m.get(r, c) == t.get(c, r))));
+            assert!(padding_clear(&t));
+        }
+    }
+
+    #[test]
+    fn flips()
+    {
+        for (rows, cols) in SIZES {
+            let m = sample(rows, cols);
+
+            let v = m.flipped_vert();
+            assert!((0..rows).all(|r| (0..cols).all(|c| m.get(r, c) == v.get(r, cols - 1 - c))));
+            assert!(padding_clear(&v));
+
+            let h = m.flipped_horz();
+            assert!((0..rows).all(|r| (0..cols).all(|c| m.get(r, c) == h.get(rows - 1 - r, c))));
+        }
+    }
+
+    #[test]
+    fn slices()
+    {
+        for (rows, cols) in SIZES {
+            let m = sample(rows, cols);
+            for (r0, c0) in [(0, 0), (rows / 3, cols / 3), (rows - 1, cols - 1)] {
+                let s = m.slice(r0..rows, c0..cols).unwrap();
+                assert_eq!((s.rows, s.cols), (rows - r0, cols - c0));
+                assert!((0..s.rows).all(|r| (0..s.cols).all(|c| s.get(r, c) == m.get(r0 + r, c0 + c))));
+                assert!(padding_clear(&s));
+            }
+        }
+        assert_eq!(
+            sample(3, 3).slice(0..4, 0..3).err(),
+            Some(BadIndex::Range { rows: 0..4, cols: 0..3, shape: (3, 3) })
+        );
+    }
+
+    #[test]
+    fn rotations()
+    {
+        let m = sample(70, 70);
+        let n = 70 - 1;
+        let cw = m.rotated_cw(1);
+        assert!((0..70).all(|r| (0..70).all(|c| m.get(r, c) == cw.get(c, n - r))));
+        assert_eq!(cells(&m.rotated_cw(2)), cells(&cw.rotated_cw(1)));
+        assert_eq!(cells(&m.rotated_ccw(1)), cells(&m.rotated_cw(3)));
+        assert_eq!(cells(&cw.rotated_ccw(1)), cells(&m));
+    }
+
+    #[test]
+    fn rows()
+    {
+        let m = BitMatrix::from_iter(2, 70, [(0, 0), (0, 65), (1, 69)]);
+        assert_eq!(m.stride(), 2);
+        assert_eq!(m.row(0), [1, 2]);
+        assert_eq!(m.row(1), [0, 32]);
+        assert_eq!(m.words().len(), 4);
+    }
+
+    #[test]
+    fn patterns()
+    {
+        let m = sample(40, 150);
+        for (pr, pc) in [(1, 1), (3, 5), (2, 70)] {
+            // Sparse enough that some positions fail and dense enough
+            // that some match.
+            let p = BitMatrix::from_fn(pr, pc, |r, c| (r * 7 + c * 3) % 11 == 0);
+            let expected = (0..=40 - pr)
+                .flat_map(|r| (0..=150 - pc).map(move |c| (r, c)))
+                .filter(|(r, c)| (0..pr).all(|i| (0..pc).all(|j| !p.get(i, j) || m.get(r + i, c + j))))
+                .collect::<Vec<_>>();
+            assert_eq!(m.find_pattern(&p), expected);
+        }
+        assert!(m.find_pattern(&sample(41, 2)).is_empty());
+    }
+
+    #[test]
+    fn orientations()
+    {
+        let m = sample(3, 70);
+        let all = m.orientations().collect::<Vec<_>>();
+        assert_eq!(all.len(), 8);
+        for (i, (_, a)) in all.iter().enumerate() {
+            assert!(all[i + 1..].iter().all(|(_, b)| cells(a) != cells(b)));
+        }
+        assert_eq!(all.iter().filter(|(_, a)| a.rows == 70).count(), 4);
+
+        let sq = sample(10, 10);
+        for (o, a) in sq.orientations() {
+            let b = if o.flipped { sq.flipped_vert() } else { sq.clone() }.rotated_cw(o.turns);
+            assert_eq!(cells(&a), cells(&b));
+        }
+    }
+
+    #[test]
+    fn pattern_any_orientation()
+    {
+        let p = BitMatrix::from_iter(2, 3, [(0, 0), (0, 1), (0, 2), (1, 2)]);
+        let flipped = Orientation { flipped: true, turns: 1 };
+        let mut m = BitMatrix::new(8, 8);
+        let q = flipped.apply(&p);
+        q.items().filter(|(_, on)| *on).for_each(|((r, c), _)| m.set(r + 4, c + 2));
+
+        assert!(m.find_pattern(&p).is_empty());
+        assert_eq!(m.find_pattern_any_orientation(&p), Some((flipped, vec![(4, 2)])));
+        assert_eq!(BitMatrix::new(8, 8).find_pattern_any_orientation(&p), None);
+    }
+
+    #[test]
+    fn non_square_rotations()
+    {
+        let m = sample(3, 70);
+        let cw = m.rotated_cw(1);
+        assert_eq!((cw.rows, cw.cols), (70, 3));
+        assert!((0..3).all(|r| (0..70).all(|c| m.get(r, c) == cw.get(c, 2 - r))));
+        assert!(padding_clear(&cw));
+
+        let half = m.rotated_cw(2);
+        assert_eq!((half.rows, half.cols), (3, 70));
+        assert!((0..3).all(|r| (0..70).all(|c| m.get(r, c) == half.get(2 - r, 69 - c))));
+        assert_eq!(cells(&m.rotated_ccw(1)), cells(&m.rotated_cw(3)));
+        assert_eq!(cells(&cw.rotated_ccw(1)), cells(&m));
+        assert_eq!(BitMatrix::new(0, 5).rotated_cw(1).rows, 5);
+    }
+
+    #[test]
+    fn checked_access()
+    {
+        let mut m = BitMatrix::new(2, 65);
+        assert_eq!(m.try_set(1, 64), Ok(()));
+        assert_eq!(m.try_get(1, 64), Some(true));
+        assert_eq!(m.try_get(0, 64), Some(false));
+        assert_eq!(m.try_get(0, 65), None);
+        assert_eq!(m.try_get(2, 0), None);
+        assert_eq!(m.try_set(0, 65), Err(BadIndex::Cell { row: 0, col: 65, rows: 2, cols: 65 }));
+        assert_eq!(m.try_update(1, 64, false), Ok(()));
+        assert!(padding_clear(&m) && m.count_ones() == 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn get_out_of_range()
+    {
+        // Would land in the next row if it weren't checked.
+        BitMatrix::new(2, 64).get(0, 64);
+    }
+
+    #[test]
+    fn checked_combinators()
+    {
+        let a = sample(4, 70);
+        let b = sample(4, 71);
+        let err = Some(BadIndex::Shape { lhs: (4, 70), rhs: (4, 71) });
+        assert_eq!(a.try_bitor(&b).err(), err);
+        assert_eq!(a.try_bitand(&b).err(), err);
+        assert_eq!(a.try_bitxor(&b).err(), err);
+        assert!(a.try_bitor(&sample(5, 70)).is_err());
+
+        let c = sample(4, 70).flipped_horz();
+        assert_eq!(a.try_bitor(&c).unwrap().words(), (&a | &c).words());
+        assert_eq!(a.try_bitand(&c).unwrap().words(), (&a & &c).words());
+        assert_eq!(a.try_bitxor(&c).unwrap().words(), (&a ^ &c).words());
+    }
+}
@@ -0,0 +1,409 @@
+//! Game of Life style cellular automata. Rather than visiting cells
+//! one at a time, the neighbours are lined up by shifting the whole
+//! matrix and counted 64 cells at a time with a bit-sliced adder:
+//! bit i of each cell's count lives in plane i.
+use crate::{BitMatrix, Fill};
+
+/// Which cells count as neighbours.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Neighbourhood {
+    /// All eight surrounding cells.
+    Moore,
+    /// Just up, down, left and right.
+    VonNeumann,
+}
+
+impl Neighbourhood {
+    fn size(&self) -> usize
+    {
+        match self {
+            Neighbourhood::Moore => 8,
+            Neighbourhood::VonNeumann => 4,
+        }
+    }
+}
+
+/// What lies past the edges of the grid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Boundary {
+    /// Nothing, cells off the edge are always off.
+    Dead,
+    /// The grid wraps round top to bottom and left to right.
+    Wrap,
+    /// The grid is a window on an infinite plane of background cells
+    /// and grows by one cell on each side every step. The background
+    /// follows the rule like everything else, so it can flip between
+    /// off and on (2021 day 20).
+    Infinite,
+}
+
+/// Counts each cell's live neighbours. The count is returned as four
+/// bit planes, bit i of a cell's count being that cell in plane i.
+/// `fill` says what's beyond the edges.
+pub fn neighbour_counts(cells: &BitMatrix, nbrs: Neighbourhood, fill: Fill) -> [BitMatrix; 4]
+{
+    let above = cells.shifted_down(fill);
+    let below = cells.shifted_up(fill);
+    let mut planes = vec![
+        cells.shifted_right(fill),
+        cells.shifted_left(fill),
+    ];
+    if nbrs == Neighbourhood::Moore {
+        planes.extend([
+            above.shifted_right(fill),
+            above.shifted_left(fill),
+            below.shifted_right(fill),
+            below.shifted_left(fill),
+        ]);
+    }
+    planes.extend([above, below]);
+
+    let mut sum: [BitMatrix; 4] = std::array::from_fn(|_| BitMatrix::new(cells.rows, cells.cols));
+    for i in 0..cells.data.len() {
+        let mut words = [0u64; 8];
+        planes.iter().zip(words.iter_mut()).for_each(|(p, w)| *w = p.data[i]);
+        for (s, bit) in sum.iter_mut().zip(add8(words)) {
+            s.data[i] = bit;
+        }
+    }
+
+    sum
+}
+
+// Adds up eight one bit numbers in each bit position with a tree of
+// full adders, giving the four bits of the totals.
+fn add8([a, b, c, d, e, f, g, h]: [u64; 8]) -> [u64; 4]
+{
+    let full = |x: u64, y: u64, z: u64| (x ^ y ^ z, x & y | z & (x ^ y));
+
+    // Ones, then the carries out of them (twos), then fours.
+    let (s1, c1) = full(a, b, c);
+    let (s2, c2) = full(d, e, f);
+    let (s3, c3) = (g ^ h, g & h);
+    let (ones, c4) = full(s1, s2, s3);
+    let (t1, d1) = full(c1, c2, c3);
+    let (twos, d2) = (t1 ^ c4, t1 & c4);
+
+    [ones, twos, d1 ^ d2, d1 & d2]
+}
+
+/// A grid of cells and the rules for what lies around it.
+#[derive(Clone, Debug)]
+pub struct Life {
+    cells: BitMatrix,
+    nbrs: Neighbourhood,
+    boundary: Boundary,
+    background: bool,
+}
+
+impl Life {
+    pub fn new(cells: BitMatrix, nbrs: Neighbourhood, boundary: Boundary) -> Self
+    {
+        Life { cells, nbrs, boundary, background: false }
+    }
+
+    pub fn cells(&self) -> &BitMatrix
+    {
+        &self.cells
+    }
+
+    /// Mutable access to the cells, for pinning some on or off
+    /// between steps.
+    pub fn cells_mut(&mut self) -> &mut BitMatrix
+    {
+        &mut self.cells
+    }
+
+    /// Whether the cells beyond the grid are on. Only ever true for
+    /// an infinite boundary.
+    pub fn background(&self) -> bool
+    {
+        self.background
+    }
+
+    /// Number of cells on in the grid.
+    pub fn count(&self) -> usize
+    {
+        self.cells.count_ones()
+    }
+
+    /// Advances one generation. A cell which is off turns on if its
+    /// number of live neighbours is in `birth` and a cell which is
+    /// on stays on if the number is in `survive`.
+    pub fn step(&mut self, birth: &[usize], survive: &[usize])
+    {
+        let (born, lives) = (patterns(birth), patterns(survive));
+        let fill = self.grow();
+        let sum = neighbour_counts(&self.cells, self.nbrs, fill);
+        for (i, w) in self.cells.data.iter_mut().enumerate() {
+            let count = [sum[0].data[i], sum[1].data[i], sum[2].data[i], sum[3].data[i]];
+            *w = *w & matching(count, &lives) | !*w & matching(count, &born);
+        }
+        self.cells.clear_padding();
+
+        if self.boundary == Boundary::Infinite {
+            self.background = if self.background {
+                survive.contains(&self.nbrs.size())
+            } else {
+                birth.contains(&0)
+            };
+        }
+    }
+
+    /// Advances one generation using a lookup table on the 3x3 block
+    /// around each cell, read left to right and top to bottom as a
+    /// binary number, the top left cell being the high bit.
+    pub fn step_lookup(&mut self, table: &[bool; 512])
+    {
+        let fill = self.grow();
+        let (cells, f) = (&self.cells, fill);
+        let rows = [cells.shifted_down(f), cells.clone(), cells.shifted_up(f)];
+        let planes = rows.iter()
+            .flat_map(|m| [m.shifted_right(f), m.clone(), m.shifted_left(f)])
+            .collect::<Vec<_>>();
+
+        let mut bits = [0u64; 9];
+        for (i, w) in self.cells.data.iter_mut().enumerate() {
+            planes.iter().zip(bits.iter_mut()).for_each(|(p, b)| *b = p.data[i]);
+            *w = select(&bits, table);
+        }
+        self.cells.clear_padding();
+
+        if self.boundary == Boundary::Infinite {
+            self.background = table[if self.background { 511 } else { 0 }];
+        }
+    }
+
+    // Gets the grid ready for a step, returning what to shift in at
+    // the edges.
+    fn grow(&mut self) -> Fill
+    {
+        match self.boundary {
+            Boundary::Dead => Fill::Off,
+            Boundary::Wrap => Fill::Wrap,
+            Boundary::Infinite => {
+                self.cells = self.cells.padded(1, self.background);
+                if self.background { Fill::On } else { Fill::Off }
+            },
+        }
+    }
+}
+
+// Each count as words to xor the count planes with, so a cell with
+// that count comes out all ones across the four of them.
+fn patterns(ns: &[usize]) -> Vec<[u64; 4]>
+{
+    ns.iter()
+        .map(|n| std::array::from_fn(|i| if n >> i & 1 == 1 { 0 } else { u64::MAX }))
+        .collect()
+}
+
+// Cells whose count (as bit planes) is one of the patterns.
+fn matching(count: [u64; 4], patterns: &[[u64; 4]]) -> u64
+{
+    patterns.iter()
+        .map(|p| (count[0] ^ p[0]) & (count[1] ^ p[1]) & (count[2] ^ p[2]) & (count[3] ^ p[3]))
+        .fold(0, |m, w| m | w)
+}
+
+// Looks every bit position up in the table at once, each bit picking
+// the top or bottom half of what's left of the table.
+fn select(bits: &[u64], table: &[bool]) -> u64
+{
+    match bits.split_first() {
+        None => if table[0] { u64::MAX } else { 0 },
+        Some((b, rest)) => {
+            let (lo, hi) = table.split_at(table.len() / 2);
+            b & select(rest, hi) | !b & select(rest, lo)
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(rows: usize, cols: usize, seed: u64) -> BitMatrix
+    {
+        let mut seed = seed;
+        BitMatrix::from_fn(rows, cols, |_, _| {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            seed >> 62 == 0
+        })
+    }
+
+    // Neighbour count done the slow way.
+    fn count(m: &BitMatrix, r: usize, c: usize, nbrs: Neighbourhood, wrap: bool) -> usize
+    {
+        let (rows, cols) = (m.rows as isize, m.cols as isize);
+        let deltas: &[(isize, isize)] = match nbrs {
+            Neighbourhood::Moore => &[(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)],
+            Neighbourhood::VonNeumann => &[(-1, 0), (0, -1), (0, 1), (1, 0)],
+        };
+        deltas.iter()
+            .filter_map(|(dr, dc)| {
+                let (rr, cc) = (r as isize + dr, c as isize + dc);
+                if wrap {
+                    Some((rr.rem_euclid(rows), cc.rem_euclid(cols)))
+                } else {
+                    (rr >= 0 && rr < rows && cc >= 0 && cc < cols).then_some((rr, cc))
+                }
+            })
+            .filter(|(rr, cc)| m.get(*rr as usize, *cc as usize))
+            .count()
+    }
+
+    fn naive_step(m: &BitMatrix, birth: &[usize], survive: &[usize], nbrs: Neighbourhood, wrap: bool) -> BitMatrix
+    {
+        BitMatrix::from_fn(m.rows, m.cols, |r, c| {
+            let n = count(m, r, c, nbrs, wrap);
+            if m.get(r, c) { survive.contains(&n) } else { birth.contains(&n) }
+        })
+    }
+
+    #[test]
+    fn shifts()
+    {
+        let m = sample(5, 70, 1);
+        for fill in [Fill::Off, Fill::On, Fill::Wrap] {
+            let edge = |r, c| match fill { Fill::Off => false, Fill::On => true, Fill::Wrap => m.get(r, c) };
+
+            let up = m.shifted_up(fill);
+            assert!((0..70).all(|c| (0..4).all(|r| up.get(r, c) == m.get(r + 1, c)) && up.get(4, c) == edge(0, c)));
+            let down = m.shifted_down(fill);
+            assert!((0..70).all(|c| (1..5).all(|r| down.get(r, c) == m.get(r - 1, c)) && down.get(0, c) == edge(4, c)));
+            let left = m.shifted_left(fill);
+            assert!((0..5).all(|r| (0..69).all(|c| left.get(r, c) == m.get(r, c + 1)) && left.get(r, 69) == edge(r, 0)));
+            let right = m.shifted_right(fill);
+            assert!((0..5).all(|r| (1..70).all(|c| right.get(r, c) == m.get(r, c - 1)) && right.get(r, 0) == edge(r, 69)));
+            assert!(right.row(0)[1] >> 6 == 0 && up.row(4)[1] >> 6 == 0);
+        }
+    }
+
+    #[test]
+    fn padding()
+    {
+        let m = sample(3, 62, 2);
+        for fill in [false, true] {
+            let p = m.padded(3, fill);
+            assert_eq!((p.rows, p.cols), (9, 68));
+            for r in 0..9 {
+                for c in 0..68 {
+                    let inside = (3..6).contains(&r) && (3..65).contains(&c);
+                    assert_eq!(p.get(r, c), if inside { m.get(r - 3, c - 3) } else { fill });
+                }
+            }
+            assert_eq!(p.row(8)[1] >> 4, 0);
+        }
+    }
+
+    #[test]
+    fn counts()
+    {
+        let m = sample(9, 130, 3);
+        for nbrs in [Neighbourhood::Moore, Neighbourhood::VonNeumann] {
+            for (fill, wrap) in [(Fill::Off, false), (Fill::Wrap, true)] {
+                let sum = neighbour_counts(&m, nbrs, fill);
+                for (r, c) in (0..9).flat_map(|r| (0..130).map(move |c| (r, c))) {
+                    let n = (0..4).filter(|i| sum[*i].get(r, c)).fold(0, |n, i| n | 1 << i);
+                    assert_eq!(n, count(&m, r, c, nbrs, wrap));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rules()
+    {
+        // Conway's life, highlife and something that turns empty
+        // cells on, on both kinds of bounded grid.
+        let rules: [(&[usize], &[usize]); 3] = [(&[3], &[2, 3]), (&[3, 6], &[2, 3]), (&[0, 1], &[4])];
+        for (birth, survive) in rules {
+            for (nbrs, boundary, wrap) in [
+                (Neighbourhood::Moore, Boundary::Dead, false),
+                (Neighbourhood::Moore, Boundary::Wrap, true),
+                (Neighbourhood::VonNeumann, Boundary::Wrap, true),
+            ] {
+                let mut expected = sample(20, 67, 4);
+                let mut life = Life::new(expected.clone(), nbrs, boundary);
+                for _ in 0..10 {
+                    expected = naive_step(&expected, birth, survive, nbrs, wrap);
+                    life.step(birth, survive);
+                    assert_eq!(life.cells().words(), expected.words());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn blinker()
+    {
+        let mut life = Life::new(BitMatrix::from_iter(5, 5, [(2, 1), (2, 2), (2, 3)]), Neighbourhood::Moore, Boundary::Dead);
+        life.step(&[3], &[2, 3]);
+        assert_eq!(life.cells().words(), BitMatrix::from_iter(5, 5, [(1, 2), (2, 2), (3, 2)]).words());
+        life.step(&[3], &[2, 3]);
+        assert_eq!(life.count(), 3);
+        assert!(life.cells().get(2, 1) && life.cells().get(2, 3));
+    }
+
+    #[test]
+    fn glider_wraps()
+    {
+        // A glider gets back to where it started on a torus after
+        // 4 steps for every cell across.
+        let start = BitMatrix::from_iter(8, 8, [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+        let mut life = Life::new(start.clone(), Neighbourhood::Moore, Boundary::Wrap);
+        (0..32).for_each(|_| life.step(&[3], &[2, 3]));
+        assert_eq!(life.cells().words(), start.words());
+    }
+
+    #[test]
+    fn infinite()
+    {
+        // Everything empty comes on, everything full goes off, so the
+        // background flips every step. Otherwise it's plain life.
+        let mut table = [false; 512];
+        for (i, on) in table.iter_mut().enumerate() {
+            let n = (i & !0x10).count_ones();
+            *on = match i {
+                0 => true,
+                511 => false,
+                _ => n == 3 || (i & 0x10 != 0 && n == 2),
+            };
+        }
+
+        let mut life = Life::new(BitMatrix::from_iter(3, 3, [(1, 0), (1, 1), (1, 2)]), Neighbourhood::Moore, Boundary::Infinite);
+        life.step_lookup(&table);
+        assert!(life.background());
+        assert_eq!((life.cells().rows, life.cells().cols), (5, 5));
+        // The blinker turns, and any cell with nothing around it at all
+        // comes on.
+        assert!(life.cells().get(1, 2) && life.cells().get(2, 2) && life.cells().get(3, 2));
+        assert!(life.cells().get(0, 0) && life.cells().get(0, 2) && !life.cells().get(1, 0));
+
+        life.step_lookup(&table);
+        assert!(!life.background());
+        assert_eq!((life.cells().rows, life.cells().cols), (7, 7));
+    }
+
+    #[test]
+    fn lookup_matches_rules()
+    {
+        // Life written out as a table gives the same as the rule.
+        let mut table = [false; 512];
+        for (i, on) in table.iter_mut().enumerate() {
+            let n = (i & !0x10).count_ones();
+            *on = n == 3 || (i & 0x10 != 0 && n == 2);
+        }
+
+        let cells = sample(30, 90, 5);
+        let mut a = Life::new(cells.clone(), Neighbourhood::Moore, Boundary::Wrap);
+        let mut b = Life::new(cells, Neighbourhood::Moore, Boundary::Wrap);
+        for _ in 0..20 {
+            a.step(&[3], &[2, 3]);
+            b.step_lookup(&table);
+            assert_eq!(a.cells().words(), b.cells().words());
+        }
+    }
+}
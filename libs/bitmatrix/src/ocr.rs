@@ -0,0 +1,212 @@
+//! Reads the block letters puzzles draw with pixels. Handles the
+//! small 4x6 font (most years) and the large 6x10 one (2018 day 10).
+use crate::BitMatrix;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum OcrError {
+    /// Nothing's been drawn.
+    Empty,
+    /// The letters are taller than either font.
+    Height(usize),
+    /// The glyph starting at the column isn't a letter we know.
+    Unknown { col: usize, glyph: String },
+}
+
+/// Decodes the letters drawn in the matrix. Blank rows and columns
+/// around the text are ignored.
+pub fn read(m: &BitMatrix) -> Result<String, OcrError>
+{
+    let top = (0..m.rows)
+        .find(|r| m.row(*r).iter().any(|w| *w != 0))
+        .ok_or(OcrError::Empty)?;
+    let bottom = (0..m.rows)
+        .rfind(|r| m.row(*r).iter().any(|w| *w != 0))
+        .unwrap();
+
+    let (height, font): (usize, &[(char, &[&str])]) = match bottom - top + 1 {
+        n if n <= 6  => (6, &SMALL),
+        n if n <= 10 => (10, &LARGE),
+        n => return Err(OcrError::Height(n)),
+    };
+    let band = top..m.rows.min(top + height);
+
+    // Letters are runs of columns with something in them, but a wide
+    // letter like the small Y fills its cell and can touch the next
+    // one, so runs are split up by matching the widest glyph first.
+    let used = (0..m.cols)
+        .map(|c| band.clone().any(|r| m.get(r, c)))
+        .collect::<Vec<_>>();
+    let mut glyphs = font.iter()
+        .map(|(ch, rows)| (*ch, trim(rows)))
+        .collect::<Vec<_>>();
+    glyphs.sort_by_key(|(_, rows)| std::cmp::Reverse(rows[0].len()));
+
+    let mut text = String::new();
+    let mut c = 0;
+    while c < m.cols {
+        if !used[c] { c += 1; continue }
+
+        let end = (c..m.cols).find(|c| !used[*c]).unwrap_or(m.cols);
+        let run = (0..height)
+            .map(|r| (c..end)
+                .map(|c| if band.contains(&(top + r)) && m.get(top + r, c) { '#' } else { '.' })
+                .collect::<String>())
+            .collect::<Vec<_>>();
+
+        let mut start = 0;
+        while start < run[0].len() {
+            let (letter, width) = glyphs.iter()
+                .find(|(_, rows)| {
+                    let end = start + rows[0].len();
+                    end <= run[0].len() && rows.iter().zip(&run).all(|(g, r)| *g == r[start..end])
+                })
+                .map(|(ch, rows)| (*ch, rows[0].len()))
+                .ok_or_else(|| OcrError::Unknown {
+                    col: c + start,
+                    glyph: run.iter().map(|r| &r[start..]).collect::<Vec<_>>().join("\n"),
+                })?;
+            text.push(letter);
+            start += width;
+        }
+        c = end;
+    }
+
+    Ok(text)
+}
+
+/// Decodes the letters in any grid of pixels, `is_on` saying which
+/// are lit.
+pub fn read_fn<F>(rows: usize, cols: usize, is_on: F) -> Result<String, OcrError>
+    where F: FnMut(usize, usize) -> bool,
+{
+    read(&BitMatrix::from_fn(rows, cols, is_on))
+}
+
+// Drops the blank columns either side of a font glyph.
+fn trim(rows: &[&str]) -> Vec<String>
+{
+    let used = |c: usize| rows.iter().any(|r| r.as_bytes()[c] == b'#');
+    let width = rows[0].len();
+    let start = (0..width).find(|c| used(*c)).unwrap_or(0);
+    let end = (0..width).rfind(|c| used(*c)).map_or(0, |c| c + 1);
+
+    rows.iter().map(|r| r[start..end].to_string()).collect()
+}
+
+const SMALL: [(char, &[&str]); 18] = [
+    ('A', &[".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', &["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', &[".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', &["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', &["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', &[".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', &["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', &[".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', &["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', &["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', &["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', &[".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', &["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', &["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', &[".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', &["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', &["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', &["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+const LARGE: [(char, &[&str]); 15] = [
+    ('A', &["..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#"]),
+    ('B', &["#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#", "#....#", "#####."]),
+    ('C', &[".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#....#", ".####."]),
+    ('E', &["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('F', &["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('G', &[".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#", "#...##", ".###.#"]),
+    ('H', &["#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#", "#....#"]),
+    ('J', &["...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.", "#...#.", ".###.."]),
+    ('K', &["#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..", "#...#.", "#....#"]),
+    ('L', &["#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('N', &["#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##", "#...##", "#....#"]),
+    ('P', &["#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('R', &["#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.", "#....#", "#....#"]),
+    ('X', &["#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.", "#....#", "#....#"]),
+    ('Z', &["######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....", "#.....", "######"]),
+];
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Lays the letters out the way the puzzles do, `gap` blank
+    // columns after each one, with a blank border around the lot.
+    fn draw(text: &str, font: &[(char, &[&str])], gap: usize) -> BitMatrix
+    {
+        let glyphs = text.chars()
+            .map(|ch| font.iter().find(|(c, _)| *c == ch).unwrap().1)
+            .collect::<Vec<_>>();
+        let rows = glyphs[0].len();
+        let cols = glyphs.iter().map(|g| g[0].len() + gap).sum::<usize>();
+
+        let mut m = BitMatrix::new(rows + 2, cols + 2);
+        let mut left = 1;
+        for g in glyphs {
+            for (r, row) in g.iter().enumerate() {
+                row.bytes()
+                    .enumerate()
+                    .filter(|(_, b)| *b == b'#')
+                    .for_each(|(c, _)| m.set(r + 1, left + c));
+            }
+            left += g[0].len() + gap;
+        }
+
+        m
+    }
+
+    #[test]
+    fn small_font()
+    {
+        let text = SMALL.iter().map(|(c, _)| *c).collect::<String>();
+        assert_eq!(read(&draw(&text, &SMALL, 1)), Ok(text));
+        assert_eq!(read(&draw("PBZGRAZA", &SMALL, 1)), Ok("PBZGRAZA".into()));
+    }
+
+    #[test]
+    fn large_font()
+    {
+        let text = LARGE.iter().map(|(c, _)| *c).collect::<String>();
+        assert_eq!(read(&draw(&text, &LARGE, 2)), Ok(text));
+    }
+
+    #[test]
+    fn touching()
+    {
+        // The small Y fills its cell, so nothing separates it from the
+        // next letter, as at the end of 2016 day 8's answer.
+        assert_eq!(read(&draw("ZJHRKCPLYJ", &SMALL, 1)), Ok("ZJHRKCPLYJ".into()));
+        assert_eq!(read(&draw("YJ", &SMALL, 0)), Ok("YJ".into()));
+        assert_eq!(read(&draw("YYHLY", &SMALL, 0)), Ok("YYHLY".into()));
+        assert_eq!(read(&draw("HIJACK", &SMALL, 0)), Ok("HIJACK".into()));
+        assert_eq!(read(&draw("NZX", &LARGE, 0)), Ok("NZX".into()));
+    }
+
+    #[test]
+    fn grid()
+    {
+        let m = draw("HI", &SMALL, 1);
+        assert_eq!(read_fn(m.rows, m.cols, |r, c| m.get(r, c)), Ok("HI".into()));
+    }
+
+    #[test]
+    fn errors()
+    {
+        assert_eq!(read(&BitMatrix::new(6, 10)), Err(OcrError::Empty));
+        assert_eq!(read(&BitMatrix::from_iter(12, 2, [(0, 0), (11, 0)])), Err(OcrError::Height(12)));
+
+        let mut m = draw("AB", &SMALL, 1);
+        m.set(3, 9);
+        assert_eq!(
+            read(&m),
+            Err(OcrError::Unknown { col: 6, glyph: "###.\n#..#\n####\n#..#\n#..#\n###.".into() })
+        );
+    }
+}
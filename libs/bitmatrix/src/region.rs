@@ -0,0 +1,156 @@
+//! Regions of connected on cells and their measurements.
+use crate::{BitMatrix, Fill};
+
+impl BitMatrix {
+    /// Returns the region of on cells connected to `start`, with or
+    /// without counting diagonals, as a matrix of the same size. The
+    /// region's empty if `start` is off.
+    pub fn flood_fill(&self, start: (usize, usize), diag: bool) -> BitMatrix
+    {
+        let mut region = BitMatrix::new(self.rows, self.cols);
+        if !self.get(start.0, start.1) {
+            return region
+        }
+
+        region.set(start.0, start.1);
+        let mut stack = vec![start];
+        while let Some(p) = stack.pop() {
+            for (r, c) in self.neighbours(p, diag) {
+                if self.get(r, c) && !region.get(r, c) {
+                    region.set(r, c);
+                    stack.push((r, c));
+                }
+            }
+        }
+
+        region
+    }
+
+    /// Splits the on cells into connected regions, with or without
+    /// counting diagonals. Regions are numbered in the order their
+    /// first cell turns up, top to bottom and left to right.
+    pub fn components(&self, diag: bool) -> Vec<BitMatrix>
+    {
+        let stride = self.stride().max(1);
+        let mut left = self.clone();
+        let mut regions = Vec::new();
+
+        let mut i = 0;
+        while i < left.data.len() {
+            let w = left.data[i];
+            if w == 0 { i += 1; continue }
+
+            let start = (i / stride, (i % stride) * 64 + w.trailing_zeros() as usize);
+            let region = left.flood_fill(start, diag);
+            left ^= &region;
+            regions.push(region);
+        }
+
+        regions
+    }
+
+    /// Number of cells on.
+    pub fn area(&self) -> usize
+    {
+        self.count_ones()
+    }
+
+    /// Number of cell edges with an on cell one side and an off cell
+    /// (or the edge of the matrix) the other.
+    pub fn perimeter(&self) -> usize
+    {
+        let f = Fill::Off;
+        [self.shifted_up(f), self.shifted_down(f), self.shifted_left(f), self.shifted_right(f)]
+            .iter()
+            .map(|n| self.data.iter()
+                .zip(&n.data)
+                .map(|(w, n)| (w & !n).count_ones() as usize)
+                .sum::<usize>())
+            .sum()
+    }
+
+    /// Number of straight runs of fence it takes to go round the on
+    /// cells, holes included. That's the same as the number of corners,
+    /// which are counted for the four corners of every cell at once.
+    pub fn sides(&self) -> usize
+    {
+        let f = Fill::Off;
+        let (above, below) = (self.shifted_down(f), self.shifted_up(f));
+        let corners = [
+            (&above, self.shifted_right(f), above.shifted_right(f)),
+            (&above, self.shifted_left(f), above.shifted_left(f)),
+            (&below, self.shifted_right(f), below.shifted_right(f)),
+            (&below, self.shifted_left(f), below.shifted_left(f)),
+        ];
+
+        corners.iter()
+            .map(|(v, h, d)| (0..self.data.len())
+                .map(|i| {
+                    let (w, v, h, d) = (self.data[i], v.data[i], h.data[i], d.data[i]);
+                    // Sticking out, or tucked in with the diagonal missing.
+                    let outer = w & !v & !h;
+                    let inner = w & v & h & !d;
+                    (outer | inner).count_ones() as usize
+                })
+                .sum::<usize>())
+            .sum()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(s: &str) -> BitMatrix
+    {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn fill()
+    {
+        let m = grid("##..#\n#..##\n..#..\n.#...");
+        let r = m.flood_fill((0, 0), false);
+        assert_eq!(r.words(), grid("##...\n#....\n.....\n.....").words());
+
+        let r = m.flood_fill((0, 4), true);
+        assert_eq!(r.words(), grid("....#\n...##\n..#..\n.#...").words());
+        assert_eq!(m.flood_fill((0, 2), false).area(), 0);
+    }
+
+    #[test]
+    fn regions()
+    {
+        let m = grid("##..#\n#..##\n..#..\n.#...");
+        let regions = m.components(false);
+        assert_eq!(regions.len(), 4);
+        assert!(regions[0].get(0, 0) && regions[1].get(0, 4) && regions[3].get(3, 1));
+        assert_eq!(regions.iter().map(|r| r.area()).collect::<Vec<_>>(), [3, 3, 1, 1]);
+        assert_eq!(m.components(true).len(), 2);
+
+        // Regions across word boundaries.
+        let wide = BitMatrix::from_fn(3, 130, |r, c| r == 1 || c == 64 || c == 129);
+        assert_eq!(wide.components(false).len(), 1);
+        let split = BitMatrix::from_fn(3, 130, |r, c| r != 1 && c % 64 == 0);
+        assert_eq!(split.components(true).len(), 6);
+    }
+
+    #[test]
+    fn metrics()
+    {
+        // Areas, perimeters and sides from 2024 day 12's examples.
+        let m = grid("....\n..#.\n..##\n...#");
+        assert_eq!((m.area(), m.perimeter(), m.sides()), (4, 10, 8));
+
+        // An O with four holes in it.
+        let o = grid("#####\n#.#.#\n#####\n#.#.#\n#####");
+        assert_eq!((o.area(), o.perimeter(), o.sides()), (21, 36, 20));
+
+        // Two blocks touching at a corner count each other's corner.
+        let e = grid("######\n###..#\n###..#\n#..###\n#..###\n######");
+        assert_eq!((e.area(), e.sides()), (28, 12));
+        let b = grid("...##.\n...##.\n.##...\n.##...");
+        assert_eq!(b.components(false).iter().map(|r| r.sides()).sum::<usize>(), 8);
+    }
+}
@@ -0,0 +1,370 @@
+//! Unbounded set of points with signed coordinates in any number of
+//! dimensions, for when what's on wanders about or keeps growing.
+//! Points are kept in 64 bit chunks running along the last axis, so
+//! the set operators work a word at a time.
+use std::collections::HashMap;
+use std::ops::{
+    BitAnd,
+    BitAndAssign,
+    BitOr,
+    BitOrAssign,
+    BitXor,
+    BitXorAssign,
+};
+
+use crate::BitMatrix;
+
+pub type Point<const N: usize> = [i32; N];
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SparseBits<const N: usize> {
+    // Chunks with nothing on are dropped, so equal sets compare equal.
+    chunks: HashMap<Point<N>, u64>,
+}
+
+impl<const N: usize> SparseBits<N> {
+    pub fn new() -> Self
+    {
+        SparseBits { chunks: HashMap::new() }
+    }
+
+    pub fn get(&self, p: Point<N>) -> bool
+    {
+        let (key, mask) = chunk_pos(p);
+        self.chunks.get(&key).is_some_and(|w| w & mask != 0)
+    }
+
+    pub fn set(&mut self, p: Point<N>)
+    {
+        let (key, mask) = chunk_pos(p);
+        *self.chunks.entry(key).or_insert(0) |= mask
+    }
+
+    pub fn clear(&mut self, p: Point<N>)
+    {
+        let (key, mask) = chunk_pos(p);
+        if let Some(w) = self.chunks.get_mut(&key) {
+            *w &= !mask;
+            if *w == 0 { self.chunks.remove(&key); }
+        }
+    }
+
+    /// Sets or clears the point.
+    pub fn update(&mut self, p: Point<N>, val: bool)
+    {
+        if val { self.set(p) } else { self.clear(p) }
+    }
+
+    /// Number of points on.
+    pub fn len(&self) -> usize
+    {
+        self.chunks.values().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        self.chunks.is_empty()
+    }
+
+    /// The points which are on, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = Point<N>> + '_
+    {
+        self.chunks.iter().flat_map(|(key, w)| {
+            let mut w = *w;
+            std::iter::from_fn(move || (w != 0).then(|| {
+                let bit = w.trailing_zeros() as i32;
+                w &= w - 1;
+                let mut p = *key;
+                p[N - 1] = key[N - 1] * 64 + bit;
+                p
+            }))
+        })
+    }
+
+    /// Smallest and largest coordinates on each axis, or None if the
+    /// set's empty.
+    pub fn bounds(&self) -> Option<(Point<N>, Point<N>)>
+    {
+        self.chunks.iter().fold(None, |bounds, (key, w)| {
+            let mut lo = *key;
+            let mut hi = *key;
+            lo[N - 1] = key[N - 1] * 64 + w.trailing_zeros() as i32;
+            hi[N - 1] = key[N - 1] * 64 + 63 - w.leading_zeros() as i32;
+
+            Some(match bounds {
+                None => (lo, hi),
+                Some((a, b)) => (
+                    std::array::from_fn(|i| a[i].min(lo[i])),
+                    std::array::from_fn(|i| b[i].max(hi[i])),
+                ),
+            })
+        })
+    }
+
+    /// The 3^N - 1 points touching `p`, diagonals included.
+    pub fn moore(p: Point<N>) -> impl Iterator<Item = Point<N>>
+    {
+        (0..3usize.pow(N as u32))
+            .map(move |mut k| std::array::from_fn(|i| {
+                let d = (k % 3) as i32 - 1;
+                k /= 3;
+                p[i] + d
+            }))
+            .filter(move |q| *q != p)
+    }
+
+    /// Advances a Game of Life style automaton one generation. A point
+    /// which is off turns on if the number of its neighbours (as given
+    /// by `nbrs`) which are on is in `birth`, and a point which is on
+    /// stays on if the number is in `survive`.
+    pub fn step<F, I>(&self, birth: &[usize], survive: &[usize], nbrs: F) -> Self
+        where
+            F: Fn(Point<N>) -> I,
+            I: Iterator<Item = Point<N>>,
+    {
+        let mut counts = HashMap::new();
+        for p in self.iter() {
+            counts.entry(p).or_insert(0);
+            nbrs(p).for_each(|q| *counts.entry(q).or_insert(0) += 1);
+        }
+
+        counts.into_iter()
+            .filter(|(p, n)| if self.get(*p) { survive.contains(n) } else { birth.contains(n) })
+            .map(|(p, _)| p)
+            .collect()
+    }
+
+    // Combines chunk by chunk, keeping chunks which end up non-zero.
+    fn combine<F>(&self, rhs: &Self, union: bool, f: F) -> Self
+        where F: Fn(u64, u64) -> u64,
+    {
+        let mut chunks = HashMap::new();
+        for (key, w) in &self.chunks {
+            let v = f(*w, rhs.chunks.get(key).copied().unwrap_or(0));
+            if v != 0 { chunks.insert(*key, v); }
+        }
+        if union {
+            for (key, w) in &rhs.chunks {
+                if !self.chunks.contains_key(key) {
+                    chunks.insert(*key, f(0, *w));
+                }
+            }
+        }
+
+        SparseBits { chunks }
+    }
+}
+
+impl SparseBits<2> {
+    /// Axial hex grid neighbours of `p`: the six tiles round about
+    /// when moving east adds 1 to the first coordinate and moving
+    /// south east adds 1 to the second.
+    pub fn hex(p: Point<2>) -> impl Iterator<Item = Point<2>>
+    {
+        let [q, r] = p;
+        [[q + 1, r], [q - 1, r], [q, r - 1], [q + 1, r - 1], [q, r + 1], [q - 1, r + 1]].into_iter()
+    }
+
+    /// The points as a dense matrix just big enough to hold them, the
+    /// first coordinate picking the row and the second the column.
+    /// Also returns the point which ends up at (0, 0).
+    pub fn to_matrix(&self) -> (BitMatrix, Point<2>)
+    {
+        let Some((lo, hi)) = self.bounds() else {
+            return (BitMatrix::new(0, 0), [0, 0])
+        };
+
+        let rows = (hi[0] - lo[0] + 1) as usize;
+        let cols = (hi[1] - lo[1] + 1) as usize;
+        let m = BitMatrix::from_iter(rows, cols, self.iter()
+            .map(|[r, c]| ((r - lo[0]) as usize, (c - lo[1]) as usize)));
+
+        (m, lo)
+    }
+}
+
+impl From<&BitMatrix> for SparseBits<2> {
+    fn from(m: &BitMatrix) -> Self
+    {
+        m.items()
+            .filter(|(_, on)| *on)
+            .map(|((r, c), _)| [r as i32, c as i32])
+            .collect()
+    }
+}
+
+impl<const N: usize> FromIterator<Point<N>> for SparseBits<N> {
+    fn from_iter<I: IntoIterator<Item = Point<N>>>(iter: I) -> Self
+    {
+        let mut bits = SparseBits::new();
+        bits.extend(iter);
+        bits
+    }
+}
+
+impl<const N: usize> Extend<Point<N>> for SparseBits<N> {
+    fn extend<I: IntoIterator<Item = Point<N>>>(&mut self, iter: I)
+    {
+        iter.into_iter().for_each(|p| self.set(p))
+    }
+}
+
+// Key of the chunk holding the point and its bit in the chunk.
+fn chunk_pos<const N: usize>(p: Point<N>) -> (Point<N>, u64)
+{
+    let mut key = p;
+    key[N - 1] = p[N - 1].div_euclid(64);
+    (key, 1 << p[N - 1].rem_euclid(64))
+}
+
+impl<const N: usize> BitOr<&SparseBits<N>> for &SparseBits<N> {
+    type Output = SparseBits<N>;
+
+    fn bitor(self, rhs: &SparseBits<N>) -> SparseBits<N>
+    {
+        self.combine(rhs, true, |a, b| a | b)
+    }
+}
+
+impl<const N: usize> BitAnd<&SparseBits<N>> for &SparseBits<N> {
+    type Output = SparseBits<N>;
+
+    fn bitand(self, rhs: &SparseBits<N>) -> SparseBits<N>
+    {
+        self.combine(rhs, false, |a, b| a & b)
+    }
+}
+
+impl<const N: usize> BitXor<&SparseBits<N>> for &SparseBits<N> {
+    type Output = SparseBits<N>;
+
+    fn bitxor(self, rhs: &SparseBits<N>) -> SparseBits<N>
+    {
+        self.combine(rhs, true, |a, b| a ^ b)
+    }
+}
+
+impl<const N: usize> BitOrAssign<&SparseBits<N>> for SparseBits<N> {
+    fn bitor_assign(&mut self, rhs: &SparseBits<N>)
+    {
+        for (key, w) in &rhs.chunks {
+            *self.chunks.entry(*key).or_insert(0) |= w;
+        }
+    }
+}
+
+impl<const N: usize> BitAndAssign<&SparseBits<N>> for SparseBits<N> {
+    fn bitand_assign(&mut self, rhs: &SparseBits<N>)
+    {
+        self.chunks.retain(|key, w| {
+            *w &= rhs.chunks.get(key).copied().unwrap_or(0);
+            *w != 0
+        });
+    }
+}
+
+impl<const N: usize> BitXorAssign<&SparseBits<N>> for SparseBits<N> {
+    fn bitxor_assign(&mut self, rhs: &SparseBits<N>)
+    {
+        *self = &*self ^ rhs;
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_and_get()
+    {
+        let mut bits = SparseBits::<2>::new();
+        for p in [[0, 0], [-1, -1], [5, -64], [5, -65], [5, 63], [5, 64]] {
+            assert!(!bits.get(p));
+            bits.set(p);
+            assert!(bits.get(p));
+        }
+        assert_eq!(bits.len(), 6);
+        assert!(!bits.get([5, 0]) && !bits.get([-1, 63]));
+
+        bits.clear([5, 64]);
+        bits.update([-1, -1], false);
+        assert_eq!(bits.len(), 4);
+
+        let mut points = bits.iter().collect::<Vec<_>>();
+        points.sort();
+        assert_eq!(points, [[0, 0], [5, -65], [5, -64], [5, 63]]);
+        assert_eq!(bits.bounds(), Some(([0, -65], [5, 63])));
+    }
+
+    #[test]
+    fn emptied()
+    {
+        let mut bits = [[1, 2, 3], [1, 2, 4]].into_iter().collect::<SparseBits<3>>();
+        bits.clear([1, 2, 3]);
+        bits.clear([1, 2, 4]);
+        assert!(bits.is_empty());
+        assert_eq!(bits, SparseBits::new());
+        assert_eq!(bits.bounds(), None);
+    }
+
+    #[test]
+    fn operators()
+    {
+        let a = [[0, 0], [0, 1], [3, -100]].into_iter().collect::<SparseBits<2>>();
+        let b = [[0, 1], [3, -100], [7, 7]].into_iter().collect::<SparseBits<2>>();
+
+        let sorted = |s: SparseBits<2>| { let mut v = s.iter().collect::<Vec<_>>(); v.sort(); v };
+        assert_eq!(sorted(&a | &b), [[0, 0], [0, 1], [3, -100], [7, 7]]);
+        assert_eq!(sorted(&a & &b), [[0, 1], [3, -100]]);
+        assert_eq!(sorted(&a ^ &b), [[0, 0], [7, 7]]);
+
+        let mut c = a.clone();
+        c ^= &a;
+        assert!(c.is_empty());
+        c |= &b;
+        c &= &a;
+        assert_eq!(c, &a & &b);
+    }
+
+    #[test]
+    fn neighbours()
+    {
+        assert_eq!(SparseBits::<1>::moore([0]).collect::<Vec<_>>(), [[-1], [1]]);
+        assert_eq!(SparseBits::<2>::moore([5, 5]).count(), 8);
+        let n4 = SparseBits::<4>::moore([0, 0, 0, 0]).collect::<Vec<_>>();
+        assert_eq!(n4.len(), 80);
+        assert!(n4.iter().all(|p| p.iter().all(|d| d.abs() <= 1) && p.iter().any(|d| *d != 0)));
+
+        // Going round the six hex neighbours in order gets back home
+        // and each one's next to the centre.
+        let hex = SparseBits::hex([2, 3]).collect::<Vec<_>>();
+        assert_eq!(hex.len(), 6);
+        assert!(hex.iter().all(|p| SparseBits::hex(*p).any(|q| q == [2, 3])));
+    }
+
+    #[test]
+    fn life()
+    {
+        // A glider moves one down and one right every four steps.
+        let glider = [[0, 1], [1, 2], [2, 0], [2, 1], [2, 2]];
+        let mut bits = glider.into_iter().collect::<SparseBits<2>>();
+        for _ in 0..4 * 20 {
+            bits = bits.step(&[3], &[2, 3], SparseBits::moore);
+        }
+        assert_eq!(bits, glider.iter().map(|[r, c]| [r + 20, c + 20]).collect());
+    }
+
+    #[test]
+    fn dense()
+    {
+        let bits = [[-3, 70], [-1, 68], [-2, 69]].into_iter().collect::<SparseBits<2>>();
+        let (m, origin) = bits.to_matrix();
+        assert_eq!(origin, [-3, 68]);
+        assert_eq!((m.rows, m.cols), (3, 3));
+        assert_eq!(m.words(), BitMatrix::from_iter(3, 3, [(0, 2), (1, 1), (2, 0)]).words());
+
+        let back = SparseBits::from(&m);
+        assert_eq!(back, bits.iter().map(|[r, c]| [r + 3, c - 68]).collect());
+    }
+}
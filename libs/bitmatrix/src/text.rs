@@ -0,0 +1,152 @@
+//! Reading and writing matrices as text, the way the puzzles draw
+//! them: a line per row, one character per cell.
+use std::fmt;
+use std::str::FromStr;
+
+use crate::BitMatrix;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// A row isn't the same length as the first one.
+    Ragged { row: usize, len: usize, expected: usize },
+    /// A character which isn't either of the glyphs.
+    Glyph { row: usize, col: usize, ch: char },
+}
+
+/// Characters used for on and off cells.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Glyphs {
+    pub on: char,
+    pub off: char,
+}
+
+impl Glyphs {
+    pub const HASH: Glyphs = Glyphs { on: '#', off: '.' };
+    pub const BLOCK: Glyphs = Glyphs { on: '█', off: ' ' };
+}
+
+impl Default for Glyphs {
+    fn default() -> Self
+    {
+        Glyphs::HASH
+    }
+}
+
+impl BitMatrix {
+    /// Reads a matrix drawn with the glyphs, one line per row. Every
+    /// row has to be the same length and anything other than the two
+    /// glyphs is an error.
+    pub fn parse_with(s: &str, glyphs: Glyphs) -> Result<Self, ParseError>
+    {
+        let mut bad = None;
+        let m = BitMatrix::try_from_rows(
+            s.lines().enumerate().map(|(r, l)| l.chars().enumerate().map(move |(c, ch)| (r, c, ch))),
+            |&(row, col, ch)| {
+                if ch != glyphs.on && ch != glyphs.off && bad.is_none() {
+                    bad.replace(ParseError::Glyph { row, col, ch });
+                }
+                ch == glyphs.on
+            }
+        )?;
+
+        bad.map_or(Ok(m), Err)
+    }
+
+    /// Draws the matrix with the glyphs, one line per row with no
+    /// newline after the last.
+    pub fn render(&self, glyphs: Glyphs) -> String
+    {
+        self.iter()
+            .map(|row| row.map(|on| if on { glyphs.on } else { glyphs.off }).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Draws the matrix two rows to a line using half block characters,
+    /// so it comes out about square in a terminal.
+    pub fn render_half_blocks(&self) -> String
+    {
+        (0..self.rows)
+            .step_by(2)
+            .map(|r| (0..self.cols)
+                .map(|c| {
+                    let top = self.get(r, c);
+                    let bottom = r + 1 < self.rows && self.get(r + 1, c);
+                    match (top, bottom) {
+                        (true, true)   => '█',
+                        (true, false)  => '▀',
+                        (false, true)  => '▄',
+                        (false, false) => ' ',
+                    }
+                })
+                .collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl FromStr for BitMatrix {
+    type Err = ParseError;
+
+    /// Reads a matrix drawn with `#` and `.`.
+    fn from_str(s: &str) -> Result<Self, Self::Err>
+    {
+        BitMatrix::parse_with(s, Glyphs::HASH)
+    }
+}
+
+/// Draws the matrix with `#` and `.`, or with half blocks for the
+/// alternate form (`{:#}`).
+impl fmt::Display for BitMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    {
+        if f.alternate() {
+            f.write_str(&self.render_half_blocks())
+        } else {
+            f.write_str(&self.render(Glyphs::HASH))
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip()
+    {
+        let s = "#..#\n.##.\n....";
+        let m = s.parse::<BitMatrix>().unwrap();
+        assert_eq!((m.rows, m.cols), (3, 4));
+        assert_eq!(m.words(), BitMatrix::from_iter(3, 4, [(0, 0), (0, 3), (1, 1), (1, 2)]).words());
+        assert_eq!(m.to_string(), s);
+        assert_eq!("".parse::<BitMatrix>().unwrap().rows, 0);
+    }
+
+    #[test]
+    fn glyphs()
+    {
+        let glyphs = Glyphs { on: 'O', off: ' ' };
+        let m = BitMatrix::parse_with(" O \nOOO", glyphs).unwrap();
+        assert_eq!(m.render(Glyphs::HASH), ".#.\n###");
+        assert_eq!(m.render(glyphs), " O \nOOO");
+        assert_eq!(m.render(Glyphs::BLOCK), " █ \n███");
+    }
+
+    #[test]
+    fn half_blocks()
+    {
+        let m = "#.#.\n##..\n.#.#".parse::<BitMatrix>().unwrap();
+        assert_eq!(m.render_half_blocks(), "█▄▀ \n ▀ ▀");
+        assert_eq!(format!("{m:#}"), m.render_half_blocks());
+    }
+
+    #[test]
+    fn errors()
+    {
+        assert_eq!("##\n#\n##".parse::<BitMatrix>().err(), Some(ParseError::Ragged { row: 1, len: 1, expected: 2 }));
+        assert_eq!("##\n#.#".parse::<BitMatrix>().err(), Some(ParseError::Ragged { row: 1, len: 3, expected: 2 }));
+        assert_eq!("#.\n.x".parse::<BitMatrix>().err(), Some(ParseError::Glyph { row: 1, col: 1, ch: 'x' }));
+    }
+}